@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE "session" DROP COLUMN mfa;
+
+DROP TABLE IF EXISTS "user_mfa";
@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE "user_mfa" (
+    user_id UUID PRIMARY KEY,
+    totp_secret TEXT NOT NULL,
+    enabled_at TIMESTAMPTZ,
+    last_used_step BIGINT,
+    recovery_codes TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    FOREIGN KEY (user_id) REFERENCES "user" (id) ON DELETE CASCADE
+);
+
+ALTER TABLE "session" ADD COLUMN mfa BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+CREATE TABLE "audit_log" (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    action TEXT NOT NULL,
+    actor_id UUID,
+    target_id UUID,
+    ip TEXT,
+    user_agent TEXT,
+    details TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX audit_log_created_at_idx ON "audit_log" (created_at);
+CREATE INDEX audit_log_actor_id_idx ON "audit_log" (actor_id);
+CREATE INDEX audit_log_target_id_idx ON "audit_log" (target_id);
+
+-- Entries are never changed or removed, not even when their users are.
+CREATE FUNCTION audit_log_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON "audit_log"
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE "user" DROP CONSTRAINT IF EXISTS user_email_normalized_check;
@@ -0,0 +1,27 @@
+-- Add up migration script here
+-- Emails are compared trimmed and lowercased, so they are stored that way.
+-- Users whose emails only differ by case or spaces would then clash on the
+-- unique email, and which account to keep is not for a migration to decide.
+DO $$
+DECLARE
+    clashes TEXT;
+BEGIN
+    SELECT string_agg(emails, '; ')
+    INTO clashes
+    FROM (
+        SELECT string_agg(format('%L', email), ', ' ORDER BY email) AS emails
+        FROM "user"
+        GROUP BY lower(btrim(email))
+        HAVING count(*) > 1
+    ) AS duplicates;
+
+    IF clashes IS NOT NULL THEN
+        RAISE EXCEPTION 'users share an email once trimmed and lowercased, merge or rename them before migrating: %', clashes;
+    END IF;
+END
+$$;
+
+UPDATE "user" SET email = lower(btrim(email)) WHERE email <> lower(btrim(email));
+
+ALTER TABLE "user"
+    ADD CONSTRAINT user_email_normalized_check CHECK (email = lower(btrim(email)));
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE company DROP COLUMN IF EXISTS version;
+ALTER TABLE "user" DROP COLUMN IF EXISTS version;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- Bumped on every update, so that updates based on a stale read can be refused.
+ALTER TABLE "user" ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE company ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS company_deleted_at_idx;
+DROP INDEX IF EXISTS user_deleted_at_idx;
+ALTER TABLE company DROP COLUMN IF EXISTS deleted_at;
+ALTER TABLE "user" DROP COLUMN IF EXISTS deleted_at;
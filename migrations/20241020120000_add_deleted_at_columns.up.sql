@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- Soft deletion: a deleted row stays until purged, hidden from every query.
+ALTER TABLE "user" ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE company ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX user_deleted_at_idx ON "user" (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX company_deleted_at_idx ON company (deleted_at) WHERE deleted_at IS NOT NULL;
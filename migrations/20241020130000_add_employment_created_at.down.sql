@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS employment_company_created_at_idx;
+ALTER TABLE employment DROP COLUMN IF EXISTS created_at;
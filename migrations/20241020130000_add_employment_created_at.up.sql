@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Employments are listed by the time they started, newest first.
+ALTER TABLE employment ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE INDEX employment_company_created_at_idx ON employment (company_id, created_at DESC, id DESC);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS audit_log_no_truncate ON "audit_log";
@@ -0,0 +1,5 @@
+-- Add up migration script here
+-- Row triggers do not fire on TRUNCATE, which would empty the log at once.
+CREATE TRIGGER audit_log_no_truncate
+    BEFORE TRUNCATE ON "audit_log"
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS audit_log_created_at_id_idx;
+CREATE INDEX audit_log_created_at_idx ON "audit_log" (created_at);
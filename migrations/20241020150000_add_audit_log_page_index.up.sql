@@ -0,0 +1,4 @@
+-- Add up migration script here
+-- The audit log is paged newest first, resuming after the last entry seen.
+DROP INDEX IF EXISTS audit_log_created_at_idx;
+CREATE INDEX audit_log_created_at_id_idx ON "audit_log" (created_at DESC, id DESC);
@@ -0,0 +1,442 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::cache::{CacheCounters, CacheRepo};
+use crate::core::port::page::{Page, PageRequest};
+use crate::core::port::user::{UserFilter, UserRepo, UserSort};
+use crate::shared::data::base64::{b64u_decode, b64u_encode};
+
+/// Length of the key encrypting cached users.
+pub const KEY_LEN: usize = 32;
+
+fn id_key(id: UserId) -> String {
+    format!("user:id:{}", id)
+}
+
+fn email_key(email: &Email) -> String {
+    format!("user:email:{}", email)
+}
+
+/// A [`UserRepo`] that serves `find_by_id` and `find_by_email` from a cache
+/// for `ttl_sec` seconds, and forwards everything else to `inner`.
+///
+/// Users are cached under their id. The email key only holds the id, and a
+/// hit through it counts only while the user still has that email, so
+/// forgetting the id key on a write is enough to invalidate both lookups.
+/// A lookup racing a write, or a write on another instance when the cache is
+/// not shared, can leave the previous user cached until its entries expire.
+/// Cache failures are logged and fall back to `inner`.
+///
+/// Users carry their password hash and tokens, so they are cached encrypted
+/// with AES-256-GCM under `key`, bound to their id key. An entry that does
+/// not decrypt, e.g. one cached by an instance with another key, is a miss.
+pub struct CachedUserRepository<R> {
+    inner: Arc<R>,
+    cache: Arc<dyn CacheRepo>,
+    ttl_sec: u64,
+    key: LessSafeKey,
+    rng: SystemRandom,
+    counters: Arc<CacheCounters>,
+}
+
+/// Decodes the configured `cache.key`, or generates one when it is unset.
+pub fn cache_key(encoded: Option<&str>) -> Result<[u8; KEY_LEN], Error> {
+    let mut key = [0; KEY_LEN];
+    match encoded {
+        Some(encoded) => {
+            let decoded = b64u_decode(encoded)?;
+            if decoded.len() != KEY_LEN {
+                return Err(anyhow!("cache.key must be {} bytes", KEY_LEN));
+            }
+            key.copy_from_slice(&decoded);
+        }
+        None => SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| anyhow!("No cache key generated"))?,
+    }
+
+    Ok(key)
+}
+
+impl<R: UserRepo> CachedUserRepository<R> {
+    /// A `ttl_sec` of 0 disables the cache.
+    pub fn new(
+        inner: Arc<R>,
+        cache: Arc<dyn CacheRepo>,
+        ttl_sec: u64,
+        key: &[u8; KEY_LEN],
+    ) -> Self {
+        let key = UnboundKey::new(&AES_256_GCM, key).expect("AES-256 takes a 32 byte key");
+        Self {
+            inner,
+            cache,
+            ttl_sec,
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+            counters: Arc::new(CacheCounters::default()),
+        }
+    }
+
+    pub fn counters(&self) -> Arc<CacheCounters> {
+        Arc::clone(&self.counters)
+    }
+
+    fn enabled(&self) -> bool {
+        self.ttl_sec > 0
+    }
+
+    fn seal(&self, key: &str, user: &User) -> Result<String, Error> {
+        let mut nonce = [0; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("No nonce generated"))?;
+        let mut sealed = serde_json::to_vec(user)?;
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(key),
+                &mut sealed,
+            )
+            .map_err(|_| anyhow!("User not encrypted"))?;
+
+        Ok(b64u_encode([&nonce[..], &sealed].concat()))
+    }
+
+    fn open(&self, key: &str, entry: &str) -> Option<User> {
+        let mut entry = b64u_decode(entry).ok()?;
+        if entry.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, sealed) = entry.split_at_mut(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+        let json = self.key.open_in_place(nonce, Aad::from(key), sealed).ok()?;
+
+        serde_json::from_slice(json).ok()
+    }
+
+    async fn cached(&self, id: UserId) -> Result<Option<User>, Error> {
+        let key = id_key(id);
+        let user = match self.cache.get(&key).await? {
+            Some(entry) => self.open(&key, &entry),
+            None => None,
+        };
+
+        Ok(user)
+    }
+
+    async fn cached_by_email(&self, email: &Email) -> Result<Option<User>, Error> {
+        let id = match self.cache.get(&email_key(email)).await? {
+            Some(id) => id.parse::<UserId>().ok(),
+            None => None,
+        };
+        let user = match id {
+            Some(id) => self.cached(id).await?,
+            None => None,
+        };
+
+        Ok(user.filter(|user| &user.email == email))
+    }
+
+    async fn store(&self, user: &User) -> Result<(), Error> {
+        let Some(id) = user.id else {
+            return Ok(());
+        };
+        let key = id_key(id);
+        self.cache
+            .set(&key, self.seal(&key, user)?, self.ttl_sec)
+            .await?;
+        self.cache
+            .set(&email_key(&user.email), id.to_string(), self.ttl_sec)
+            .await
+    }
+
+    /// Counts a hit or a miss, loading and caching the user on a miss.
+    async fn lookup(
+        &self,
+        cached: impl Future<Output = Result<Option<User>, Error>>,
+        load: impl Future<Output = Result<Option<User>, Error>>,
+    ) -> Result<Option<User>, Error> {
+        if !self.enabled() {
+            return load.await;
+        }
+
+        match cached.await {
+            Ok(Some(user)) => {
+                self.counters.hit();
+                return Ok(Some(user));
+            }
+            Ok(None) => {}
+            Err(error) => tracing::warn!("User cache unavailable: {}", error),
+        }
+        self.counters.miss();
+
+        let user = load.await?;
+        if let Some(user) = &user {
+            if let Err(error) = self.store(user).await {
+                tracing::warn!("User not cached: {}", error);
+            }
+        }
+
+        Ok(user)
+    }
+
+    async fn forget(&self, id: UserId) {
+        if !self.enabled() {
+            return;
+        }
+        if let Err(error) = self.cache.remove(&id_key(id)).await {
+            tracing::error!("Cached user {} not invalidated: {}", id, error);
+        }
+    }
+}
+
+#[async_trait]
+impl<R: UserRepo> UserRepo for CachedUserRepository<R> {
+    async fn save(&self, entity: &User) -> Result<User, Error> {
+        self.inner.save(entity).await
+    }
+
+    async fn update(&self, id: UserId, entity: &User) -> Result<User, Error> {
+        let updated = self.inner.update(id, entity).await;
+        self.forget(id).await;
+
+        updated
+    }
+
+    async fn delete(&self, id: UserId) -> Result<(), Error> {
+        let deleted = self.inner.delete(id).await;
+        self.forget(id).await;
+
+        deleted
+    }
+
+    async fn restore(&self, id: UserId) -> Result<Option<User>, Error> {
+        let restored = self.inner.restore(id).await;
+        self.forget(id).await;
+
+        restored
+    }
+
+    async fn purge_deleted(&self, before: &Timestamp) -> Result<u64, Error> {
+        // Deleted users were forgotten when they were deleted.
+        self.inner.purge_deleted(before).await
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, Error> {
+        self.inner.find_all().await
+    }
+
+    async fn find_by_id(&self, id: UserId) -> Result<Option<User>, Error> {
+        self.lookup(self.cached(id), self.inner.find_by_id(id))
+            .await
+    }
+
+    async fn find_by_email(&self, email: &Email) -> Result<Option<User>, Error> {
+        self.lookup(self.cached_by_email(email), self.inner.find_by_email(email))
+            .await
+    }
+
+    async fn find_by_filter(
+        &self,
+        filter: &UserFilter,
+        page: &PageRequest<UserSort>,
+    ) -> Result<Page<User>, Error> {
+        self.inner.find_by_filter(filter, page).await
+    }
+
+    async fn count_by_filter(&self, filter: &UserFilter) -> Result<i64, Error> {
+        self.inner.count_by_filter(filter).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::cache::CacheRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+    use crate::core::domain::valueobject::password::HashedPassword;
+    use crate::core::domain::valueobject::role::Role;
+    use crate::core::port::cache::CacheStats;
+
+    fn repository(ttl_sec: u64) -> CachedUserRepository<UserRepository> {
+        repository_with(Arc::new(CacheRepository::new()), ttl_sec)
+    }
+
+    fn repository_with(
+        cache: Arc<dyn CacheRepo>,
+        ttl_sec: u64,
+    ) -> CachedUserRepository<UserRepository> {
+        CachedUserRepository::new(
+            Arc::new(UserRepository::new(Arc::new(MemStore::new()))),
+            cache,
+            ttl_sec,
+            &cache_key(None).unwrap(),
+        )
+    }
+
+    fn email(email: &str) -> Email {
+        Email::parse(email).unwrap()
+    }
+
+    fn user(address: &str) -> User {
+        User {
+            id: None,
+            name: "John".to_string(),
+            surname: "Doe".to_string(),
+            email: email(address),
+            role: Role::USER,
+            password_hash: HashedPassword::from("hash".to_string()),
+            reset_token: None,
+            reset_sent_at: None,
+            email_verification_token: None,
+            email_verification_sent_at: None,
+            email_verified_at: None,
+            blocked_at: None,
+            created_at: Timestamp::now_utc(),
+            updated_at: Timestamp::now_utc(),
+            version: 1,
+            deleted_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn serves_repeated_lookups_until_the_user_changes() {
+        let repository = repository(60);
+        let saved = repository.save(&user("john@example.com")).await.unwrap();
+        let id = saved.id.unwrap();
+
+        assert_eq!(
+            repository.find_by_id(id).await.unwrap(),
+            Some(saved.clone())
+        );
+        assert_eq!(
+            repository
+                .find_by_email(&email("john@example.com"))
+                .await
+                .unwrap(),
+            Some(saved.clone())
+        );
+        assert_eq!(
+            repository.counters().stats(),
+            CacheStats { hits: 1, misses: 1 }
+        );
+
+        let updated = repository
+            .update(
+                id,
+                &User {
+                    email: email("jane@example.com"),
+                    ..saved
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            repository
+                .find_by_email(&email("john@example.com"))
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(repository.find_by_id(id).await.unwrap(), Some(updated));
+        assert_eq!(
+            repository.counters().stats(),
+            CacheStats { hits: 1, misses: 3 }
+        );
+
+        repository.delete(id).await.unwrap();
+        assert_eq!(repository.find_by_id(id).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn caches_users_encrypted() {
+        let cache: Arc<dyn CacheRepo> = Arc::new(CacheRepository::new());
+        let repository = repository_with(Arc::clone(&cache), 60);
+        let saved = repository.save(&user("john@example.com")).await.unwrap();
+        let id = saved.id.unwrap();
+        let saved = repository
+            .update(
+                id,
+                &User {
+                    reset_token: Some("reset-token".to_string()),
+                    ..saved
+                },
+            )
+            .await
+            .unwrap();
+        repository.find_by_id(id).await.unwrap();
+
+        let entry = cache.get(&id_key(id)).await.unwrap().unwrap();
+        for secret in ["hash", "reset-token", "john@example.com"] {
+            assert!(!entry.contains(secret));
+        }
+        assert_eq!(repository.find_by_id(id).await.unwrap(), Some(saved));
+
+        let other_key = repository_with(Arc::clone(&cache), 60);
+        assert_eq!(other_key.cached(id).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn blocking_and_unblocking_invalidate_the_cached_user() {
+        let repository = repository(60);
+        let saved = repository.save(&user("john@example.com")).await.unwrap();
+        let id = saved.id.unwrap();
+        assert!(!repository
+            .find_by_id(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_blocked());
+
+        let mut blocked = repository.find_by_id(id).await.unwrap().unwrap();
+        blocked.block();
+        repository.update(id, &blocked).await.unwrap();
+        assert!(repository
+            .find_by_id(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_blocked());
+        assert!(repository
+            .find_by_email(&email("john@example.com"))
+            .await
+            .unwrap()
+            .unwrap()
+            .is_blocked());
+
+        let mut unblocked = repository.find_by_id(id).await.unwrap().unwrap();
+        unblocked.unblock();
+        repository.update(id, &unblocked).await.unwrap();
+        assert!(!repository
+            .find_by_id(id)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_blocked());
+        assert_eq!(
+            repository.counters().stats(),
+            CacheStats { hits: 3, misses: 3 }
+        );
+    }
+
+    #[tokio::test]
+    async fn a_ttl_of_zero_disables_the_cache() {
+        let repository = repository(0);
+
+        assert_eq!(
+            repository.find_by_id(UserId::generate()).await.unwrap(),
+            None
+        );
+        assert_eq!(repository.counters().stats(), CacheStats::default());
+    }
+}
@@ -0,0 +1,956 @@
+//! Behaviour every storage backend must share, checked against each of them.
+//! A repository added for a new backend should be run through these too.
+
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::entity::company::Company;
+use crate::core::domain::entity::mfa::{UsedCode, UserMfa};
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::{CompanyId, UserId};
+use crate::core::domain::valueobject::password::HashedPassword;
+use crate::core::domain::valueobject::position::Position;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::domain::valueobject::sector::Sector;
+use crate::core::port::audit::{AuditFilter, AuditRepo, AuditSort};
+use crate::core::port::company::{CompanyRepo, CompanySort};
+use crate::core::port::employment::{EmploymentRepo, EmploymentSort};
+use crate::core::port::error::{AlreadyTaken, VersionConflict};
+use crate::core::port::mfa::MfaRepo;
+use crate::core::port::page::{PageRequest, SortKey};
+use crate::core::port::unit_of_work::UnitOfWorkFactory;
+use crate::core::port::user::{UserFilter, UserRepo, UserSort};
+
+fn user(email: &str) -> User {
+    User {
+        id: None,
+        name: "John".to_string(),
+        surname: "Doe".to_string(),
+        email: Email::parse(email).unwrap(),
+        role: Role::USER,
+        password_hash: HashedPassword::from("hash".to_string()),
+        reset_token: None,
+        reset_sent_at: None,
+        email_verification_token: None,
+        email_verification_sent_at: None,
+        email_verified_at: None,
+        blocked_at: None,
+        created_at: Timestamp::now_utc(),
+        updated_at: Timestamp::now_utc(),
+        version: 1,
+        deleted_at: None,
+    }
+}
+
+fn company(name: &str, url: &str) -> Company {
+    Company::new(
+        name.to_string(),
+        2020,
+        "Makes things".to_string(),
+        url.to_string(),
+        Sector::Software,
+    )
+}
+
+/// A timestamp with microseconds, which every backend stores exactly.
+fn sub_second() -> Timestamp {
+    Timestamp::new(
+        Utc.with_ymd_and_hms(2024, 10, 19, 12, 30, 45).unwrap() + Duration::microseconds(123_456),
+    )
+}
+
+fn first_page<S: SortKey>(limit: i64, sort: S) -> PageRequest<S> {
+    PageRequest::new(Some(limit), None, sort).unwrap()
+}
+
+fn emails(users: Vec<User>) -> Vec<String> {
+    let mut emails: Vec<String> = users.into_iter().map(|user| user.email.into()).collect();
+    emails.sort();
+    emails
+}
+
+pub async fn user_repo_contract<R: UserRepo>(repository: &R) {
+    // Saving assigns an id and clears the token and status fields.
+    let saved = repository
+        .save(&User {
+            reset_token: Some("token".to_string()),
+            blocked_at: Some(Timestamp::now_utc()),
+            ..user("john@example.com")
+        })
+        .await
+        .unwrap();
+    let id = saved.id.expect("saved users have an id");
+    assert_eq!(saved.reset_token, None);
+    assert_eq!(saved.blocked_at, None);
+    assert_eq!(
+        repository.find_by_id(id).await.unwrap(),
+        Some(saved.clone())
+    );
+    assert_eq!(
+        repository
+            .find_by_email(&Email::parse("John@Example.com").unwrap())
+            .await
+            .unwrap(),
+        Some(saved.clone())
+    );
+    assert_eq!(
+        repository
+            .find_by_email(&Email::parse("nobody@example.com").unwrap())
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Emails are unique, on save and on update.
+    let duplicate = repository
+        .save(&user("john@example.com"))
+        .await
+        .unwrap_err();
+    assert!(duplicate.is::<AlreadyTaken>());
+    let jane = repository.save(&user("jane@example.com")).await.unwrap();
+    let jane_id = jane.id.unwrap();
+    let taken = User {
+        email: saved.email.clone(),
+        ..jane.clone()
+    };
+    let duplicate = repository.update(jane_id, &taken).await.unwrap_err();
+    assert!(duplicate.is::<AlreadyTaken>());
+
+    // Updates overwrite the given fields, keep the tokens and timestamps that
+    // are `None`, except `blocked_at`, and round-trip timestamps.
+    let updated = repository
+        .update(
+            id,
+            &User {
+                name: "Johnny".to_string(),
+                email_verified_at: Some(sub_second()),
+                blocked_at: Some(sub_second()),
+                ..saved.clone()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.name, "Johnny");
+    assert_eq!(updated.email_verified_at, Some(sub_second()));
+    assert_eq!(updated.blocked_at, Some(sub_second()));
+    assert_eq!(updated.created_at, saved.created_at);
+    assert!(updated.updated_at.datetime >= saved.updated_at.datetime);
+    let kept = repository
+        .update(
+            id,
+            &User {
+                email_verified_at: None,
+                ..updated.clone()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(kept.email_verified_at, Some(sub_second()));
+    assert_eq!(kept.blocked_at, Some(sub_second()));
+    assert_eq!(repository.find_by_id(id).await.unwrap(), Some(kept.clone()));
+
+    // Every update bumps the version, and updates of a stale read conflict.
+    assert_eq!(saved.version, 1);
+    assert_eq!(kept.version, 3);
+    let stale = repository.update(id, &saved).await.unwrap_err();
+    assert!(stale.is::<VersionConflict>());
+    assert_eq!(repository.find_by_id(id).await.unwrap(), Some(kept));
+    let missing = repository
+        .update(UserId::generate(), &saved)
+        .await
+        .unwrap_err();
+    assert!(!missing.is::<VersionConflict>());
+
+    // Filters and counts agree.
+    let blocked = UserFilter {
+        blocked: Some(true),
+        ..UserFilter::default()
+    };
+    assert_eq!(
+        emails(
+            repository
+                .find_by_filter(&blocked, &first_page(10, UserSort::CreatedAt))
+                .await
+                .unwrap()
+                .items
+        ),
+        ["john@example.com"]
+    );
+    assert_eq!(repository.count_by_filter(&blocked).await.unwrap(), 1);
+    // Unblocking clears `blocked_at`.
+    let blocked_jane = repository
+        .update(
+            jane_id,
+            &User {
+                blocked_at: Some(sub_second()),
+                ..jane.clone()
+            },
+        )
+        .await
+        .unwrap();
+    let unblocked = repository
+        .update(
+            jane_id,
+            &User {
+                blocked_at: None,
+                ..blocked_jane
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(unblocked.blocked_at, None);
+    assert_eq!(repository.count_by_filter(&blocked).await.unwrap(), 1);
+    let everyone = UserFilter::default();
+    assert_eq!(
+        emails(
+            repository
+                .find_by_filter(&everyone, &first_page(10, UserSort::CreatedAt))
+                .await
+                .unwrap()
+                .items
+        ),
+        ["jane@example.com", "john@example.com"]
+    );
+
+    // Pages resume after the cursor of the previous one, in either order.
+    let by_email = first_page(1, UserSort::Email);
+    let first = repository
+        .find_by_filter(&everyone, &by_email)
+        .await
+        .unwrap();
+    assert_eq!(emails(first.items), ["jane@example.com"]);
+    let second = repository
+        .find_by_filter(
+            &everyone,
+            &PageRequest {
+                cursor: first.next_cursor,
+                ..by_email
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(emails(second.items), ["john@example.com"]);
+    assert_eq!(second.next_cursor, None);
+    let newest = first_page(1, UserSort::CreatedAt);
+    let first = repository.find_by_filter(&everyone, &newest).await.unwrap();
+    let second = repository
+        .find_by_filter(
+            &everyone,
+            &PageRequest {
+                cursor: first.next_cursor,
+                ..newest
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(second.next_cursor, None);
+    assert_eq!(
+        emails(first.items.into_iter().chain(second.items).collect()),
+        ["jane@example.com", "john@example.com"]
+    );
+    assert_eq!(emails(repository.find_all().await.unwrap()).len(), 2);
+
+    // Deleting hides the user everywhere and is idempotent. Its email stays
+    // taken until it is purged.
+    let deleted = repository.find_by_id(id).await.unwrap().unwrap();
+    repository.delete(id).await.unwrap();
+    repository.delete(id).await.unwrap();
+    assert_eq!(repository.find_by_id(id).await.unwrap(), None);
+    assert_eq!(
+        repository.find_by_email(&deleted.email).await.unwrap(),
+        None
+    );
+    assert_eq!(
+        emails(
+            repository
+                .find_by_filter(&everyone, &first_page(10, UserSort::CreatedAt))
+                .await
+                .unwrap()
+                .items
+        ),
+        ["jane@example.com"]
+    );
+    assert_eq!(repository.count_by_filter(&everyone).await.unwrap(), 1);
+    assert_eq!(repository.find_all().await.unwrap().len(), 1);
+    let gone = repository.update(id, &deleted).await.unwrap_err();
+    assert!(!gone.is::<VersionConflict>());
+    let taken = repository
+        .save(&user("john@example.com"))
+        .await
+        .unwrap_err();
+    assert!(taken.is::<AlreadyTaken>());
+
+    // Restoring brings the user back as it was, once.
+    let restored = repository
+        .restore(id)
+        .await
+        .unwrap()
+        .expect("deleted users are restored");
+    assert_eq!(restored.deleted_at, None);
+    assert_eq!(restored.email, deleted.email);
+    assert_eq!(restored.version, deleted.version + 1);
+    assert_eq!(repository.restore(id).await.unwrap(), None);
+    assert_eq!(repository.restore(jane_id).await.unwrap(), None);
+    assert_eq!(repository.find_by_id(id).await.unwrap(), Some(restored));
+
+    // Purging removes the users deleted before the cutoff, and only those.
+    repository.delete(id).await.unwrap();
+    assert_eq!(
+        repository.purge_deleted(&saved.created_at).await.unwrap(),
+        0
+    );
+    let later = Timestamp::now_utc() + 1_000;
+    assert_eq!(repository.purge_deleted(&later).await.unwrap(), 1);
+    assert_eq!(repository.restore(id).await.unwrap(), None);
+    assert!(repository.find_by_id(jane_id).await.unwrap().is_some());
+    assert!(repository.save(&user("john@example.com")).await.is_ok());
+
+    // Emails sort by bytes, whatever the database collation: punctuation
+    // is not skipped.
+    for email in ["ab@example.com", "a_d@example.com", "a.c@example.com"] {
+        repository.save(&user(email)).await.unwrap();
+    }
+    let mut page = first_page(2, UserSort::Email);
+    let mut ordered: Vec<String> = vec![];
+    loop {
+        let found = repository.find_by_filter(&everyone, &page).await.unwrap();
+        ordered.extend(found.items.into_iter().map(|user| user.email.into()));
+        match found.next_cursor {
+            Some(cursor) => page.cursor = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(
+        ordered,
+        [
+            "a.c@example.com",
+            "a_d@example.com",
+            "ab@example.com",
+            "jane@example.com",
+            "john@example.com"
+        ]
+    );
+}
+
+pub async fn company_repo_contract<R: CompanyRepo>(repository: &R) {
+    let acme = company("Acme", "https://acme.example");
+    let id = repository.save(&acme).await.unwrap();
+    assert_eq!(Some(id), acme.id);
+    let found = repository
+        .find_by_id(id)
+        .await
+        .unwrap()
+        .expect("saved companies are found");
+    assert_eq!(found.name, acme.name);
+    assert_eq!(found.url, acme.url);
+    assert_eq!(
+        repository.find_by_name("Acme").await.unwrap().unwrap().id,
+        Some(id)
+    );
+    assert!(repository.find_by_name("Nobody").await.unwrap().is_none());
+
+    // Names and URLs are unique, on save and on update.
+    let name_taken = AlreadyTaken::new("company", "name");
+    let url_taken = AlreadyTaken::new("company", "url");
+    let duplicate = repository
+        .save(&company("Acme", "https://new.example"))
+        .await
+        .unwrap_err();
+    assert_eq!(duplicate.downcast_ref::<AlreadyTaken>(), Some(&name_taken));
+    let duplicate = repository
+        .save(&company("New", "https://acme.example"))
+        .await
+        .unwrap_err();
+    assert_eq!(duplicate.downcast_ref::<AlreadyTaken>(), Some(&url_taken));
+    let other = repository
+        .save(&company("Other", "https://other.example"))
+        .await
+        .unwrap();
+    let taken = company("Acme", "https://other.example");
+    let duplicate = repository.update(other, &taken).await.unwrap_err();
+    assert_eq!(duplicate.downcast_ref::<AlreadyTaken>(), Some(&name_taken));
+
+    // Updates overwrite every field and round-trip timestamps.
+    let updated = repository
+        .update(
+            id,
+            &Company {
+                description: "Makes more things".to_string(),
+                ..found.clone()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.description, "Makes more things");
+    assert_eq!(updated.created_at, found.created_at);
+    let refound = repository.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(refound.updated_at, updated.updated_at);
+    assert_eq!((found.version, refound.version), (1, 2));
+    let stale = repository.update(id, &found).await.unwrap_err();
+    assert!(stale.is::<VersionConflict>());
+    let missing = repository
+        .update(CompanyId::generate(), &found)
+        .await
+        .unwrap_err();
+    assert!(!missing.is::<VersionConflict>());
+    assert_eq!(repository.find_all().await.unwrap().len(), 2);
+
+    // Companies saved without an id get one.
+    let unnamed = Company {
+        id: None,
+        ..company("Unnamed", "https://unnamed.example")
+    };
+    let assigned = repository.save(&unnamed).await.unwrap();
+    assert!(repository.find_by_id(assigned).await.unwrap().is_some());
+
+    // Pages resume after the cursor of the previous one.
+    let by_name = first_page(2, CompanySort::Name);
+    let first = repository.find_page(&by_name).await.unwrap();
+    let second = repository
+        .find_page(&PageRequest {
+            cursor: first.next_cursor,
+            ..by_name
+        })
+        .await
+        .unwrap();
+    let names: Vec<String> = first
+        .items
+        .into_iter()
+        .chain(second.items)
+        .map(|company| company.name)
+        .collect();
+    assert_eq!(names, ["Acme", "Other", "Unnamed"]);
+    assert_eq!(second.next_cursor, None);
+    let newest = repository
+        .find_page(&first_page(10, CompanySort::CreatedAt))
+        .await
+        .unwrap();
+    assert_eq!(newest.items.len(), 3);
+
+    // Deleting hides the company everywhere and is idempotent. Its name
+    // stays taken until it is purged.
+    let deleted = repository.find_by_id(id).await.unwrap().unwrap();
+    repository.delete(id).await.unwrap();
+    repository.delete(id).await.unwrap();
+    assert!(repository.find_by_id(id).await.unwrap().is_none());
+    assert!(repository
+        .find_by_name(&deleted.name)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(repository.find_all().await.unwrap().len(), 2);
+    let gone = repository.update(id, &deleted).await.unwrap_err();
+    assert!(!gone.is::<VersionConflict>());
+    let duplicate = repository
+        .save(&company("Acme", "https://acme.example"))
+        .await
+        .unwrap_err();
+    assert_eq!(duplicate.downcast_ref::<AlreadyTaken>(), Some(&name_taken));
+
+    // Restoring brings the company back, once.
+    let restored = repository
+        .restore(id)
+        .await
+        .unwrap()
+        .expect("deleted companies are restored");
+    assert!(restored.deleted_at.is_none());
+    assert_eq!(restored.name, deleted.name);
+    assert_eq!(restored.version, deleted.version + 1);
+    assert!(repository.restore(id).await.unwrap().is_none());
+    assert!(repository.restore(other).await.unwrap().is_none());
+    assert!(repository.find_by_id(id).await.unwrap().is_some());
+
+    // Purging removes the companies deleted before the cutoff, and only
+    // those.
+    repository.delete(id).await.unwrap();
+    assert_eq!(
+        repository.purge_deleted(&found.created_at).await.unwrap(),
+        0
+    );
+    let later = Timestamp::now_utc() + 1_000;
+    assert_eq!(repository.purge_deleted(&later).await.unwrap(), 1);
+    assert!(repository.restore(id).await.unwrap().is_none());
+    assert!(repository.find_by_id(other).await.unwrap().is_some());
+    assert!(repository
+        .save(&company("Acme", "https://acme.example"))
+        .await
+        .is_ok());
+
+    // Names sort by bytes, whatever the database collation: capitals first
+    // and punctuation is not skipped.
+    for (name, url) in [
+        ("ab", "https://ab.example"),
+        ("a_d", "https://a-d.example"),
+        ("a.c", "https://a-c.example"),
+        ("Zed", "https://zed.example"),
+    ] {
+        repository.save(&company(name, url)).await.unwrap();
+    }
+    let mut page = first_page(2, CompanySort::Name);
+    let mut names: Vec<String> = vec![];
+    loop {
+        let found = repository.find_page(&page).await.unwrap();
+        names.extend(found.items.into_iter().map(|company| company.name));
+        match found.next_cursor {
+            Some(cursor) => page.cursor = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(
+        names,
+        ["Acme", "Other", "Unnamed", "Zed", "a.c", "a_d", "ab"]
+    );
+}
+
+/// Employments reference a user and a company. They are hidden while either
+/// is deleted, and purged with either.
+pub async fn employment_repo_contract<U, C, E>(users: &U, companies: &C, employments: &E)
+where
+    U: UserRepo,
+    C: CompanyRepo,
+    E: EmploymentRepo,
+{
+    let user_id = users
+        .save(&user("ceo@example.com"))
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    let company_id = companies
+        .save(&company("Acme", "https://acme.example"))
+        .await
+        .unwrap();
+
+    assert!(employments
+        .save(UserId::generate(), company_id, &Position::CEO)
+        .await
+        .is_err());
+    assert!(employments
+        .save(user_id, CompanyId::generate(), &Position::CEO)
+        .await
+        .is_err());
+    employments
+        .save(user_id, company_id, &Position::CEO)
+        .await
+        .unwrap();
+    assert_eq!(
+        employments
+            .find_position(user_id, company_id)
+            .await
+            .unwrap(),
+        Some(Position::CEO)
+    );
+
+    companies.delete(company_id).await.unwrap();
+    assert_eq!(
+        employments
+            .find_position(user_id, company_id)
+            .await
+            .unwrap(),
+        None
+    );
+    companies.restore(company_id).await.unwrap();
+    assert_eq!(
+        employments
+            .find_position(user_id, company_id)
+            .await
+            .unwrap(),
+        Some(Position::CEO)
+    );
+
+    let other_id = companies
+        .save(&company("Other", "https://other.example"))
+        .await
+        .unwrap();
+    employments
+        .save(user_id, other_id, &Position::Manager)
+        .await
+        .unwrap();
+    users.delete(user_id).await.unwrap();
+    assert_eq!(
+        employments.find_position(user_id, other_id).await.unwrap(),
+        None
+    );
+    users.restore(user_id).await.unwrap();
+    assert_eq!(
+        employments.find_position(user_id, other_id).await.unwrap(),
+        Some(Position::Manager)
+    );
+
+    // Employees are listed by company, the most recently hired first.
+    let hired = users
+        .save(&user("hired@example.com"))
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    employments
+        .save(hired, other_id, &Position::WhiteCollar)
+        .await
+        .unwrap();
+    let newest = first_page(1, EmploymentSort::CreatedAt);
+    let first = employments
+        .find_by_company(other_id, &newest)
+        .await
+        .unwrap();
+    assert_eq!(first.items[0].user_id, hired);
+    let second = employments
+        .find_by_company(
+            other_id,
+            &PageRequest {
+                cursor: first.next_cursor,
+                ..newest
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(second.items[0].user_id, user_id);
+    assert_eq!(second.items[0].position, Position::Manager);
+    assert_eq!(second.next_cursor, None);
+
+    // Purging takes the employments along: a user saved under the same id
+    // is not employed anywhere.
+    users.delete(user_id).await.unwrap();
+    let later = Timestamp::now_utc() + 1_000;
+    users.purge_deleted(&later).await.unwrap();
+    users
+        .save(&User {
+            id: Some(user_id),
+            ..user("ceo@example.com")
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        employments.find_position(user_id, other_id).await.unwrap(),
+        None
+    );
+    companies.delete(company_id).await.unwrap();
+    companies.purge_deleted(&later).await.unwrap();
+    assert!(companies.find_by_id(company_id).await.unwrap().is_none());
+}
+
+/// Entries are paged newest first, ties broken by id, and a cursor resumes
+/// right after the last entry of its page.
+pub async fn audit_repo_contract<R: AuditRepo>(repository: &R) {
+    let at = |minutes: i64| Timestamp::new(sub_second().datetime - Duration::minutes(minutes));
+    let mut entries = Vec::new();
+    for minutes in [0, 1, 1, 1, 2] {
+        let entry = AuditEntry {
+            created_at: at(minutes),
+            ..AuditEntry::new(AuditAction::Logout, None, None)
+        };
+        repository.append(&entry).await.unwrap();
+        entries.push(entry);
+    }
+    let failed = AuditEntry {
+        created_at: at(1),
+        ..AuditEntry::new(AuditAction::LoginFailed, None, None)
+    };
+    repository.append(&failed).await.unwrap();
+    entries.sort_by_key(|entry| std::cmp::Reverse((entry.created_at.datetime, entry.id)));
+
+    let logouts = AuditFilter {
+        action: Some(AuditAction::Logout),
+        ..AuditFilter::default()
+    };
+    let mut paged = Vec::new();
+    let mut cursor = None;
+    loop {
+        let request = PageRequest::new(Some(2), cursor.as_deref(), AuditSort::CreatedAt).unwrap();
+        let page = repository.find_by_filter(&logouts, &request).await.unwrap();
+        assert!(page.items.len() <= 2);
+        paged.extend(page.items.iter().map(|entry| entry.id));
+        match page.next_cursor {
+            Some(next) => cursor = Some(next.encode()),
+            None => break,
+        }
+    }
+    let expected: Vec<_> = entries.iter().map(|entry| entry.id).collect();
+    assert_eq!(paged, expected);
+
+    let first = repository
+        .find_by_filter(
+            &AuditFilter::default(),
+            &PageRequest::new(Some(1), None, AuditSort::CreatedAt).unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(first.items[0].created_at, entries[0].created_at);
+}
+
+/// Each accepted code is recorded once, however many logins verified it.
+pub async fn mfa_repo_contract<U: UserRepo, M: MfaRepo>(users: &U, repository: &M) {
+    let user_id = users
+        .save(&user("john@example.com"))
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+    let mut mfa = UserMfa::new(user_id);
+    let codes = mfa.enable();
+    repository.save(&mfa).await.unwrap();
+
+    let step = UsedCode::TotpStep(100);
+    assert!(repository.record_use(user_id, &step).await.unwrap());
+    assert!(!repository.record_use(user_id, &step).await.unwrap());
+    assert!(!repository
+        .record_use(user_id, &UsedCode::TotpStep(99))
+        .await
+        .unwrap());
+    assert!(repository
+        .record_use(user_id, &UsedCode::TotpStep(101))
+        .await
+        .unwrap());
+
+    let recovery = mfa.clone().verify(&codes[0], 0).unwrap();
+    assert!(repository.record_use(user_id, &recovery).await.unwrap());
+    assert!(!repository.record_use(user_id, &recovery).await.unwrap());
+
+    let stored = repository.find_by_user(user_id).await.unwrap().unwrap();
+    assert_eq!(stored.last_used_step, Some(101));
+    assert_eq!(stored.recovery_codes.len(), codes.len() - 1);
+    assert!(!repository
+        .record_use(UserId::generate(), &step)
+        .await
+        .unwrap());
+}
+
+/// Writes of a unit of work show in `companies` and `employments`, the
+/// repositories outside of it, only once committed.
+pub async fn unit_of_work_contract<W, U, C, E>(
+    unit_of_work: &W,
+    users: &U,
+    companies: &C,
+    employments: &E,
+) where
+    W: UnitOfWorkFactory,
+    U: UserRepo,
+    C: CompanyRepo,
+    E: EmploymentRepo,
+{
+    let user_id = users
+        .save(&user("ceo@example.com"))
+        .await
+        .unwrap()
+        .id
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for finish in ["rollback", "drop", "commit"] {
+        let work = unit_of_work.begin().await.unwrap();
+        let company_id = work
+            .companies()
+            .save(&company(finish, &format!("https://{}.example", finish)))
+            .await
+            .unwrap();
+        work.employments()
+            .save(user_id, company_id, &Position::CEO)
+            .await
+            .unwrap();
+        assert!(work
+            .companies()
+            .find_by_id(company_id)
+            .await
+            .unwrap()
+            .is_some());
+
+        match finish {
+            "rollback" => work.rollback().await.unwrap(),
+            "drop" => drop(work),
+            _ => {
+                assert!(companies.find_by_id(company_id).await.unwrap().is_none());
+                work.commit().await.unwrap();
+            }
+        }
+        ids.push(company_id);
+    }
+
+    let mut committed = Vec::new();
+    for id in ids.iter().copied() {
+        let company = companies.find_by_id(id).await.unwrap();
+        committed.push(company.is_some());
+    }
+    assert_eq!(committed, [false, false, true]);
+    assert_eq!(
+        employments.find_position(user_id, ids[2]).await.unwrap(),
+        Some(Position::CEO)
+    );
+
+    // A conflicting update in a unit of work fails rather than waits.
+    let work = unit_of_work.begin().await.unwrap();
+    let current = companies.find_by_id(ids[2]).await.unwrap().unwrap();
+    let stale = Company {
+        version: current.version - 1,
+        ..current
+    };
+    let conflict = work.companies().update(ids[2], &stale).await.unwrap_err();
+    assert!(conflict.is::<VersionConflict>());
+    drop(work);
+
+    // A failed step leaves nothing behind once the unit is dropped.
+    let work = unit_of_work.begin().await.unwrap();
+    let company_id = work
+        .companies()
+        .save(&company("Half", "https://half.example"))
+        .await
+        .unwrap();
+    assert!(work
+        .employments()
+        .save(UserId::generate(), company_id, &Position::CEO)
+        .await
+        .is_err());
+    drop(work);
+    assert!(companies.find_by_name("Half").await.unwrap().is_none());
+}
+
+mod memory {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::audit::AuditRepository;
+    use crate::adapter::driven::storage::memory::repository::company::CompanyRepository;
+    use crate::adapter::driven::storage::memory::repository::employment::EmploymentRepository;
+    use crate::adapter::driven::storage::memory::repository::mfa::MfaRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+    use crate::adapter::driven::storage::memory::unit_of_work::MemUnitOfWorkFactory;
+
+    #[tokio::test]
+    async fn user_repository_meets_contract() {
+        user_repo_contract(&UserRepository::new(Arc::new(MemStore::new()))).await;
+    }
+
+    #[tokio::test]
+    async fn company_repository_meets_contract() {
+        company_repo_contract(&CompanyRepository::new(Arc::new(MemStore::new()))).await;
+    }
+
+    #[tokio::test]
+    async fn employment_repository_meets_contract() {
+        let store = Arc::new(MemStore::new());
+
+        employment_repo_contract(
+            &UserRepository::new(Arc::clone(&store)),
+            &CompanyRepository::new(Arc::clone(&store)),
+            &EmploymentRepository::new(store),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn audit_repository_meets_contract() {
+        audit_repo_contract(&AuditRepository::new()).await;
+    }
+
+    #[tokio::test]
+    async fn mfa_repository_meets_contract() {
+        mfa_repo_contract(
+            &UserRepository::new(Arc::new(MemStore::new())),
+            &MfaRepository::new(),
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn unit_of_work_meets_contract() {
+        let store = Arc::new(MemStore::new());
+
+        unit_of_work_contract(
+            &MemUnitOfWorkFactory::new(Arc::clone(&store)),
+            &UserRepository::new(Arc::clone(&store)),
+            &CompanyRepository::new(Arc::clone(&store)),
+            &EmploymentRepository::new(store),
+        )
+        .await;
+    }
+}
+
+mod cached {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::adapter::driven::storage::cached::user::{cache_key, CachedUserRepository};
+    use crate::adapter::driven::storage::memory::repository::cache::CacheRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+
+    #[tokio::test]
+    async fn cached_user_repository_meets_contract() {
+        user_repo_contract(&CachedUserRepository::new(
+            Arc::new(UserRepository::new(Arc::new(MemStore::new()))),
+            Arc::new(CacheRepository::new()),
+            60,
+            &cache_key(None).unwrap(),
+        ))
+        .await;
+    }
+}
+
+/// Each test gets a fresh database with the migrations applied, on the server
+/// of `DATABASE_URL`.
+mod postgres {
+    use std::sync::Arc;
+
+    use sqlx::PgPool;
+
+    use super::*;
+    use crate::adapter::driven::storage::db::repository::audit::AuditRepository;
+    use crate::adapter::driven::storage::db::repository::company::CompanyRepository;
+    use crate::adapter::driven::storage::db::repository::employment::EmploymentRepository;
+    use crate::adapter::driven::storage::db::repository::mfa::MfaRepository;
+    use crate::adapter::driven::storage::db::repository::user::UserRepository;
+    use crate::adapter::driven::storage::db::unit_of_work::PgUnitOfWorkFactory;
+
+    #[sqlx::test]
+    async fn user_repository_meets_contract(pool: PgPool) {
+        user_repo_contract(&UserRepository::new(Arc::new(pool))).await;
+    }
+
+    #[sqlx::test]
+    async fn company_repository_meets_contract(pool: PgPool) {
+        company_repo_contract(&CompanyRepository::new(Arc::new(pool))).await;
+    }
+
+    #[sqlx::test]
+    async fn employment_repository_meets_contract(pool: PgPool) {
+        let pool = Arc::new(pool);
+
+        employment_repo_contract(
+            &UserRepository::new(Arc::clone(&pool)),
+            &CompanyRepository::new(Arc::clone(&pool)),
+            &EmploymentRepository::new(pool),
+        )
+        .await;
+    }
+
+    #[sqlx::test]
+    async fn audit_repository_meets_contract(pool: PgPool) {
+        audit_repo_contract(&AuditRepository::new(Arc::new(pool))).await;
+    }
+
+    #[sqlx::test]
+    async fn mfa_repository_meets_contract(pool: PgPool) {
+        let pool = Arc::new(pool);
+
+        mfa_repo_contract(
+            &UserRepository::new(Arc::clone(&pool)),
+            &MfaRepository::new(pool),
+        )
+        .await;
+    }
+
+    #[sqlx::test]
+    async fn unit_of_work_meets_contract(pool: PgPool) {
+        let pool = Arc::new(pool);
+
+        unit_of_work_contract(
+            &PgUnitOfWorkFactory::new(Arc::clone(&pool)),
+            &UserRepository::new(Arc::clone(&pool)),
+            &CompanyRepository::new(Arc::clone(&pool)),
+            &EmploymentRepository::new(pool),
+        )
+        .await;
+    }
+}
@@ -1,9 +1,19 @@
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use anyhow::Error;
-use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
+use anyhow::{anyhow, bail, Error};
+use log::LevelFilter;
+use sqlx::migrate::Migrator;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{ConnectOptions, Pool, Postgres};
+use tracing::warn;
 
-use crate::shared::config::config::Config;
+use crate::shared::config::config::{Config, Database};
+use crate::shared::config::environment::Environment;
+
+/// The migrations of `migrations/`, built into the binary.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
 #[derive(Debug, Clone)]
 pub struct DB {
@@ -11,17 +21,207 @@ pub struct DB {
 }
 
 impl DB {
-    pub async fn new() -> Result<Self, Error> {
-        let config = Config::get();
-        let url = &config.database.uri;
-        let max_conn = &config.database.max_connections;
-        let pool = PgPoolOptions::new()
-            .max_connections(*max_conn)
-            .connect(url)
-            .await?;
+    /// Connects as configured in `database`, then recreates, migrates or
+    /// truncates the database if asked to.
+    pub async fn new(environment: &Environment) -> Result<Self, Error> {
+        let config = &Config::get().database;
+        check_resets_allowed(environment, config)?;
+
+        let pool = connect(config).await?;
+
+        if config.dangerously_recreate {
+            warn!("recreating the database schema");
+            recreate(&pool).await?;
+            MIGRATOR.run(&pool).await?;
+        } else if config.auto_migrate {
+            MIGRATOR.run(&pool).await?;
+        }
+        if config.dangerously_truncate {
+            warn!("truncating every database table");
+            truncate(&pool).await?;
+        }
 
         Ok(DB {
             pool: Arc::new(pool),
         })
     }
 }
+
+/// Truncating or recreating a production database is never what was meant.
+fn check_resets_allowed(environment: &Environment, config: &Database) -> Result<(), Error> {
+    if *environment == Environment::Production
+        && (config.dangerously_truncate || config.dangerously_recreate)
+    {
+        bail!("refusing to truncate or recreate the database in production");
+    }
+
+    Ok(())
+}
+
+async fn connect(config: &Database) -> Result<Pool<Postgres>, Error> {
+    let mut options = PgConnectOptions::from_str(&config.uri)?;
+    options = if config.enable_logging {
+        options.log_statements(LevelFilter::Info)
+    } else {
+        options.disable_statement_logging()
+    };
+
+    let mut pool = PgPoolOptions::new()
+        .min_connections(config.min_connections)
+        .max_connections(config.max_connections)
+        .idle_timeout(Duration::from_millis(config.idle_timeout));
+    if let Some(acquire_timeout) = config.acquire_timeout {
+        pool = pool.acquire_timeout(Duration::from_millis(acquire_timeout));
+    }
+
+    let connect_timeout = Duration::from_millis(config.connect_timeout);
+    tokio::time::timeout(connect_timeout, pool.connect_with(options))
+        .await
+        .map_err(|_| anyhow!("database connection timed out after {:?}", connect_timeout))?
+        .map_err(Error::from)
+}
+
+/// Drops the current schema with everything in it, leaving it empty.
+async fn recreate(pool: &Pool<Postgres>) -> Result<(), Error> {
+    let schema = sqlx::query_scalar!(r#"SELECT current_schema() AS "schema!""#)
+        .fetch_one(pool)
+        .await?;
+    let schema = quote_identifier(&schema);
+
+    sqlx::raw_sql(&format!(
+        "DROP SCHEMA {schema} CASCADE; CREATE SCHEMA {schema};"
+    ))
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Deletes the rows of every table but the migration history and the audit
+/// log. The audit log is append-only, its triggers refuse to truncate it.
+async fn truncate(pool: &Pool<Postgres>) -> Result<(), Error> {
+    let tables = sqlx::query_scalar!(
+        r#"
+				SELECT tablename::TEXT AS "tablename!"
+				FROM pg_tables
+				WHERE schemaname = current_schema()
+					AND tablename NOT IN ('_sqlx_migrations', 'audit_log')
+				"#
+    )
+    .fetch_all(pool)
+    .await?;
+    if tables.is_empty() {
+        return Ok(());
+    }
+
+    let tables: Vec<String> = tables.iter().map(|table| quote_identifier(table)).collect();
+    sqlx::raw_sql(&format!("TRUNCATE TABLE {} CASCADE", tables.join(", ")))
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database(truncate: bool, recreate: bool) -> Database {
+        Database {
+            uri: "postgres://localhost/matchmaker".to_string(),
+            enable_logging: false,
+            min_connections: 1,
+            max_connections: 1,
+            connect_timeout: 500,
+            idle_timeout: 500,
+            acquire_timeout: None,
+            auto_migrate: true,
+            dangerously_truncate: truncate,
+            dangerously_recreate: recreate,
+        }
+    }
+
+    #[test]
+    fn production_databases_are_never_reset() {
+        for (truncate, recreate) in [(true, false), (false, true), (true, true)] {
+            let config = database(truncate, recreate);
+
+            assert!(check_resets_allowed(&Environment::Production, &config).is_err());
+            assert!(check_resets_allowed(&Environment::Test, &config).is_ok());
+        }
+        assert!(check_resets_allowed(&Environment::Production, &database(false, false)).is_ok());
+    }
+
+    #[sqlx::test]
+    async fn truncate_keeps_the_schema_migrations_and_audit_log(pool: Pool<Postgres>) {
+        sqlx::query!(
+            r#"
+						INSERT INTO "user" (name, surname, email, role, password_hash)
+						VALUES ('John', 'Doe', 'john@example.com', 'USER', 'hash')
+						"#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query!(r#"INSERT INTO "audit_log" (action) VALUES ('logout')"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        truncate(&pool).await.unwrap();
+
+        let users = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM "user""#)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(users, 0);
+        let entries = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM "audit_log""#)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(entries, 1);
+        assert!(sqlx::raw_sql(r#"TRUNCATE TABLE "audit_log""#)
+            .execute(&pool)
+            .await
+            .is_err());
+        MIGRATOR.run(&pool).await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn recreate_then_migrate_rebuilds_the_schema(pool: Pool<Postgres>) {
+        recreate(&pool).await.unwrap();
+        MIGRATOR.run(&pool).await.unwrap();
+
+        let users = sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM "user""#)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(users, 0);
+    }
+
+    #[sqlx::test]
+    async fn email_normalization_lists_the_emails_it_would_merge(pool: Pool<Postgres>) {
+        // Back to before emails were normalized.
+        MIGRATOR.undo(&pool, 20241019160000).await.unwrap();
+        sqlx::raw_sql(
+            r#"
+						INSERT INTO "user" (name, surname, email, role, password_hash)
+						VALUES ('John', 'Doe', 'john@example.com', 'USER', 'hash'),
+							('John', 'Doe', ' John@Example.com', 'USER', 'hash'),
+							('Jane', 'Doe', 'Jane@Example.com', 'USER', 'hash')
+						"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let error = MIGRATOR.run(&pool).await.unwrap_err().to_string();
+
+        assert!(error.contains("' John@Example.com', 'john@example.com'"));
+        assert!(!error.contains("Jane"));
+    }
+}
@@ -0,0 +1,169 @@
+use thiserror::Error;
+
+use crate::core::domain::error::DomainError;
+use crate::core::port::error::AlreadyTaken;
+
+/// A stored row that does not map to the domain, e.g. one holding a role that
+/// was since removed. Reading it fails the query rather than the process.
+#[derive(Debug, Error)]
+#[error("Corrupt {table} row {key}: {source}")]
+pub struct CorruptRow {
+    pub table: &'static str,
+    /// The primary key of the row.
+    pub key: String,
+    pub source: DomainError,
+}
+
+impl CorruptRow {
+    pub fn new(table: &'static str, key: impl ToString, source: DomainError) -> Self {
+        CorruptRow {
+            table,
+            key: key.to_string(),
+            source,
+        }
+    }
+}
+
+/// Reports a unique constraint violation as [`AlreadyTaken`] on the one of
+/// `fields` whose constraint was violated. The constraints keep the names
+/// Postgres gives `UNIQUE` columns, `<entity>_<field>_key`.
+pub fn already_taken(
+    error: sqlx::Error,
+    entity: &'static str,
+    fields: &[&'static str],
+) -> anyhow::Error {
+    match error.as_database_error() {
+        Some(database_error) if database_error.is_unique_violation() => {
+            let constraint = database_error.constraint().unwrap_or_default();
+            let field = fields
+                .iter()
+                .find(|field| constraint == format!("{}_{}_key", entity, field))
+                .unwrap_or(&fields[0]);
+
+            AlreadyTaken::new(entity, field).into()
+        }
+        _ => error.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sqlx::{Pool, Postgres};
+
+    use super::*;
+    use crate::adapter::driven::storage::db::repository::audit::AuditRepository;
+    use crate::adapter::driven::storage::db::repository::company::CompanyRepository;
+    use crate::adapter::driven::storage::db::repository::employment::EmploymentRepository;
+    use crate::adapter::driven::storage::db::repository::user::UserRepository;
+    use crate::core::domain::valueobject::email::Email;
+    use crate::core::domain::valueobject::id::{CompanyId, UserId};
+    use crate::core::port::audit::{AuditFilter, AuditRepo, AuditSort};
+    use crate::core::port::company::CompanyRepo;
+    use crate::core::port::employment::EmploymentRepo;
+    use crate::core::port::page::PageRequest;
+    use crate::core::port::user::UserRepo;
+
+    const USER_ID: &str = "00000000-0000-0000-0000-000000000001";
+    const COMPANY_ID: &str = "00000000-0000-0000-0000-000000000002";
+
+    /// Stores rows that an older schema, or a hand-written fix, could have left
+    /// behind: values the domain no longer knows.
+    async fn insert_corrupt_rows(pool: &Pool<Postgres>) {
+        sqlx::raw_sql(&format!(
+            r#"
+            ALTER TABLE "user" DROP CONSTRAINT user_role_check;
+            ALTER TABLE company DROP CONSTRAINT company_sector_check;
+            ALTER TABLE employment DROP CONSTRAINT employment_position_check;
+
+            INSERT INTO "user" (id, name, surname, email, role, password_hash)
+            VALUES ('{USER_ID}', 'John', 'Doe', 'john@example.com', 'SUPERUSER', 'hash');
+            INSERT INTO company (id, foundation_date, name, description, sector)
+            VALUES ('{COMPANY_ID}', 2000, 'Acme', 'Makes things', 'Farming');
+            INSERT INTO employment (user_id, company_id, position)
+            VALUES ('{USER_ID}', '{COMPANY_ID}', 'Intern');
+            INSERT INTO audit_log (action) VALUES ('user_renamed');
+            "#
+        ))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn assert_corrupt(error: anyhow::Error, table: &str, source: DomainError) {
+        let corrupt = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<CorruptRow>())
+            .unwrap_or_else(|| panic!("not a corrupt row: {:?}", error));
+
+        assert_eq!(corrupt.table, table);
+        assert_eq!(corrupt.source, source);
+    }
+
+    #[sqlx::test]
+    async fn corrupt_rows_fail_the_query_instead_of_panicking(pool: Pool<Postgres>) {
+        insert_corrupt_rows(&pool).await;
+        let pool = Arc::new(pool);
+        let users = UserRepository::new(pool.clone());
+        let companies = CompanyRepository::new(pool.clone());
+        let employments = EmploymentRepository::new(pool.clone());
+        let audit = AuditRepository::new(pool);
+        let user_id: UserId = USER_ID.parse().unwrap();
+        let company_id: CompanyId = COMPANY_ID.parse().unwrap();
+
+        let unknown_role = DomainError::UnknownRole("SUPERUSER".to_string());
+        assert_corrupt(
+            users.find_by_id(user_id).await.unwrap_err(),
+            "user",
+            unknown_role.clone(),
+        );
+        assert_corrupt(
+            users
+                .find_by_email(&Email::parse("john@example.com").unwrap())
+                .await
+                .unwrap_err(),
+            "user",
+            unknown_role.clone(),
+        );
+        assert_corrupt(users.find_all().await.unwrap_err(), "user", unknown_role);
+
+        let unknown_sector = DomainError::UnknownSector("Farming".to_string());
+        assert_corrupt(
+            companies.find_by_id(company_id).await.unwrap_err(),
+            "company",
+            unknown_sector.clone(),
+        );
+        assert_corrupt(
+            companies.find_by_name("Acme").await.unwrap_err(),
+            "company",
+            unknown_sector.clone(),
+        );
+        assert_corrupt(
+            companies.find_all().await.unwrap_err(),
+            "company",
+            unknown_sector,
+        );
+
+        assert_corrupt(
+            employments
+                .find_position(user_id, company_id)
+                .await
+                .unwrap_err(),
+            "employment",
+            DomainError::UnknownPosition("Intern".to_string()),
+        );
+
+        assert_corrupt(
+            audit
+                .find_by_filter(
+                    &AuditFilter::default(),
+                    &PageRequest::new(None, None, AuditSort::CreatedAt).unwrap(),
+                )
+                .await
+                .unwrap_err(),
+            "audit_log",
+            DomainError::UnknownAuditAction("user_renamed".to_string()),
+        );
+    }
+}
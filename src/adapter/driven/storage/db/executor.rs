@@ -0,0 +1,53 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use sqlx::pool::PoolConnection;
+use sqlx::{PgConnection, Pool, Postgres, Transaction};
+use tokio::sync::{MappedMutexGuard, Mutex, MutexGuard};
+
+/// Where a repository runs its queries: straight on the pool, or in a
+/// transaction shared with other repositories.
+#[derive(Debug, Clone)]
+pub enum Executor {
+    Pool(Arc<Pool<Postgres>>),
+    /// `None` once the transaction was committed or rolled back.
+    Transaction(Arc<Mutex<Option<Transaction<'static, Postgres>>>>),
+}
+
+/// A connection for one query, see [`Executor::acquire`].
+pub enum Connection<'a> {
+    Pooled(Box<PoolConnection<Postgres>>),
+    Transaction(MappedMutexGuard<'a, Transaction<'static, Postgres>>),
+}
+
+impl Executor {
+    pub async fn acquire(&self) -> Result<Connection<'_>, Error> {
+        match self {
+            Executor::Pool(pool) => Ok(Connection::Pooled(Box::new(pool.acquire().await?))),
+            Executor::Transaction(tx) => MutexGuard::try_map(tx.lock().await, Option::as_mut)
+                .map(Connection::Transaction)
+                .map_err(|_| anyhow!("transaction is already finished")),
+        }
+    }
+}
+
+impl Deref for Connection<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Connection::Pooled(conn) => conn,
+            Connection::Transaction(tx) => tx,
+        }
+    }
+}
+
+impl DerefMut for Connection<'_> {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            Connection::Pooled(conn) => conn,
+            Connection::Transaction(tx) => tx,
+        }
+    }
+}
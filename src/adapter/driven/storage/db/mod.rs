@@ -1,2 +1,5 @@
 pub mod db_connection;
+pub mod error;
+pub mod executor;
 pub mod repository;
+pub mod unit_of_work;
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::core::domain::entity::api_key::ApiKey;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::scope::Scope;
+use crate::core::port::api_key::ApiKeyRepo;
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyRepository {
+    db: Arc<Pool<Postgres>>,
+}
+
+impl ApiKeyRepository {
+    pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        ApiKeyRepository { db }
+    }
+}
+
+/// Scopes stored by an older release and since removed grant nothing.
+fn parse_scopes(scopes: Vec<String>) -> Vec<Scope> {
+    scopes
+        .iter()
+        .filter_map(|scope| Scope::parse(scope))
+        .collect()
+}
+
+#[async_trait]
+impl ApiKeyRepo for ApiKeyRepository {
+    async fn save(&self, api_key: &ApiKey) -> Result<ApiKey, Error> {
+        let scopes: Vec<String> = api_key
+            .scopes
+            .iter()
+            .map(|scope| scope.as_str().to_string())
+            .collect();
+        let row = sqlx::query!(
+            r#"
+						INSERT INTO "api_key" (id, user_id, name, prefix, key_hash, scopes, mfa, created_at, last_used_at, expires_at, revoked_at)
+						VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+						RETURNING id, user_id, name, prefix, key_hash, scopes, mfa, created_at, last_used_at, expires_at, revoked_at
+						"#,
+            api_key.id,
+            api_key.user_id.as_uuid(),
+            api_key.name,
+            api_key.prefix,
+            api_key.key_hash,
+            &scopes,
+            api_key.mfa,
+            api_key.created_at.convert_to_offset(),
+            api_key.last_used_at.as_ref().map(|ts| ts.convert_to_offset()),
+            api_key.expires_at.as_ref().map(|ts| ts.convert_to_offset()),
+            api_key.revoked_at.as_ref().map(|ts| ts.convert_to_offset()),
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(ApiKey {
+            id: row.id,
+            user_id: UserId::from(row.user_id),
+            name: row.name,
+            prefix: row.prefix,
+            key_hash: row.key_hash,
+            scopes: parse_scopes(row.scopes),
+            mfa: row.mfa,
+            created_at: Timestamp::from(row.created_at),
+            last_used_at: row.last_used_at.map(Timestamp::from),
+            expires_at: row.expires_at.map(Timestamp::from),
+            revoked_at: row.revoked_at.map(Timestamp::from),
+        })
+    }
+
+    async fn find_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, Error> {
+        let row = sqlx::query!(
+            r#"
+						SELECT id, user_id, name, prefix, key_hash, scopes, mfa, created_at, last_used_at, expires_at, revoked_at
+						FROM "api_key"
+						WHERE prefix = $1
+						"#,
+            prefix
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        let api_key = row.map(|row| ApiKey {
+            id: row.id,
+            user_id: UserId::from(row.user_id),
+            name: row.name,
+            prefix: row.prefix,
+            key_hash: row.key_hash,
+            scopes: parse_scopes(row.scopes),
+            mfa: row.mfa,
+            created_at: Timestamp::from(row.created_at),
+            last_used_at: row.last_used_at.map(Timestamp::from),
+            expires_at: row.expires_at.map(Timestamp::from),
+            revoked_at: row.revoked_at.map(Timestamp::from),
+        });
+
+        Ok(api_key)
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<ApiKey>, Error> {
+        let rows = sqlx::query!(
+            r#"
+						SELECT id, user_id, name, prefix, key_hash, scopes, mfa, created_at, last_used_at, expires_at, revoked_at
+						FROM "api_key"
+						WHERE user_id = $1 AND revoked_at IS NULL
+						ORDER BY created_at DESC
+						"#,
+            user_id.as_uuid()
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        let api_keys = rows
+            .into_iter()
+            .map(|row| ApiKey {
+                id: row.id,
+                user_id: UserId::from(row.user_id),
+                name: row.name,
+                prefix: row.prefix,
+                key_hash: row.key_hash,
+                scopes: parse_scopes(row.scopes),
+                mfa: row.mfa,
+                created_at: Timestamp::from(row.created_at),
+                last_used_at: row.last_used_at.map(Timestamp::from),
+                expires_at: row.expires_at.map(Timestamp::from),
+                revoked_at: row.revoked_at.map(Timestamp::from),
+            })
+            .collect();
+
+        Ok(api_keys)
+    }
+
+    async fn revoke(&self, user_id: UserId, id: Uuid) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+						UPDATE "api_key"
+						SET revoked_at = now()
+						WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL
+						"#,
+            id,
+            user_id.as_uuid()
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn touch(&self, id: Uuid) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+						UPDATE "api_key"
+						SET last_used_at = now()
+						WHERE id = $1
+						"#,
+            id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::adapter::driven::storage::db::error::CorruptRow;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::audit::{AuditFilter, AuditRepo, AuditSort};
+use crate::core::port::page::{Page, PageRequest};
+
+#[derive(Debug, Clone)]
+pub struct AuditRepository {
+    db: Arc<Pool<Postgres>>,
+}
+
+impl AuditRepository {
+    pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        AuditRepository { db }
+    }
+}
+
+#[async_trait]
+impl AuditRepo for AuditRepository {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+						INSERT INTO "audit_log" (id, action, actor_id, target_id, ip, user_agent, details, created_at)
+						VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+						"#,
+            entry.id,
+            entry.action.as_str(),
+            entry.actor_id.map(Uuid::from),
+            entry.target_id,
+            entry.ip,
+            entry.user_agent,
+            entry.details,
+            entry.created_at.convert_to_offset(),
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_filter(
+        &self,
+        filter: &AuditFilter,
+        page: &PageRequest<AuditSort>,
+    ) -> Result<Page<AuditEntry>, Error> {
+        let after = page.cursor.as_ref().and_then(|cursor| cursor.timestamp());
+        let after_id = page.cursor.as_ref().map(|cursor| cursor.id);
+
+        let rows = match page.sort {
+            AuditSort::CreatedAt => {
+                sqlx::query!(
+                    r#"
+						SELECT id, action, actor_id, target_id, ip, user_agent, details, created_at
+						FROM "audit_log"
+						WHERE ($1::TEXT IS NULL OR action = $1)
+								AND ($2::UUID IS NULL OR actor_id = $2)
+								AND ($3::UUID IS NULL OR target_id = $3)
+								AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+								AND ($5::TIMESTAMPTZ IS NULL OR created_at < $5)
+								AND ($6::TIMESTAMPTZ IS NULL OR (created_at, id) < ($6, $7::UUID))
+						ORDER BY created_at DESC, id DESC
+						LIMIT $8
+						"#,
+                    filter.action.map(|action| action.as_str()),
+                    filter.actor_id.map(Uuid::from),
+                    filter.target_id,
+                    filter.from.as_ref().map(|ts| ts.convert_to_offset()),
+                    filter.to.as_ref().map(|ts| ts.convert_to_offset()),
+                    after.map(|ts| ts.convert_to_offset()),
+                    after_id,
+                    page.fetch_limit()
+                )
+                .fetch_all(&*self.db)
+                .await?
+            }
+        };
+
+        let entries = rows
+            .into_iter()
+            .map(|row| {
+                Ok(AuditEntry {
+                    id: row.id,
+                    action: AuditAction::try_from(row.action.as_str())
+                        .map_err(|e| CorruptRow::new("audit_log", row.id, e))?,
+                    actor_id: row.actor_id.map(UserId::from),
+                    target_id: row.target_id,
+                    ip: row.ip,
+                    user_agent: row.user_agent,
+                    details: row.details,
+                    created_at: Timestamp::from(row.created_at),
+                })
+            })
+            .collect::<Result<Vec<AuditEntry>, CorruptRow>>()?;
+
+        Ok(Page::from_rows(entries, page, |entry| {
+            page.sort.cursor_key(entry)
+        }))
+    }
+}
@@ -3,34 +3,98 @@ use std::sync::Arc;
 use anyhow::{anyhow, Context, Error};
 use async_trait::async_trait;
 use sqlx::{Pool, Postgres};
+use time::OffsetDateTime;
 use uuid::Uuid;
 
+use crate::adapter::driven::storage::db::error::{already_taken, CorruptRow};
+use crate::adapter::driven::storage::db::executor::Executor;
 use crate::core::domain::entity::company::Company;
 use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::CompanyId;
 use crate::core::domain::valueobject::sector::Sector;
-use crate::core::port::company::CompanyRepo;
+use crate::core::port::company::{CompanyRepo, CompanySort};
+use crate::core::port::error::VersionConflict;
+use crate::core::port::page::{Page, PageRequest};
+
+/// A `company` row as stored.
+struct CompanyRow {
+    id: Uuid,
+    foundation_date: i16,
+    name: String,
+    description: String,
+    url: Option<String>,
+    sector: String,
+    created_at: OffsetDateTime,
+    updated_at: OffsetDateTime,
+    version: i32,
+    deleted_at: Option<OffsetDateTime>,
+}
+
+impl TryFrom<CompanyRow> for Company {
+    type Error = CorruptRow;
+
+    fn try_from(row: CompanyRow) -> Result<Self, Self::Error> {
+        let sector = Sector::try_from(row.sector.as_str())
+            .map_err(|e| CorruptRow::new("company", row.id, e))?;
+
+        Ok(Company {
+            id: Some(CompanyId::from(row.id)),
+            foundation_date: row.foundation_date,
+            name: row.name,
+            description: row.description,
+            url: row.url.unwrap_or_default(),
+            sector,
+            created_at: Timestamp::from(row.created_at),
+            updated_at: Timestamp::from(row.updated_at),
+            version: row.version,
+            deleted_at: row.deleted_at.map(Timestamp::from),
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct CompanyRepository {
-    db: Arc<Pool<Postgres>>,
+    db: Executor,
 }
 
 impl CompanyRepository {
     pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        CompanyRepository {
+            db: Executor::Pool(db),
+        }
+    }
+
+    pub fn with_executor(db: Executor) -> Self {
         CompanyRepository { db }
     }
+
+    async fn exists(&self, id: CompanyId) -> Result<bool, Error> {
+        let mut conn = self.db.acquire().await?;
+        let exists = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (SELECT 1 FROM company WHERE id = $1 AND deleted_at IS NULL) AS "exists!"
+            "#,
+            id.as_uuid()
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .context("Error querying company by id")?;
+
+        Ok(exists)
+    }
 }
 
 #[async_trait]
 impl CompanyRepo for CompanyRepository {
-    async fn save(&self, company: &Company) -> Result<Uuid, Error> {
+    async fn save(&self, company: &Company) -> Result<CompanyId, Error> {
+        let mut conn = self.db.acquire().await?;
         let saved_company_id = sqlx::query_scalar!(
             r#"
             INSERT INTO company (id, foundation_date, name, description, url, sector, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            VALUES (COALESCE($1, uuid_generate_v4()), $2, $3, $4, $5, $6, $7, $8)
             RETURNING id
             "#,
-            company.id,
+            company.id.map(Uuid::from),
             company.foundation_date,
             company.name,
             company.description,
@@ -39,18 +103,19 @@ impl CompanyRepo for CompanyRepository {
             Timestamp::now_utc().convert_to_offset(),
             Timestamp::now_utc().convert_to_offset(),
         )
-        .fetch_one(&*self.db)
+        .fetch_one(&mut *conn)
         .await
-        .context("Error saving company to database")?;
+        .map_err(|e| already_taken(e, "company", &["name", "url"]))?;
 
-        Ok(saved_company_id)
+        Ok(CompanyId::from(saved_company_id))
     }
 
-    async fn update(&self, id_str: &str, company: &Company) -> Result<Company, Error> {
-        let id = Uuid::parse_str(id_str).context("Invalid UUID format")?;
+    async fn update(&self, id: CompanyId, company: &Company) -> Result<Company, Error> {
         let sector_str = company.sector.to_string();
 
-        let row = sqlx::query!(
+        let mut conn = self.db.acquire().await?;
+        let row = sqlx::query_as!(
+            CompanyRow,
             r#"
             UPDATE company
             SET
@@ -59,152 +124,196 @@ impl CompanyRepo for CompanyRepository {
                 description = COALESCE($4, description),
                 url = COALESCE($5, url),
                 sector = COALESCE($6, sector),
-                updated_at = COALESCE($7, updated_at)
-            WHERE id = $1
-            RETURNING id, foundation_date, name, description, url, sector, created_at, updated_at
+                updated_at = COALESCE($7, updated_at),
+                version = version + 1
+            WHERE id = $1 AND version = $8 AND deleted_at IS NULL
+            RETURNING id, foundation_date, name, description, url, sector, created_at, updated_at, version, deleted_at
             "#,
-            id,
+            id.as_uuid(),
             company.foundation_date as i16,
             company.name,
             company.description,
             company.url,
             sector_str,
             Timestamp::now_utc().convert_to_offset(),
+            company.version,
         )
-        .fetch_one(&*self.db)
+        .fetch_optional(&mut *conn)
         .await
-        .context("Error updating company in database")?;
-
-        let updated_company = Company {
-            id: Some(row.id),
-            foundation_date: row.foundation_date,
-            name: row.name,
-            description: row.description,
-            url: row.url.unwrap_or_default(),
-            sector: Sector::from_string(&row.sector)
-                .ok_or_else(|| anyhow!("Unknown sector value: {}", row.sector))?,
-            created_at: Timestamp::from(row.created_at),
-            updated_at: Timestamp::from(row.updated_at),
-        };
+        .map_err(|e| already_taken(e, "company", &["name", "url"]))?;
+        drop(conn);
 
-        Ok(updated_company)
+        // No row matched: the company is gone, or was changed since it was read.
+        match row {
+            Some(row) => Ok(Company::try_from(row)?),
+            None if self.exists(id).await? => {
+                Err(VersionConflict::new("company", id, company.version).into())
+            }
+            None => Err(anyhow!("company {} not found", id)),
+        }
     }
 
-    async fn delete(&self, id_str: &str) -> Result<(), Error> {
-        let id = Uuid::parse_str(id_str).context("Invalid UUID format")?;
+    async fn delete(&self, id: CompanyId) -> Result<(), Error> {
+        let mut conn = self.db.acquire().await?;
         sqlx::query!(
             r#"
-            DELETE FROM company WHERE id = $1
+            UPDATE company SET deleted_at = $2 WHERE id = $1 AND deleted_at IS NULL
             "#,
-            id
+            id.as_uuid(),
+            Timestamp::now_utc().convert_to_offset()
         )
-        .execute(&*self.db)
+        .execute(&mut *conn)
         .await
         .context("Error deleting company from database")?;
 
         Ok(())
     }
 
+    async fn restore(&self, id: CompanyId) -> Result<Option<Company>, Error> {
+        let mut conn = self.db.acquire().await?;
+        let row = sqlx::query_as!(
+            CompanyRow,
+            r#"
+            UPDATE company
+            SET deleted_at = NULL, updated_at = $2, version = version + 1
+            WHERE id = $1 AND deleted_at IS NOT NULL
+            RETURNING id, foundation_date, name, description, url, sector, created_at, updated_at, version, deleted_at
+            "#,
+            id.as_uuid(),
+            Timestamp::now_utc().convert_to_offset()
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Error restoring company in database")?;
+
+        Ok(row.map(Company::try_from).transpose()?)
+    }
+
+    async fn purge_deleted(&self, before: &Timestamp) -> Result<u64, Error> {
+        let mut conn = self.db.acquire().await?;
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM company WHERE deleted_at < $1
+            "#,
+            before.convert_to_offset()
+        )
+        .execute(&mut *conn)
+        .await
+        .context("Error purging deleted companies from database")?;
+
+        Ok(result.rows_affected())
+    }
+
     async fn find_all(&self) -> Result<Vec<Company>, Error> {
-        let rows = sqlx::query!(
+        let mut conn = self.db.acquire().await?;
+        let rows = sqlx::query_as!(
+            CompanyRow,
             r#"
-            SELECT id, foundation_date, name, description, url, sector, created_at, updated_at
+            SELECT id, foundation_date, name, description, url, sector, created_at, updated_at, version, deleted_at
             FROM company
+            WHERE deleted_at IS NULL
             "#
         )
-        .fetch_all(&*self.db)
+        .fetch_all(&mut *conn)
         .await
         .context("Error fetching all companies from database")?;
 
         let companies = rows
             .into_iter()
-            .map(|row| {
-                let sector_enum = Sector::from_string(&row.sector)
-                    .ok_or_else(|| anyhow!("Unknown sector value: {}", row.sector))?;
-
-                Ok(Company {
-                    id: Some(row.id),
-                    foundation_date: row.foundation_date,
-                    name: row.name,
-                    description: row.description,
-                    url: row.url.unwrap_or_default(),
-                    sector: sector_enum,
-                    created_at: Timestamp::from(row.created_at),
-                    updated_at: Timestamp::from(row.updated_at),
-                })
-            })
-            .collect::<Result<Vec<Company>, Error>>()
+            .map(Company::try_from)
+            .collect::<Result<Vec<Company>, CorruptRow>>()
             .context("Error mapping rows to company entities")?;
 
         Ok(companies)
     }
 
-    async fn find_by_id(&self, id_str: &str) -> Result<Option<Company>, Error> {
-        let id = Uuid::parse_str(id_str).context("Invalid UUID format")?;
-
-        let row = sqlx::query!(
+    async fn find_by_id(&self, id: CompanyId) -> Result<Option<Company>, Error> {
+        let mut conn = self.db.acquire().await?;
+        let row = sqlx::query_as!(
+            CompanyRow,
             r#"
-            SELECT id, foundation_date, name, description, url, sector, created_at, updated_at
-            FROM company WHERE id = $1
+            SELECT id, foundation_date, name, description, url, sector, created_at, updated_at, version, deleted_at
+            FROM company WHERE id = $1 AND deleted_at IS NULL
             "#,
-            id
+            id.as_uuid()
         )
-        .fetch_optional(&*self.db)
+        .fetch_optional(&mut *conn)
         .await
         .context("Error querying company by id")?;
 
-        match row {
-            Some(row) => {
-                let sector_enum = Sector::from_string(&row.sector)
-                    .ok_or_else(|| anyhow!("Unknown sector value: {}", row.sector))?;
-
-                let company = Company {
-                    id: Some(row.id),
-                    foundation_date: row.foundation_date,
-                    name: row.name,
-                    description: row.description,
-                    url: row.url.unwrap_or_default(),
-                    sector: sector_enum,
-                    created_at: Timestamp::from(row.created_at),
-                    updated_at: Timestamp::from(row.updated_at),
-                };
-
-                Ok(Some(company))
-            }
-            None => Ok(None),
-        }
+        Ok(row.map(Company::try_from).transpose()?)
     }
 
     async fn find_by_name(&self, name: &str) -> Result<Option<Company>, Error> {
-        let row = sqlx::query!(
+        let mut conn = self.db.acquire().await?;
+        let row = sqlx::query_as!(
+            CompanyRow,
             r#"
-            SELECT id, foundation_date, name, description, url, sector, created_at, updated_at
-            FROM company WHERE name = $1
+            SELECT id, foundation_date, name, description, url, sector, created_at, updated_at, version, deleted_at
+            FROM company WHERE name = $1 AND deleted_at IS NULL
             "#,
             name
         )
-        .fetch_optional(&*self.db)
+        .fetch_optional(&mut *conn)
         .await
         .context("Error querying company by name")?;
 
-        if let Some(row) = row {
-            let sector_enum = Sector::from_string(&row.sector)
-                .ok_or_else(|| anyhow!("Unknown sector value: {}", row.sector))?;
-
-            let company = Company {
-                id: Some(row.id),
-                foundation_date: row.foundation_date,
-                name: row.name,
-                description: row.description,
-                url: row.url.unwrap_or_default(),
-                sector: sector_enum,
-                created_at: Timestamp::from(row.created_at),
-                updated_at: Timestamp::from(row.updated_at),
-            };
-
-            Ok(Some(company))
-        } else {
-            Ok(None)
+        Ok(row.map(Company::try_from).transpose()?)
+    }
+
+    async fn find_page(&self, page: &PageRequest<CompanySort>) -> Result<Page<Company>, Error> {
+        let after_id = page.cursor.as_ref().map(|cursor| cursor.id);
+        let mut conn = self.db.acquire().await?;
+        let rows = match page.sort {
+            CompanySort::CreatedAt => {
+                let after = page.cursor.as_ref().and_then(|cursor| cursor.timestamp());
+                sqlx::query_as!(
+                    CompanyRow,
+                    r#"
+                    SELECT id, foundation_date, name, description, url, sector, created_at, updated_at, version, deleted_at
+                    FROM company
+                    WHERE deleted_at IS NULL
+                        AND ($1::TIMESTAMPTZ IS NULL OR (created_at, id) < ($1, $2::UUID))
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $3
+                    "#,
+                    after.map(|ts| ts.convert_to_offset()),
+                    after_id,
+                    page.fetch_limit()
+                )
+                .fetch_all(&mut *conn)
+                .await
+            }
+            CompanySort::Name => {
+                let after = page.cursor.as_ref().map(|cursor| cursor.key.as_str());
+                sqlx::query_as!(
+                    CompanyRow,
+                    r#"
+                    SELECT id, foundation_date, name, description, url, sector, created_at, updated_at, version, deleted_at
+                    FROM company
+                    WHERE deleted_at IS NULL
+                        AND ($1::TEXT IS NULL OR (name COLLATE "C", id) > ($1 COLLATE "C", $2::UUID))
+                    ORDER BY name COLLATE "C", id
+                    LIMIT $3
+                    "#,
+                    after,
+                    after_id,
+                    page.fetch_limit()
+                )
+                .fetch_all(&mut *conn)
+                .await
+            }
         }
+        .context("Error fetching a page of companies from database")?;
+
+        let companies = rows
+            .into_iter()
+            .map(Company::try_from)
+            .collect::<Result<Vec<Company>, CorruptRow>>()
+            .context("Error mapping rows to company entities")?;
+
+        Ok(Page::from_rows(companies, page, |company| {
+            page.sort.cursor_key(company)
+        }))
     }
 }
@@ -0,0 +1,163 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::adapter::driven::storage::db::error::CorruptRow;
+use crate::adapter::driven::storage::db::executor::Executor;
+use crate::core::domain::entity::employment::Employment;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::{CompanyId, EmploymentId, UserId};
+use crate::core::domain::valueobject::position::Position;
+use crate::core::port::employment::{EmploymentRepo, EmploymentSort};
+use crate::core::port::page::{Page, PageRequest};
+
+/// An `employment` row as stored.
+struct EmploymentRow {
+    id: Uuid,
+    user_id: Uuid,
+    company_id: Uuid,
+    position: String,
+    created_at: OffsetDateTime,
+}
+
+impl TryFrom<EmploymentRow> for Employment {
+    type Error = CorruptRow;
+
+    fn try_from(row: EmploymentRow) -> Result<Self, Self::Error> {
+        let position = Position::try_from(row.position.as_str())
+            .map_err(|e| CorruptRow::new("employment", row.id, e))?;
+
+        Ok(Employment {
+            id: EmploymentId::from(row.id),
+            user_id: UserId::from(row.user_id),
+            company_id: CompanyId::from(row.company_id),
+            position,
+            created_at: Timestamp::from(row.created_at),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EmploymentRepository {
+    db: Executor,
+}
+
+impl EmploymentRepository {
+    pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        EmploymentRepository {
+            db: Executor::Pool(db),
+        }
+    }
+
+    pub fn with_executor(db: Executor) -> Self {
+        EmploymentRepository { db }
+    }
+}
+
+#[async_trait]
+impl EmploymentRepo for EmploymentRepository {
+    async fn save(
+        &self,
+        user_id: UserId,
+        company_id: CompanyId,
+        position: &Position,
+    ) -> Result<EmploymentId, Error> {
+        let mut conn = self.db.acquire().await?;
+        let id = EmploymentId::generate();
+        let saved_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO employment (id, user_id, company_id, position, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            id.as_uuid(),
+            user_id.as_uuid(),
+            company_id.as_uuid(),
+            position.as_str(),
+            Timestamp::now_utc().convert_to_offset(),
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .context("Error saving employment to database")?;
+
+        Ok(EmploymentId::from(saved_id))
+    }
+
+    async fn find_position(
+        &self,
+        user_id: UserId,
+        company_id: CompanyId,
+    ) -> Result<Option<Position>, Error> {
+        let mut conn = self.db.acquire().await?;
+        let position = sqlx::query_scalar!(
+            r#"
+            SELECT employment.position
+            FROM employment
+            JOIN "user" ON "user".id = employment.user_id AND "user".deleted_at IS NULL
+            JOIN company ON company.id = employment.company_id AND company.deleted_at IS NULL
+            WHERE employment.user_id = $1 AND employment.company_id = $2
+            "#,
+            user_id.as_uuid(),
+            company_id.as_uuid()
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Error querying employment position")?;
+
+        let position = position
+            .map(|position| {
+                Position::try_from(position.as_str()).map_err(|e| {
+                    CorruptRow::new("employment", format!("({}, {})", user_id, company_id), e)
+                })
+            })
+            .transpose()?;
+
+        Ok(position)
+    }
+
+    async fn find_by_company(
+        &self,
+        company_id: CompanyId,
+        page: &PageRequest<EmploymentSort>,
+    ) -> Result<Page<Employment>, Error> {
+        let after = page.cursor.as_ref().and_then(|cursor| cursor.timestamp());
+        let after_id = page.cursor.as_ref().map(|cursor| cursor.id);
+
+        let mut conn = self.db.acquire().await?;
+        let rows = match page.sort {
+            EmploymentSort::CreatedAt => sqlx::query_as!(
+                EmploymentRow,
+                r#"
+                SELECT employment.id, employment.user_id, employment.company_id, employment.position, employment.created_at
+                FROM employment
+                JOIN "user" ON "user".id = employment.user_id AND "user".deleted_at IS NULL
+                JOIN company ON company.id = employment.company_id AND company.deleted_at IS NULL
+                WHERE employment.company_id = $1
+                    AND ($2::TIMESTAMPTZ IS NULL OR (employment.created_at, employment.id) < ($2, $3::UUID))
+                ORDER BY employment.created_at DESC, employment.id DESC
+                LIMIT $4
+                "#,
+                company_id.as_uuid(),
+                after.map(|ts| ts.convert_to_offset()),
+                after_id,
+                page.fetch_limit()
+            )
+            .fetch_all(&mut *conn)
+            .await
+            .context("Error fetching a page of employments from database")?,
+        };
+
+        let employments = rows
+            .into_iter()
+            .map(Employment::try_from)
+            .collect::<Result<Vec<Employment>, CorruptRow>>()?;
+
+        Ok(Page::from_rows(employments, page, |employment| {
+            page.sort.cursor_key(employment)
+        }))
+    }
+}
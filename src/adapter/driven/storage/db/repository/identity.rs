@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+
+use crate::core::domain::entity::identity::UserIdentity;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::identity::IdentityRepo;
+
+#[derive(Debug, Clone)]
+pub struct IdentityRepository {
+    db: Arc<Pool<Postgres>>,
+}
+
+impl IdentityRepository {
+    pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        IdentityRepository { db }
+    }
+}
+
+#[async_trait]
+impl IdentityRepo for IdentityRepository {
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, Error> {
+        let row = sqlx::query!(
+            r#"
+						SELECT user_id, provider, subject, email, created_at
+						FROM "user_identity"
+						WHERE provider = $1 AND subject = $2
+						"#,
+            provider,
+            subject
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        let identity = row.map(|row| UserIdentity {
+            user_id: UserId::from(row.user_id),
+            provider: row.provider,
+            subject: row.subject,
+            email: row.email,
+            created_at: Timestamp::from(row.created_at),
+        });
+
+        Ok(identity)
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<UserIdentity>, Error> {
+        let rows = sqlx::query!(
+            r#"
+						SELECT user_id, provider, subject, email, created_at
+						FROM "user_identity"
+						WHERE user_id = $1
+						ORDER BY provider
+						"#,
+            user_id.as_uuid()
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        let identities = rows
+            .into_iter()
+            .map(|row| UserIdentity {
+                user_id: UserId::from(row.user_id),
+                provider: row.provider,
+                subject: row.subject,
+                email: row.email,
+                created_at: Timestamp::from(row.created_at),
+            })
+            .collect();
+
+        Ok(identities)
+    }
+
+    async fn save(&self, identity: &UserIdentity) -> Result<UserIdentity, Error> {
+        let row = sqlx::query!(
+            r#"
+						INSERT INTO "user_identity" (user_id, provider, subject, email, created_at)
+						VALUES ($1, $2, $3, $4, $5)
+						RETURNING user_id, provider, subject, email, created_at
+						"#,
+            identity.user_id.as_uuid(),
+            identity.provider,
+            identity.subject,
+            identity.email,
+            identity.created_at.convert_to_offset(),
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(UserIdentity {
+            user_id: UserId::from(row.user_id),
+            provider: row.provider,
+            subject: row.subject,
+            email: row.email,
+            created_at: Timestamp::from(row.created_at),
+        })
+    }
+
+    async fn delete(&self, user_id: UserId, provider: &str) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+						DELETE FROM "user_identity"
+						WHERE user_id = $1 AND provider = $2
+						"#,
+            user_id.as_uuid(),
+            provider
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+
+use crate::core::domain::entity::mfa::{UsedCode, UserMfa};
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::mfa::MfaRepo;
+
+#[derive(Debug, Clone)]
+pub struct MfaRepository {
+    db: Arc<Pool<Postgres>>,
+}
+
+impl MfaRepository {
+    pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        MfaRepository { db }
+    }
+}
+
+#[async_trait]
+impl MfaRepo for MfaRepository {
+    async fn find_by_user(&self, user_id: UserId) -> Result<Option<UserMfa>, Error> {
+        let row = sqlx::query!(
+            r#"
+						SELECT user_id, totp_secret, enabled_at, last_used_step, recovery_codes, created_at, updated_at
+						FROM "user_mfa"
+						WHERE user_id = $1
+						"#,
+            user_id.as_uuid()
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        let mfa = row.map(|row| UserMfa {
+            user_id: UserId::from(row.user_id),
+            totp_secret: row.totp_secret,
+            enabled_at: row.enabled_at.map(Timestamp::from),
+            last_used_step: row.last_used_step,
+            recovery_codes: row.recovery_codes,
+            created_at: Timestamp::from(row.created_at),
+            updated_at: Timestamp::from(row.updated_at),
+        });
+
+        Ok(mfa)
+    }
+
+    async fn save(&self, mfa: &UserMfa) -> Result<UserMfa, Error> {
+        let row = sqlx::query!(
+            r#"
+						INSERT INTO "user_mfa" (user_id, totp_secret, enabled_at, last_used_step, recovery_codes, created_at, updated_at)
+						VALUES ($1, $2, $3, $4, $5, $6, $7)
+						ON CONFLICT (user_id) DO UPDATE
+						SET
+								totp_secret = EXCLUDED.totp_secret,
+								enabled_at = EXCLUDED.enabled_at,
+								last_used_step = EXCLUDED.last_used_step,
+								recovery_codes = EXCLUDED.recovery_codes,
+								updated_at = EXCLUDED.updated_at
+						RETURNING user_id, totp_secret, enabled_at, last_used_step, recovery_codes, created_at, updated_at
+						"#,
+            mfa.user_id.as_uuid(),
+            mfa.totp_secret,
+            mfa.enabled_at.as_ref().map(|ts| ts.convert_to_offset()),
+            mfa.last_used_step,
+            &mfa.recovery_codes,
+            mfa.created_at.convert_to_offset(),
+            mfa.updated_at.convert_to_offset(),
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(UserMfa {
+            user_id: UserId::from(row.user_id),
+            totp_secret: row.totp_secret,
+            enabled_at: row.enabled_at.map(Timestamp::from),
+            last_used_step: row.last_used_step,
+            recovery_codes: row.recovery_codes,
+            created_at: Timestamp::from(row.created_at),
+            updated_at: Timestamp::from(row.updated_at),
+        })
+    }
+
+    async fn record_use(&self, user_id: UserId, code: &UsedCode) -> Result<bool, Error> {
+        let now = Timestamp::now_utc().convert_to_offset();
+        let result = match code {
+            UsedCode::TotpStep(step) => {
+                sqlx::query!(
+                    r#"
+						UPDATE "user_mfa"
+						SET last_used_step = $2, updated_at = $3
+						WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)
+						"#,
+                    user_id.as_uuid(),
+                    step,
+                    now,
+                )
+                .execute(&*self.db)
+                .await?
+            }
+            UsedCode::RecoveryCode(hash) => {
+                sqlx::query!(
+                    r#"
+						UPDATE "user_mfa"
+						SET recovery_codes = array_remove(recovery_codes, $2), updated_at = $3
+						WHERE user_id = $1 AND $2 = ANY(recovery_codes)
+						"#,
+                    user_id.as_uuid(),
+                    hash,
+                    now,
+                )
+                .execute(&*self.db)
+                .await?
+            }
+        };
+
+        Ok(result.rows_affected() == 1)
+    }
+}
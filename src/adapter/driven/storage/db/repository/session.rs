@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::core::domain::entity::session::Session;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::session::SessionRepo;
+
+#[derive(Debug, Clone)]
+pub struct SessionRepository {
+    db: Arc<Pool<Postgres>>,
+}
+
+impl SessionRepository {
+    pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        SessionRepository { db }
+    }
+}
+
+#[async_trait]
+impl SessionRepo for SessionRepository {
+    async fn save(&self, session: &Session) -> Result<Session, Error> {
+        let row = sqlx::query!(
+            r#"
+						INSERT INTO "session" (id, user_id, refresh_token_hash, user_agent, ip, mfa, created_at, last_used_at, expires_at, revoked_at)
+						VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+						RETURNING id, user_id, refresh_token_hash, user_agent, ip, mfa, created_at, last_used_at, expires_at, revoked_at
+						"#,
+            session.id,
+            session.user_id.as_uuid(),
+            session.refresh_token_hash,
+            session.user_agent,
+            session.ip,
+            session.mfa,
+            session.created_at.convert_to_offset(),
+            session.last_used_at.convert_to_offset(),
+            session.expires_at.convert_to_offset(),
+            session.revoked_at.as_ref().map(|ts| ts.convert_to_offset()),
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(Session {
+            id: row.id,
+            user_id: UserId::from(row.user_id),
+            refresh_token_hash: row.refresh_token_hash,
+            user_agent: row.user_agent,
+            ip: row.ip,
+            mfa: row.mfa,
+            created_at: Timestamp::from(row.created_at),
+            last_used_at: Timestamp::from(row.last_used_at),
+            expires_at: Timestamp::from(row.expires_at),
+            revoked_at: row.revoked_at.map(Timestamp::from),
+        })
+    }
+
+    async fn rotate(&self, session: &Session, previous_hash: &str) -> Result<bool, Error> {
+        let result = sqlx::query!(
+            r#"
+						UPDATE "session"
+						SET
+								refresh_token_hash = $3,
+								user_agent = $4,
+								ip = $5,
+								last_used_at = $6,
+								expires_at = $7
+						WHERE id = $1 AND refresh_token_hash = $2 AND revoked_at IS NULL
+						"#,
+            session.id,
+            previous_hash,
+            session.refresh_token_hash,
+            session.user_agent,
+            session.ip,
+            session.last_used_at.convert_to_offset(),
+            session.expires_at.convert_to_offset(),
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke(&self, id: Uuid) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+						UPDATE "session"
+						SET revoked_at = COALESCE(revoked_at, now())
+						WHERE id = $1
+						"#,
+            id
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Session>, Error> {
+        let row = sqlx::query!(
+            r#"
+						SELECT id, user_id, refresh_token_hash, user_agent, ip, mfa, created_at, last_used_at, expires_at, revoked_at
+						FROM "session"
+						WHERE id = $1
+						"#,
+            id
+        )
+        .fetch_optional(&*self.db)
+        .await?;
+
+        let session = row.map(|row| Session {
+            id: row.id,
+            user_id: UserId::from(row.user_id),
+            refresh_token_hash: row.refresh_token_hash,
+            user_agent: row.user_agent,
+            ip: row.ip,
+            mfa: row.mfa,
+            created_at: Timestamp::from(row.created_at),
+            last_used_at: Timestamp::from(row.last_used_at),
+            expires_at: Timestamp::from(row.expires_at),
+            revoked_at: row.revoked_at.map(Timestamp::from),
+        });
+
+        Ok(session)
+    }
+
+    async fn find_active_by_user(&self, user_id: UserId) -> Result<Vec<Session>, Error> {
+        let rows = sqlx::query!(
+            r#"
+						SELECT id, user_id, refresh_token_hash, user_agent, ip, mfa, created_at, last_used_at, expires_at, revoked_at
+						FROM "session"
+						WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > now()
+						ORDER BY last_used_at DESC
+						"#,
+            user_id.as_uuid()
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        let sessions = rows
+            .into_iter()
+            .map(|row| Session {
+                id: row.id,
+                user_id: UserId::from(row.user_id),
+                refresh_token_hash: row.refresh_token_hash,
+                user_agent: row.user_agent,
+                ip: row.ip,
+                mfa: row.mfa,
+                created_at: Timestamp::from(row.created_at),
+                last_used_at: Timestamp::from(row.last_used_at),
+                expires_at: Timestamp::from(row.expires_at),
+                revoked_at: row.revoked_at.map(Timestamp::from),
+            })
+            .collect();
+
+        Ok(sessions)
+    }
+}
@@ -1,16 +1,69 @@
 use std::sync::Arc;
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use async_trait::async_trait;
 use sqlx::{Pool, Postgres};
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use crate::adapter::driven::storage::db::error::{already_taken, CorruptRow};
 use crate::core::domain::entity::user::User;
 use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
 use crate::core::domain::valueobject::password::HashedPassword;
 use crate::core::domain::valueobject::role::Role;
-use crate::core::port::user::UserRepo;
+use crate::core::port::error::VersionConflict;
+use crate::core::port::page::{Page, PageRequest};
+use crate::core::port::user::{UserFilter, UserRepo, UserSort};
+
+/// A `"user"` row as stored.
+struct UserRow {
+    id: Uuid,
+    name: String,
+    surname: String,
+    email: String,
+    role: String,
+    password_hash: String,
+    reset_token: Option<String>,
+    reset_sent_at: Option<OffsetDateTime>,
+    email_verification_token: Option<String>,
+    email_verification_sent_at: Option<OffsetDateTime>,
+    email_verified_at: Option<OffsetDateTime>,
+    blocked_at: Option<OffsetDateTime>,
+    created_at: OffsetDateTime,
+    updated_at: OffsetDateTime,
+    version: i32,
+    deleted_at: Option<OffsetDateTime>,
+}
+
+impl TryFrom<UserRow> for User {
+    type Error = CorruptRow;
+
+    fn try_from(row: UserRow) -> Result<Self, Self::Error> {
+        let role = Role::try_from(row.role).map_err(|e| CorruptRow::new("user", row.id, e))?;
+        let email = Email::try_from(row.email).map_err(|e| CorruptRow::new("user", row.id, e))?;
+
+        Ok(User {
+            id: Some(UserId::from(row.id)),
+            name: row.name,
+            surname: row.surname,
+            email,
+            role,
+            password_hash: HashedPassword::from(row.password_hash),
+            reset_token: row.reset_token,
+            reset_sent_at: row.reset_sent_at.map(Timestamp::from),
+            email_verification_token: row.email_verification_token,
+            email_verification_sent_at: row.email_verification_sent_at.map(Timestamp::from),
+            email_verified_at: row.email_verified_at.map(Timestamp::from),
+            blocked_at: row.blocked_at.map(Timestamp::from),
+            created_at: Timestamp::from(row.created_at),
+            updated_at: Timestamp::from(row.updated_at),
+            version: row.version,
+            deleted_at: row.deleted_at.map(Timestamp::from),
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct UserRepository {
@@ -21,21 +74,35 @@ impl UserRepository {
     pub fn new(db: Arc<Pool<Postgres>>) -> Self {
         UserRepository { db }
     }
+
+    async fn exists(&self, id: UserId) -> Result<bool, Error> {
+        let exists = sqlx::query_scalar!(
+            r#"
+						SELECT EXISTS (SELECT 1 FROM "user" WHERE id = $1 AND deleted_at IS NULL) AS "exists!"
+						"#,
+            id.as_uuid()
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
+        Ok(exists)
+    }
 }
 
 #[async_trait]
 impl UserRepo for UserRepository {
     async fn save(&self, user: &User) -> Result<User, Error> {
-        let result = sqlx::query!(
+        let result = sqlx::query_as!(
+            UserRow,
         r#"
         INSERT INTO "user" (id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
-        RETURNING id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at
+        VALUES (COALESCE($1, uuid_generate_v4()), $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+        RETURNING id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
         "#,
-        user.id,
+        user.id.map(Uuid::from),
         user.name,
         user.surname,
-        user.email,
+        user.email.as_str(),
         user.role.as_string(),
         user.password_hash.as_string(),
         None as Option<String>,
@@ -48,30 +115,14 @@ impl UserRepo for UserRepository {
         Timestamp::now_utc().convert_to_offset(),
     )
           .fetch_one(&*self.db)
-          .await?;
-
-        let saved_user = User {
-            id: Some(result.id),
-            name: result.name,
-            surname: result.surname,
-            email: result.email,
-            role: Role::from(result.role),
-            password_hash: HashedPassword::from(result.password_hash),
-            reset_token: result.reset_token,
-            reset_sent_at: result.reset_sent_at.map(Timestamp::from),
-            email_verification_token: result.email_verification_token,
-            email_verification_sent_at: result.email_verification_sent_at.map(Timestamp::from),
-            email_verified_at: result.email_verified_at.map(Timestamp::from),
-            blocked_at: result.blocked_at.map(Timestamp::from),
-            created_at: Timestamp::from(result.created_at),
-            updated_at: Timestamp::from(result.updated_at),
-        };
+          .await
+          .map_err(|e| already_taken(e, "user", &["email"]))?;
 
-        Ok(saved_user)
+        Ok(User::try_from(result)?)
     }
-    async fn update(&self, id_str: &str, user: &User) -> Result<User, Error> {
-        let id = Uuid::parse_str(id_str)?;
-        let result = sqlx::query!(
+    async fn update(&self, id: UserId, user: &User) -> Result<User, Error> {
+        let result = sqlx::query_as!(
+            UserRow,
             r#"
 						UPDATE "user"
 						SET
@@ -85,15 +136,16 @@ impl UserRepo for UserRepository {
 								email_verification_token = COALESCE($9, email_verification_token),
 								email_verification_sent_at = COALESCE($10, email_verification_sent_at),
 								email_verified_at = COALESCE($11, email_verified_at),
-								blocked_at = COALESCE($12, blocked_at),
-								updated_at = COALESCE($13, updated_at)
-						WHERE id = $1
-						RETURNING id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at
+								blocked_at = $12,
+								updated_at = COALESCE($13, updated_at),
+								version = version + 1
+						WHERE id = $1 AND version = $14 AND deleted_at IS NULL
+						RETURNING id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
 						"#,
-            id,
+            id.as_uuid(),
             user.name,
             user.surname,
-            user.email,
+            user.email.as_str(),
             user.role.as_string(),
             user.password_hash.as_string(),
             user.reset_token.as_ref(),
@@ -102,142 +154,227 @@ impl UserRepo for UserRepository {
             user.email_verification_sent_at.as_ref().map(|ts| ts.convert_to_offset()),
             user.email_verified_at.as_ref().map(|ts| ts.convert_to_offset()),
             user.blocked_at.as_ref().map(|ts| ts.convert_to_offset()),
-            Timestamp::now_utc().convert_to_offset()
+            Timestamp::now_utc().convert_to_offset(),
+            user.version
         )
-          .fetch_one(&*self.db)
-          .await?;
+          .fetch_optional(&*self.db)
+          .await
+          .map_err(|e| already_taken(e, "user", &["email"]))?;
 
-        let updated_user = User {
-            id: Some(result.id),
-            name: result.name,
-            surname: result.surname,
-            email: result.email,
-            role: Role::from(result.role),
-            password_hash: HashedPassword::from(result.password_hash),
-            reset_token: result.reset_token,
-            reset_sent_at: result.reset_sent_at.map(Timestamp::from),
-            email_verification_token: result.email_verification_token,
-            email_verification_sent_at: result.email_verification_sent_at.map(Timestamp::from),
-            email_verified_at: result.email_verified_at.map(Timestamp::from),
-            blocked_at: result.blocked_at.map(Timestamp::from),
-            created_at: Timestamp::from(result.created_at),
-            updated_at: Timestamp::from(result.updated_at),
-        };
+        // No row matched: the user is gone, or was changed since it was read.
+        match result {
+            Some(row) => Ok(User::try_from(row)?),
+            None if self.exists(id).await? => {
+                Err(VersionConflict::new("user", id, user.version).into())
+            }
+            None => Err(anyhow!("user {} not found", id)),
+        }
+    }
 
-        Ok(updated_user)
+    async fn delete(&self, id: UserId) -> Result<(), Error> {
+        sqlx::query!(
+            r#"
+						UPDATE "user"
+						SET deleted_at = $2
+						WHERE id = $1 AND deleted_at IS NULL
+						"#,
+            id.as_uuid(),
+            Timestamp::now_utc().convert_to_offset()
+        )
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
     }
 
-    async fn delete(&self, id_str: &str) -> Result<(), Error> {
-        let id = Uuid::parse_str(id_str)?;
+    async fn restore(&self, id: UserId) -> Result<Option<User>, Error> {
+        let row = sqlx::query_as!(
+            UserRow,
+            r#"
+						UPDATE "user"
+						SET deleted_at = NULL, updated_at = $2, version = version + 1
+						WHERE id = $1 AND deleted_at IS NOT NULL
+						RETURNING id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
+						"#,
+            id.as_uuid(),
+            Timestamp::now_utc().convert_to_offset()
+        )
+          .fetch_optional(&*self.db)
+          .await?;
 
-        sqlx::query!(
+        Ok(row.map(User::try_from).transpose()?)
+    }
+
+    async fn purge_deleted(&self, before: &Timestamp) -> Result<u64, Error> {
+        let result = sqlx::query!(
             r#"
 						DELETE FROM "user"
-						WHERE id = $1
+						WHERE deleted_at < $1
 						"#,
-            id
+            before.convert_to_offset()
         )
         .execute(&*self.db)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
     async fn find_all(&self) -> Result<Vec<User>, Error> {
-        let rows = sqlx::query!(
+        let rows = sqlx::query_as!(
+            UserRow,
             r#"
-						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at
+						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
 						FROM "user"
+						WHERE deleted_at IS NULL
 						"#
         )
           .fetch_all(&*self.db)
           .await?;
 
-        let users = rows
-            .into_iter()
-            .map(|row| User {
-                id: Some(row.id),
-                name: row.name,
-                surname: row.surname,
-                email: row.email,
-                role: Role::from(row.role),
-                password_hash: HashedPassword::from(row.password_hash),
-                reset_token: row.reset_token,
-                reset_sent_at: row.reset_sent_at.map(Timestamp::from),
-                email_verification_token: row.email_verification_token,
-                email_verification_sent_at: row.email_verification_sent_at.map(Timestamp::from),
-                email_verified_at: row.email_verified_at.map(Timestamp::from),
-                blocked_at: row.blocked_at.map(Timestamp::from),
-                created_at: Timestamp::from(row.created_at),
-                updated_at: Timestamp::from(row.updated_at),
-            })
-            .collect();
-
-        Ok(users)
+        rows.into_iter()
+            .map(|row| User::try_from(row).map_err(Error::from))
+            .collect()
     }
 
-    async fn find_by_id(&self, id_str: &str) -> Result<Option<User>, Error> {
-        let id = Uuid::parse_str(id_str)?;
-        let row = sqlx::query!(
+    async fn find_by_id(&self, id: UserId) -> Result<Option<User>, Error> {
+        let row = sqlx::query_as!(
+            UserRow,
             r#"
-						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at
+						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
 						FROM "user"
-						WHERE id = $1
+						WHERE id = $1 AND deleted_at IS NULL
 						"#,
-            id
+            id.as_uuid()
         )
           .fetch_optional(&*self.db)
           .await?;
 
-        let user = row.map(|row| User {
-            id: Some(row.id),
-            name: row.name,
-            surname: row.surname,
-            email: row.email,
-            role: Role::from(row.role),
-            password_hash: HashedPassword::from(row.password_hash),
-            reset_token: row.reset_token,
-            reset_sent_at: row.reset_sent_at.map(Timestamp::from),
-            email_verification_token: row.email_verification_token,
-            email_verification_sent_at: row.email_verification_sent_at.map(Timestamp::from),
-            email_verified_at: row.email_verified_at.map(Timestamp::from),
-            blocked_at: row.blocked_at.map(Timestamp::from),
-            created_at: Timestamp::from(row.created_at),
-            updated_at: Timestamp::from(row.updated_at),
-        });
-
-        Ok(user)
+        Ok(row.map(User::try_from).transpose()?)
     }
 
-    async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error> {
-        let row = sqlx::query!(
+    async fn find_by_email(&self, email: &Email) -> Result<Option<User>, Error> {
+        let row = sqlx::query_as!(
+            UserRow,
             r#"
-						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at
+						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
 						FROM "user"
-						WHERE email = $1
+						WHERE email = $1 AND deleted_at IS NULL
 						"#,
-            email
+            email.as_str()
         )
           .fetch_optional(&*self.db)
           .await?;
 
-        let user = row.map(|row| User {
-            id: Some(row.id),
-            name: row.name,
-            surname: row.surname,
-            email: row.email,
-            role: Role::from(row.role),
-            password_hash: HashedPassword::from(row.password_hash),
-            reset_token: row.reset_token,
-            reset_sent_at: row.reset_sent_at.map(Timestamp::from),
-            email_verification_token: row.email_verification_token,
-            email_verification_sent_at: row.email_verification_sent_at.map(Timestamp::from),
-            email_verified_at: row.email_verified_at.map(Timestamp::from),
-            blocked_at: row.blocked_at.map(Timestamp::from),
-            created_at: Timestamp::from(row.created_at),
-            updated_at: Timestamp::from(row.updated_at),
-        });
+        Ok(row.map(User::try_from).transpose()?)
+    }
+
+    async fn find_by_filter(
+        &self,
+        filter: &UserFilter,
+        page: &PageRequest<UserSort>,
+    ) -> Result<Page<User>, Error> {
+        let after_id = page.cursor.as_ref().map(|cursor| cursor.id);
+        let rows = match page.sort {
+            UserSort::CreatedAt => {
+                let after = page.cursor.as_ref().and_then(|cursor| cursor.timestamp());
+                sqlx::query_as!(
+                    UserRow,
+                    r#"
+						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
+						FROM "user"
+						WHERE deleted_at IS NULL
+								AND ($1::TEXT IS NULL OR role = $1)
+								AND ($2::BOOL IS NULL OR (email_verified_at IS NOT NULL) = $2)
+								AND ($3::BOOL IS NULL OR (blocked_at IS NOT NULL) = $3)
+								AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+								AND ($5::TIMESTAMPTZ IS NULL OR created_at < $5)
+								AND ($6::TIMESTAMPTZ IS NULL OR (created_at, id) < ($6, $7::UUID))
+						ORDER BY created_at DESC, id DESC
+						LIMIT $8
+						"#,
+                    filter.role.as_ref().map(|role| role.as_string()),
+                    filter.verified,
+                    filter.blocked,
+                    filter
+                        .created_from
+                        .as_ref()
+                        .map(|ts| ts.convert_to_offset()),
+                    filter.created_to.as_ref().map(|ts| ts.convert_to_offset()),
+                    after.map(|ts| ts.convert_to_offset()),
+                    after_id,
+                    page.fetch_limit()
+                )
+                .fetch_all(&*self.db)
+                .await?
+            }
+            UserSort::Email => {
+                let after = page.cursor.as_ref().map(|cursor| cursor.key.as_str());
+                sqlx::query_as!(
+                    UserRow,
+                    r#"
+						SELECT id, name, surname, email, role, password_hash, reset_token, reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at, blocked_at, created_at, updated_at, version, deleted_at
+						FROM "user"
+						WHERE deleted_at IS NULL
+								AND ($1::TEXT IS NULL OR role = $1)
+								AND ($2::BOOL IS NULL OR (email_verified_at IS NOT NULL) = $2)
+								AND ($3::BOOL IS NULL OR (blocked_at IS NOT NULL) = $3)
+								AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+								AND ($5::TIMESTAMPTZ IS NULL OR created_at < $5)
+								AND ($6::TEXT IS NULL OR (email COLLATE "C", id) > ($6 COLLATE "C", $7::UUID))
+						ORDER BY email COLLATE "C", id
+						LIMIT $8
+						"#,
+                    filter.role.as_ref().map(|role| role.as_string()),
+                    filter.verified,
+                    filter.blocked,
+                    filter
+                        .created_from
+                        .as_ref()
+                        .map(|ts| ts.convert_to_offset()),
+                    filter.created_to.as_ref().map(|ts| ts.convert_to_offset()),
+                    after,
+                    after_id,
+                    page.fetch_limit()
+                )
+                .fetch_all(&*self.db)
+                .await?
+            }
+        };
+
+        let users = rows
+            .into_iter()
+            .map(User::try_from)
+            .collect::<Result<Vec<User>, CorruptRow>>()?;
+
+        Ok(Page::from_rows(users, page, |user| {
+            page.sort.cursor_key(user)
+        }))
+    }
+
+    async fn count_by_filter(&self, filter: &UserFilter) -> Result<i64, Error> {
+        let count = sqlx::query_scalar!(
+            r#"
+						SELECT COUNT(*) AS "count!"
+						FROM "user"
+						WHERE deleted_at IS NULL
+								AND ($1::TEXT IS NULL OR role = $1)
+								AND ($2::BOOL IS NULL OR (email_verified_at IS NOT NULL) = $2)
+								AND ($3::BOOL IS NULL OR (blocked_at IS NOT NULL) = $3)
+								AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+								AND ($5::TIMESTAMPTZ IS NULL OR created_at < $5)
+						"#,
+            filter.role.as_ref().map(|role| role.as_string()),
+            filter.verified,
+            filter.blocked,
+            filter
+                .created_from
+                .as_ref()
+                .map(|ts| ts.convert_to_offset()),
+            filter.created_to.as_ref().map(|ts| ts.convert_to_offset()),
+        )
+        .fetch_one(&*self.db)
+        .await?;
 
-        Ok(user)
+        Ok(count)
     }
 }
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres, Transaction};
+use tokio::sync::Mutex;
+
+use crate::adapter::driven::storage::db::executor::Executor;
+use crate::adapter::driven::storage::db::repository::company::CompanyRepository;
+use crate::adapter::driven::storage::db::repository::employment::EmploymentRepository;
+use crate::core::port::company::CompanyRepo;
+use crate::core::port::employment::EmploymentRepo;
+use crate::core::port::unit_of_work::{UnitOfWork, UnitOfWorkFactory};
+
+/// Starts units of work as database transactions.
+#[derive(Debug, Clone)]
+pub struct PgUnitOfWorkFactory {
+    db: Arc<Pool<Postgres>>,
+}
+
+impl PgUnitOfWorkFactory {
+    pub fn new(db: Arc<Pool<Postgres>>) -> Self {
+        PgUnitOfWorkFactory { db }
+    }
+}
+
+#[async_trait]
+impl UnitOfWorkFactory for PgUnitOfWorkFactory {
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork>, Error> {
+        let tx = Arc::new(Mutex::new(Some(self.db.begin().await?)));
+
+        Ok(Box::new(PgUnitOfWork {
+            companies: CompanyRepository::with_executor(Executor::Transaction(Arc::clone(&tx))),
+            employments: EmploymentRepository::with_executor(Executor::Transaction(Arc::clone(
+                &tx,
+            ))),
+            tx,
+        }))
+    }
+}
+
+/// Repositories on one database transaction. Dropped without a commit, the
+/// transaction is rolled back.
+pub struct PgUnitOfWork {
+    tx: Arc<Mutex<Option<Transaction<'static, Postgres>>>>,
+    companies: CompanyRepository,
+    employments: EmploymentRepository,
+}
+
+impl PgUnitOfWork {
+    async fn finish(&self) -> Result<Transaction<'static, Postgres>, Error> {
+        self.tx
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| anyhow!("transaction is already finished"))
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for PgUnitOfWork {
+    fn companies(&self) -> &dyn CompanyRepo {
+        &self.companies
+    }
+
+    fn employments(&self) -> &dyn EmploymentRepo {
+        &self.employments
+    }
+
+    async fn commit(self: Box<Self>) -> Result<(), Error> {
+        self.finish().await?.commit().await?;
+
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<(), Error> {
+        self.finish().await?.rollback().await?;
+
+        Ok(())
+    }
+}
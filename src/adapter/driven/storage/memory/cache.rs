@@ -1,3 +1,4 @@
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::hash::Hash;
 
@@ -28,12 +29,67 @@ where
         writer.insert(key, value);
     }
 
+    /// Adds `value` unless `key` is present. Returns whether it was added.
+    pub async fn add_new(&self, key: K, value: V) -> bool {
+        let mut writer = self.items.write().await;
+
+        match writer.entry(key) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                true
+            }
+        }
+    }
+
+    /// Applies `f` to the value of `key`, inserting `default` first if the key
+    /// is missing, and returns the updated value. Atomic with respect to the
+    /// other methods.
+    pub async fn update<F>(&self, key: K, default: V, f: F) -> V
+    where
+        F: FnOnce(&mut V),
+    {
+        let mut writer = self.items.write().await;
+        let value = writer.entry(key).or_insert(default);
+
+        f(value);
+        value.to_owned()
+    }
+
+    /// Applies `f` to the value of `key`, if present, and returns its result.
+    /// Atomic with respect to the other methods.
+    pub async fn modify<F, R>(&self, key: &K, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut V) -> R,
+    {
+        let mut writer = self.items.write().await;
+
+        writer.get_mut(key).map(f)
+    }
+
     pub async fn remove(&self, key: K) {
         let mut writer = self.items.write().await;
 
         writer.remove(&key);
     }
 
+    /// Removes `key` and returns its value.
+    pub async fn take(&self, key: &K) -> Option<V> {
+        let mut writer = self.items.write().await;
+
+        writer.remove(key)
+    }
+
+    /// Keeps only the entries for which `f` returns true.
+    pub async fn retain<F>(&self, f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut writer = self.items.write().await;
+
+        writer.retain(f);
+    }
+
     pub async fn get(&self, key: &K) -> Option<V> {
         let reader = self.items.read().await;
 
@@ -43,6 +99,29 @@ where
         }
     }
 
+    /// A copy of the cache as it is now.
+    pub async fn copy(&self) -> Self
+    where
+        K: Clone,
+    {
+        let reader = self.items.read().await;
+
+        Self {
+            items: RwLock::new(reader.clone()),
+        }
+    }
+
+    /// Replaces every entry with a copy of the entries of `other`.
+    pub async fn copy_from(&self, other: &Self)
+    where
+        K: Clone,
+    {
+        let items = other.items.read().await.clone();
+        let mut writer = self.items.write().await;
+
+        *writer = items;
+    }
+
     pub async fn get_all(&self) -> Vec<V> {
         let reader = self.items.read().await;
 
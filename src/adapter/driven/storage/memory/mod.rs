@@ -1,3 +1,6 @@
 pub mod cache;
+mod page;
 pub mod redis_connection;
 pub mod repository;
+pub mod store;
+pub mod unit_of_work;
@@ -0,0 +1,47 @@
+use std::cmp::Ordering;
+
+use uuid::Uuid;
+
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::port::page::{Page, PageRequest, SortKey, SortOrder};
+
+/// The page of `rows` that `request` asks for, ordered like the Postgres
+/// repositories order it. `key` gives the sort key value and id of a row,
+/// as in the cursors.
+pub(super) fn keyset_page<T, S: SortKey>(
+    rows: Vec<T>,
+    request: &PageRequest<S>,
+    key: impl Fn(&T) -> (String, Uuid),
+) -> Page<T> {
+    let order = request.sort.order();
+    let mut rows: Vec<((String, Uuid), T)> = rows.into_iter().map(|row| (key(&row), row)).collect();
+    rows.sort_by(|(a, _), (b, _)| compare(order, a, b));
+
+    let after = request
+        .cursor
+        .as_ref()
+        .map(|cursor| (cursor.key.clone(), cursor.id));
+    let limit = usize::try_from(request.fetch_limit()).unwrap_or(usize::MAX);
+    let rows = rows
+        .into_iter()
+        .filter(|(row_key, _)| {
+            after
+                .as_ref()
+                .is_none_or(|after| compare(order, row_key, after) == Ordering::Greater)
+        })
+        .take(limit)
+        .map(|(_, row)| row)
+        .collect();
+
+    Page::from_rows(rows, request, key)
+}
+
+fn compare(order: SortOrder, a: &(String, Uuid), b: &(String, Uuid)) -> Ordering {
+    match order {
+        SortOrder::NewestFirst => {
+            let time = |key: &str| Timestamp::parse_rfc3339(key).ok().map(|ts| ts.datetime);
+            (time(&b.0), b.1).cmp(&(time(&a.0), a.1))
+        }
+        SortOrder::Alphabetical => a.cmp(b),
+    }
+}
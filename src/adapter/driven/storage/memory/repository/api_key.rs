@@ -0,0 +1,91 @@
+use std::cmp::Reverse;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::domain::entity::api_key::ApiKey;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::api_key::ApiKeyRepo;
+
+/// API keys kept in process memory.
+pub struct ApiKeyRepository {
+    cache: MemCache<Uuid, ApiKey>,
+}
+
+impl ApiKeyRepository {
+    pub fn new() -> Self {
+        Self {
+            cache: MemCache::new(),
+        }
+    }
+}
+
+impl Default for ApiKeyRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepo for ApiKeyRepository {
+    async fn save(&self, api_key: &ApiKey) -> Result<ApiKey, Error> {
+        if self.find_by_prefix(&api_key.prefix).await?.is_some() {
+            return Err(anyhow!("API key prefix {} is taken", api_key.prefix));
+        }
+        self.cache.add(api_key.id, api_key.clone()).await;
+
+        Ok(api_key.clone())
+    }
+
+    async fn find_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, Error> {
+        let api_key = self
+            .cache
+            .get_all()
+            .await
+            .into_iter()
+            .find(|api_key| api_key.prefix == prefix);
+
+        Ok(api_key)
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<ApiKey>, Error> {
+        let mut api_keys: Vec<ApiKey> = self
+            .cache
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|api_key| api_key.user_id == user_id && api_key.revoked_at.is_none())
+            .collect();
+        api_keys.sort_by_key(|api_key| Reverse(api_key.created_at.datetime));
+
+        Ok(api_keys)
+    }
+
+    async fn revoke(&self, user_id: UserId, id: Uuid) -> Result<bool, Error> {
+        let revoked = self
+            .cache
+            .modify(&id, |api_key| {
+                if api_key.user_id != user_id || api_key.revoked_at.is_some() {
+                    return false;
+                }
+                api_key.revoked_at = Some(Timestamp::now_utc());
+                true
+            })
+            .await;
+
+        Ok(revoked.unwrap_or(false))
+    }
+
+    async fn touch(&self, id: Uuid) -> Result<(), Error> {
+        self.cache
+            .modify(&id, |api_key| {
+                api_key.last_used_at = Some(Timestamp::now_utc());
+            })
+            .await;
+
+        Ok(())
+    }
+}
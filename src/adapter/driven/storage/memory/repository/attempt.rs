@@ -0,0 +1,237 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use bb8::Pool;
+use sidekiq::redis_rs as redis;
+use sidekiq::RedisConnectionManager;
+use tokio::time::{Duration, Instant};
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::port::throttle::AttemptRepo;
+
+#[derive(Debug, Clone)]
+struct Attempts {
+    failures: u32,
+    forget_at: Instant,
+    locked_until: Option<Instant>,
+}
+
+impl Attempts {
+    fn new() -> Self {
+        Self {
+            failures: 0,
+            forget_at: Instant::now(),
+            locked_until: None,
+        }
+    }
+
+    /// Whether the failures were forgotten and no lock is left, so the entry
+    /// no longer counts for anything.
+    fn is_stale(&self, now: Instant) -> bool {
+        self.forget_at <= now && self.locked_until.is_none_or(|until| until <= now)
+    }
+}
+
+/// Attempt counters for a single instance, kept in process memory. Stale
+/// counters are dropped on each increment, so every email or IP tried does not
+/// stay in memory for good.
+pub struct AttemptRepository {
+    cache: MemCache<String, Attempts>,
+}
+
+impl AttemptRepository {
+    pub fn new() -> Self {
+        Self {
+            cache: MemCache::new(),
+        }
+    }
+}
+
+impl Default for AttemptRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AttemptRepo for AttemptRepository {
+    async fn increment(&self, key: &str, ttl_sec: u64) -> Result<u32, Error> {
+        let now = Instant::now();
+        self.cache
+            .retain(|_, attempts| !attempts.is_stale(now))
+            .await;
+        let attempts = self
+            .cache
+            .update(key.to_string(), Attempts::new(), |attempts| {
+                if attempts.forget_at <= now {
+                    attempts.failures = 0;
+                }
+                attempts.failures = attempts.failures.saturating_add(1);
+                attempts.forget_at = now + Duration::from_secs(ttl_sec);
+            })
+            .await;
+
+        Ok(attempts.failures)
+    }
+
+    async fn lock(&self, key: &str, duration_sec: u64) -> Result<(), Error> {
+        let locked_until = Instant::now() + Duration::from_secs(duration_sec);
+        self.cache
+            .update(key.to_string(), Attempts::new(), |attempts| {
+                attempts.locked_until = Some(locked_until);
+            })
+            .await;
+
+        Ok(())
+    }
+
+    async fn lock_remaining(&self, key: &str) -> Result<Option<u64>, Error> {
+        let remaining = self
+            .cache
+            .get(&key.to_string())
+            .await
+            .and_then(|attempts| attempts.locked_until)
+            .map(|locked_until| locked_until.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+            // Round up so that a lock is never reported as 0 seconds.
+            .map(|remaining| remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0));
+
+        Ok(remaining)
+    }
+
+    async fn clear(&self, key: &str) -> Result<(), Error> {
+        self.cache.remove(key.to_string()).await;
+
+        Ok(())
+    }
+}
+
+/// Attempt counters shared by every instance, kept in Redis.
+pub struct RedisAttemptRepository {
+    pool: Pool<RedisConnectionManager>,
+}
+
+impl RedisAttemptRepository {
+    pub fn new(pool: Pool<RedisConnectionManager>) -> Self {
+        Self { pool }
+    }
+}
+
+fn failures_key(key: &str) -> String {
+    format!("{}:failures", key)
+}
+
+fn lock_key(key: &str) -> String {
+    format!("{}:lock", key)
+}
+
+#[async_trait]
+impl AttemptRepo for RedisAttemptRepository {
+    async fn increment(&self, key: &str, ttl_sec: u64) -> Result<u32, Error> {
+        let mut conn = self.pool.get().await?;
+        let key = failures_key(key);
+
+        let (failures,): (u32,) = redis::pipe()
+            .atomic()
+            .cmd("INCR")
+            .arg(&key)
+            .cmd("EXPIRE")
+            .arg(&key)
+            .arg(ttl_sec)
+            .ignore()
+            .query_async(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(failures)
+    }
+
+    async fn lock(&self, key: &str, duration_sec: u64) -> Result<(), Error> {
+        let mut conn = self.pool.get().await?;
+
+        redis::cmd("SET")
+            .arg(lock_key(key))
+            .arg(1)
+            .arg("EX")
+            .arg(duration_sec)
+            .query_async::<_, ()>(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn lock_remaining(&self, key: &str) -> Result<Option<u64>, Error> {
+        let mut conn = self.pool.get().await?;
+
+        // TTL is -2 for missing keys and -1 for keys without expiry.
+        let ttl: i64 = redis::cmd("TTL")
+            .arg(lock_key(key))
+            .query_async(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(u64::try_from(ttl).ok().filter(|ttl| *ttl > 0))
+    }
+
+    async fn clear(&self, key: &str) -> Result<(), Error> {
+        let mut conn = self.pool.get().await?;
+
+        redis::cmd("DEL")
+            .arg(failures_key(key))
+            .arg(lock_key(key))
+            .query_async::<_, ()>(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn memory_counts_failures_and_locks() {
+        let repository = AttemptRepository::new();
+
+        assert_eq!(repository.increment("key", 60).await.unwrap(), 1);
+        assert_eq!(repository.increment("key", 60).await.unwrap(), 2);
+        assert_eq!(repository.lock_remaining("key").await.unwrap(), None);
+
+        repository.lock("key", 30).await.unwrap();
+        assert_eq!(repository.lock_remaining("key").await.unwrap(), Some(30));
+
+        repository.clear("key").await.unwrap();
+        assert_eq!(repository.lock_remaining("key").await.unwrap(), None);
+        assert_eq!(repository.increment("key", 60).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn memory_forgets_failures_after_ttl() {
+        let repository = AttemptRepository::new();
+
+        repository.increment("key", 0).await.unwrap();
+
+        assert_eq!(repository.increment("key", 0).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn memory_drops_stale_counters() {
+        let repository = AttemptRepository::new();
+
+        repository.increment("forgotten", 0).await.unwrap();
+        repository.increment("locked", 0).await.unwrap();
+        repository.lock("locked", 30).await.unwrap();
+        repository.lock("unlocked", 0).await.unwrap();
+        repository.increment("counting", 60).await.unwrap();
+
+        let mut left: Vec<u32> = repository
+            .cache
+            .get_all()
+            .await
+            .iter()
+            .map(|attempts| attempts.failures)
+            .collect();
+        left.sort();
+        assert_eq!(left, [1, 1]);
+        assert_eq!(repository.lock_remaining("locked").await.unwrap(), Some(30));
+        assert_eq!(repository.increment("counting", 60).await.unwrap(), 2);
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::adapter::driven::storage::memory::page::keyset_page;
+use crate::core::domain::entity::audit::AuditEntry;
+use crate::core::port::audit::{AuditFilter, AuditRepo, AuditSort};
+use crate::core::port::page::{Page, PageRequest};
+
+/// Audit log kept in process memory. Entries can only be appended.
+pub struct AuditRepository {
+    entries: RwLock<Vec<AuditEntry>>,
+}
+
+impl AuditRepository {
+    pub fn new() -> Self {
+        Self {
+            entries: RwLock::new(Vec::new()),
+        }
+    }
+}
+
+impl Default for AuditRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn matches(filter: &AuditFilter, entry: &AuditEntry) -> bool {
+    filter.action.is_none_or(|action| action == entry.action)
+        && filter
+            .actor_id
+            .is_none_or(|actor_id| entry.actor_id == Some(actor_id))
+        && filter
+            .target_id
+            .is_none_or(|target_id| entry.target_id == Some(target_id))
+        && filter
+            .from
+            .as_ref()
+            .is_none_or(|from| entry.created_at.datetime >= from.datetime)
+        && filter
+            .to
+            .as_ref()
+            .is_none_or(|to| entry.created_at.datetime < to.datetime)
+}
+
+#[async_trait]
+impl AuditRepo for AuditRepository {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), Error> {
+        self.entries.write().await.push(entry.clone());
+
+        Ok(())
+    }
+
+    async fn find_by_filter(
+        &self,
+        filter: &AuditFilter,
+        page: &PageRequest<AuditSort>,
+    ) -> Result<Page<AuditEntry>, Error> {
+        let entries: Vec<AuditEntry> = self
+            .entries
+            .read()
+            .await
+            .iter()
+            .filter(|entry| matches(filter, entry))
+            .cloned()
+            .collect();
+
+        Ok(keyset_page(entries, page, |entry| {
+            page.sort.cursor_key(entry)
+        }))
+    }
+}
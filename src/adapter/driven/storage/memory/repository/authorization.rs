@@ -0,0 +1,138 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use bb8::Pool;
+use sidekiq::redis_rs as redis;
+use sidekiq::RedisConnectionManager;
+use tokio::time::{Duration, Instant};
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::domain::entity::identity::AuthorizationRequest;
+use crate::core::port::identity::AuthorizationRepo;
+
+/// Started logins of a single instance, kept in process memory.
+pub struct AuthorizationRepository {
+    cache: MemCache<String, (AuthorizationRequest, Instant)>,
+}
+
+impl AuthorizationRepository {
+    pub fn new() -> Self {
+        Self {
+            cache: MemCache::new(),
+        }
+    }
+}
+
+impl Default for AuthorizationRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AuthorizationRepo for AuthorizationRepository {
+    async fn put(&self, request: &AuthorizationRequest, ttl_sec: u64) -> Result<(), Error> {
+        let now = Instant::now();
+        // Abandoned logins are never taken, drop them as new ones come in.
+        self.cache
+            .retain(|_, (_, expires_at)| *expires_at > now)
+            .await;
+
+        let expires_at = now + Duration::from_secs(ttl_sec);
+        self.cache
+            .add(request.state.clone(), (request.clone(), expires_at))
+            .await;
+
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<AuthorizationRequest>, Error> {
+        let request = self
+            .cache
+            .take(&state.to_string())
+            .await
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(request, _)| request);
+
+        Ok(request)
+    }
+}
+
+/// Started logins shared by every instance, kept in Redis.
+pub struct RedisAuthorizationRepository {
+    pool: Pool<RedisConnectionManager>,
+}
+
+impl RedisAuthorizationRepository {
+    pub fn new(pool: Pool<RedisConnectionManager>) -> Self {
+        Self { pool }
+    }
+}
+
+fn state_key(state: &str) -> String {
+    format!("oauth2:state:{}", state)
+}
+
+#[async_trait]
+impl AuthorizationRepo for RedisAuthorizationRepository {
+    async fn put(&self, request: &AuthorizationRequest, ttl_sec: u64) -> Result<(), Error> {
+        let mut conn = self.pool.get().await?;
+
+        redis::cmd("SET")
+            .arg(state_key(&request.state))
+            .arg(serde_json::to_string(request)?)
+            .arg("EX")
+            .arg(ttl_sec)
+            .query_async::<_, ()>(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<AuthorizationRequest>, Error> {
+        let mut conn = self.pool.get().await?;
+        let key = state_key(state);
+
+        let (request,): (Option<String>,) = redis::pipe()
+            .atomic()
+            .cmd("GET")
+            .arg(&key)
+            .cmd("DEL")
+            .arg(&key)
+            .ignore()
+            .query_async(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(request
+            .map(|request| serde_json::from_str(&request))
+            .transpose()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn memory_requests_are_taken_once() {
+        let repository = AuthorizationRepository::new();
+        let request = AuthorizationRequest::new("google", None);
+
+        repository.put(&request, 60).await.unwrap();
+
+        assert_eq!(
+            repository.take(&request.state).await.unwrap(),
+            Some(request.clone())
+        );
+        assert_eq!(repository.take(&request.state).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn memory_requests_expire() {
+        let repository = AuthorizationRepository::new();
+        let request = AuthorizationRequest::new("google", None);
+
+        repository.put(&request, 0).await.unwrap();
+
+        assert_eq!(repository.take(&request.state).await.unwrap(), None);
+    }
+}
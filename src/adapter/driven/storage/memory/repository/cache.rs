@@ -0,0 +1,144 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use bb8::Pool;
+use sidekiq::redis_rs as redis;
+use sidekiq::RedisConnectionManager;
+use tokio::time::{Duration, Instant};
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::port::cache::CacheRepo;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    value: String,
+    expires_at: Instant,
+}
+
+/// A cache for a single instance, kept in process memory.
+pub struct CacheRepository {
+    cache: MemCache<String, Entry>,
+}
+
+impl CacheRepository {
+    pub fn new() -> Self {
+        Self {
+            cache: MemCache::new(),
+        }
+    }
+}
+
+impl Default for CacheRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl CacheRepo for CacheRepository {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        let value = self
+            .cache
+            .get(&key.to_string())
+            .await
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.value);
+
+        Ok(value)
+    }
+
+    async fn set(&self, key: &str, value: String, ttl_sec: u64) -> Result<(), Error> {
+        let now = Instant::now();
+        // Expired entries are only dropped here, so that reads never write.
+        self.cache.retain(|_, entry| entry.expires_at > now).await;
+        self.cache
+            .add(
+                key.to_string(),
+                Entry {
+                    value,
+                    expires_at: now + Duration::from_secs(ttl_sec),
+                },
+            )
+            .await;
+
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), Error> {
+        self.cache.remove(key.to_string()).await;
+
+        Ok(())
+    }
+}
+
+/// A cache shared by every instance, kept in Redis.
+pub struct RedisCacheRepository {
+    pool: Pool<RedisConnectionManager>,
+}
+
+impl RedisCacheRepository {
+    pub fn new(pool: Pool<RedisConnectionManager>) -> Self {
+        Self { pool }
+    }
+}
+
+fn cache_key(key: &str) -> String {
+    format!("cache:{}", key)
+}
+
+#[async_trait]
+impl CacheRepo for RedisCacheRepository {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        let mut conn = self.pool.get().await?;
+
+        let value: Option<String> = redis::cmd("GET")
+            .arg(cache_key(key))
+            .query_async(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(value)
+    }
+
+    async fn set(&self, key: &str, value: String, ttl_sec: u64) -> Result<(), Error> {
+        let mut conn = self.pool.get().await?;
+
+        redis::cmd("SET")
+            .arg(cache_key(key))
+            .arg(value)
+            .arg("EX")
+            .arg(ttl_sec)
+            .query_async::<_, ()>(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), Error> {
+        let mut conn = self.pool.get().await?;
+
+        redis::cmd("DEL")
+            .arg(cache_key(key))
+            .query_async::<_, ()>(conn.unnamespaced_borrow_mut())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn memory_forgets_values_after_ttl() {
+        let repository = CacheRepository::new();
+
+        repository.set("kept", "1".to_string(), 60).await.unwrap();
+        repository.set("expired", "2".to_string(), 0).await.unwrap();
+
+        assert_eq!(repository.get("kept").await.unwrap(), Some("1".to_string()));
+        assert_eq!(repository.get("expired").await.unwrap(), None);
+
+        repository.remove("kept").await.unwrap();
+        assert_eq!(repository.get("kept").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,271 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+
+use crate::adapter::driven::storage::memory::page::keyset_page;
+use crate::adapter::driven::storage::memory::store::MemStore;
+use crate::core::domain::entity::company::Company;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::CompanyId;
+use crate::core::port::company::{CompanyRepo, CompanySort};
+use crate::core::port::error::{AlreadyTaken, VersionConflict};
+use crate::core::port::page::{Page, PageRequest};
+
+/// Companies kept in process memory, with the semantics of the Postgres
+/// repository: names and URLs are unique, even among deleted companies, and a
+/// deleted company is hidden until restored or purged. Purging a company
+/// deletes its employments.
+pub struct CompanyRepository {
+    store: Arc<MemStore>,
+}
+
+impl CompanyRepository {
+    pub fn new(store: Arc<MemStore>) -> Self {
+        CompanyRepository { store }
+    }
+
+    async fn ensure_unique(
+        &self,
+        company: &Company,
+        owner: Option<CompanyId>,
+    ) -> Result<(), Error> {
+        for stored in self.store.companies.get_all().await {
+            if stored.id == owner {
+                continue;
+            }
+            if stored.name == company.name {
+                return Err(AlreadyTaken::new("company", "name").into());
+            }
+            if stored.url == company.url {
+                return Err(AlreadyTaken::new("company", "url").into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CompanyRepo for CompanyRepository {
+    async fn save(&self, company: &Company) -> Result<CompanyId, Error> {
+        let mut write = self.store.write().await;
+
+        let id = company.id.unwrap_or_else(|| write.next_id());
+        self.ensure_unique(company, None).await?;
+
+        let now = Timestamp::now_utc();
+        let saved_company = Company {
+            id: Some(id),
+            created_at: now.clone(),
+            updated_at: now,
+            version: 1,
+            deleted_at: None,
+            ..company.clone()
+        };
+        if !self.store.companies.add_new(id, saved_company).await {
+            return Err(anyhow!("company {} already exists", id));
+        }
+
+        Ok(id)
+    }
+
+    async fn update(&self, id: CompanyId, company: &Company) -> Result<Company, Error> {
+        let _write = self.store.write().await;
+
+        if self.find_by_id(id).await?.is_none() {
+            return Err(anyhow!("company {} not found", id));
+        }
+        self.ensure_unique(company, Some(id)).await?;
+
+        self.store
+            .companies
+            .modify(&id, |stored| {
+                if stored.version != company.version {
+                    return Err(VersionConflict::new("company", id, company.version));
+                }
+                *stored = Company {
+                    id: Some(id),
+                    created_at: stored.created_at.clone(),
+                    updated_at: Timestamp::now_utc(),
+                    version: stored.version + 1,
+                    ..company.clone()
+                };
+                Ok(stored.clone())
+            })
+            .await
+            .ok_or_else(|| anyhow!("company {} not found", id))?
+            .map_err(Error::from)
+    }
+
+    async fn delete(&self, id: CompanyId) -> Result<(), Error> {
+        let _write = self.store.write().await;
+
+        self.store
+            .companies
+            .modify(&id, |stored| {
+                if stored.deleted_at.is_none() {
+                    stored.deleted_at = Some(Timestamp::now_utc());
+                }
+            })
+            .await;
+
+        Ok(())
+    }
+
+    async fn restore(&self, id: CompanyId) -> Result<Option<Company>, Error> {
+        let _write = self.store.write().await;
+
+        let restored = self
+            .store
+            .companies
+            .modify(&id, |stored| {
+                stored.deleted_at.take()?;
+                stored.updated_at = Timestamp::now_utc();
+                stored.version += 1;
+                Some(stored.clone())
+            })
+            .await
+            .flatten();
+
+        Ok(restored)
+    }
+
+    async fn purge_deleted(&self, before: &Timestamp) -> Result<u64, Error> {
+        let _write = self.store.write().await;
+
+        let purged: Vec<CompanyId> = self
+            .store
+            .companies
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|company| {
+                company
+                    .deleted_at
+                    .as_ref()
+                    .is_some_and(|deleted_at| deleted_at.datetime < before.datetime)
+            })
+            .filter_map(|company| company.id)
+            .collect();
+        for id in &purged {
+            self.store.companies.remove(*id).await;
+        }
+        self.store
+            .employments
+            .retain(|_, employment| !purged.contains(&employment.company_id))
+            .await;
+
+        Ok(u64::try_from(purged.len())?)
+    }
+
+    async fn find_all(&self) -> Result<Vec<Company>, Error> {
+        let companies = self
+            .store
+            .companies
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|company| company.deleted_at.is_none())
+            .collect();
+
+        Ok(companies)
+    }
+
+    async fn find_by_id(&self, id: CompanyId) -> Result<Option<Company>, Error> {
+        let company = self.store.companies.get(&id).await;
+
+        Ok(company.filter(|company| company.deleted_at.is_none()))
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Option<Company>, Error> {
+        let company = self
+            .store
+            .companies
+            .get_all()
+            .await
+            .into_iter()
+            .find(|company| company.name == name && company.deleted_at.is_none());
+
+        Ok(company)
+    }
+
+    async fn find_page(&self, page: &PageRequest<CompanySort>) -> Result<Page<Company>, Error> {
+        let companies = self.find_all().await?;
+
+        Ok(keyset_page(companies, page, |company| {
+            page.sort.cursor_key(company)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::valueobject::sector::Sector;
+
+    fn company(name: &str, url: &str) -> Company {
+        Company::new(
+            name.to_string(),
+            2020,
+            "Makes things".to_string(),
+            url.to_string(),
+            Sector::Software,
+        )
+    }
+
+    #[tokio::test]
+    async fn names_and_urls_are_unique() {
+        let repository = CompanyRepository::new(Arc::new(MemStore::new()));
+
+        let acme = company("Acme", "https://acme.example");
+        let id = repository.save(&acme).await.unwrap();
+        let other = repository
+            .save(&company("Other", "https://other.example"))
+            .await
+            .unwrap();
+
+        assert!(repository
+            .save(&company("Acme", "https://new.example"))
+            .await
+            .is_err());
+        assert!(repository
+            .save(&company("New", "https://acme.example"))
+            .await
+            .is_err());
+
+        let renamed = Company {
+            name: "Acme".to_string(),
+            ..company("Other", "https://other.example")
+        };
+        assert!(repository.update(other, &renamed).await.is_err());
+
+        let updated = repository
+            .update(
+                id,
+                &Company {
+                    description: "Makes more things".to_string(),
+                    ..acme
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.description, "Makes more things");
+        assert_eq!(
+            repository.find_by_name("Acme").await.unwrap().unwrap().id,
+            Some(id)
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_companies_cannot_be_updated() {
+        let repository = CompanyRepository::new(Arc::new(MemStore::new()));
+        let acme = company("Acme", "https://acme.example");
+
+        assert!(repository
+            .update(CompanyId::generate(), &acme)
+            .await
+            .is_err());
+        repository.delete(CompanyId::generate()).await.unwrap();
+    }
+}
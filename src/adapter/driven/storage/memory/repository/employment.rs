@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+
+use crate::adapter::driven::storage::memory::page::keyset_page;
+use crate::adapter::driven::storage::memory::store::MemStore;
+use crate::core::domain::entity::employment::Employment;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::{CompanyId, EmploymentId, UserId};
+use crate::core::domain::valueobject::position::Position;
+use crate::core::port::employment::{EmploymentRepo, EmploymentSort};
+use crate::core::port::page::{Page, PageRequest};
+
+/// Employments kept in process memory. Like the foreign keys in Postgres, an
+/// employment needs a user and a company of the same store, and is hidden
+/// while either of them is deleted.
+pub struct EmploymentRepository {
+    store: Arc<MemStore>,
+}
+
+impl EmploymentRepository {
+    pub fn new(store: Arc<MemStore>) -> Self {
+        EmploymentRepository { store }
+    }
+}
+
+#[async_trait]
+impl EmploymentRepo for EmploymentRepository {
+    async fn save(
+        &self,
+        user_id: UserId,
+        company_id: CompanyId,
+        position: &Position,
+    ) -> Result<EmploymentId, Error> {
+        let _write = self.store.write().await;
+
+        if self.store.users.get(&user_id).await.is_none() {
+            return Err(anyhow!("user {} not found", user_id));
+        }
+        if self.store.companies.get(&company_id).await.is_none() {
+            return Err(anyhow!("company {} not found", company_id));
+        }
+
+        let id = EmploymentId::generate();
+        let employment = Employment {
+            id,
+            user_id,
+            company_id,
+            position: position.clone(),
+            created_at: Timestamp::now_utc(),
+        };
+        self.store.employments.add(id, employment).await;
+
+        Ok(id)
+    }
+
+    async fn find_position(
+        &self,
+        user_id: UserId,
+        company_id: CompanyId,
+    ) -> Result<Option<Position>, Error> {
+        let user = self.store.users.get(&user_id).await;
+        let company = self.store.companies.get(&company_id).await;
+        let live = user.is_some_and(|user| user.deleted_at.is_none())
+            && company.is_some_and(|company| company.deleted_at.is_none());
+        if !live {
+            return Ok(None);
+        }
+
+        let position = self
+            .store
+            .employments
+            .get_all()
+            .await
+            .into_iter()
+            .find(|employment| employment.user_id == user_id && employment.company_id == company_id)
+            .map(|employment| employment.position);
+
+        Ok(position)
+    }
+
+    async fn find_by_company(
+        &self,
+        company_id: CompanyId,
+        page: &PageRequest<EmploymentSort>,
+    ) -> Result<Page<Employment>, Error> {
+        let company = self.store.companies.get(&company_id).await;
+        let mut employments = Vec::new();
+        if company.is_some_and(|company| company.deleted_at.is_none()) {
+            for employment in self.store.employments.get_all().await {
+                let user = self.store.users.get(&employment.user_id).await;
+                if employment.company_id == company_id
+                    && user.is_some_and(|user| user.deleted_at.is_none())
+                {
+                    employments.push(employment);
+                }
+            }
+        }
+
+        Ok(keyset_page(employments, page, |employment| {
+            page.sort.cursor_key(employment)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::company::CompanyRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::core::domain::entity::company::Company;
+    use crate::core::domain::entity::user::User;
+    use crate::core::domain::valueobject::date::Timestamp;
+    use crate::core::domain::valueobject::email::Email;
+    use crate::core::domain::valueobject::password::HashedPassword;
+    use crate::core::domain::valueobject::role::Role;
+    use crate::core::domain::valueobject::sector::Sector;
+    use crate::core::port::company::CompanyRepo;
+    use crate::core::port::user::UserRepo;
+
+    struct Fixture {
+        users: UserRepository,
+        companies: CompanyRepository,
+        employments: EmploymentRepository,
+        user_id: UserId,
+        company_id: CompanyId,
+    }
+
+    async fn fixture() -> Fixture {
+        let store = Arc::new(MemStore::new());
+        let users = UserRepository::new(Arc::clone(&store));
+        let companies = CompanyRepository::new(Arc::clone(&store));
+        let employments = EmploymentRepository::new(store);
+
+        let user = users
+            .save(&User {
+                id: None,
+                name: "John".to_string(),
+                surname: "Doe".to_string(),
+                email: Email::parse("john@example.com").unwrap(),
+                role: Role::USER,
+                password_hash: HashedPassword::from("hash".to_string()),
+                reset_token: None,
+                reset_sent_at: None,
+                email_verification_token: None,
+                email_verification_sent_at: None,
+                email_verified_at: None,
+                blocked_at: None,
+                created_at: Timestamp::now_utc(),
+                updated_at: Timestamp::now_utc(),
+                version: 1,
+                deleted_at: None,
+            })
+            .await
+            .unwrap();
+        let company_id = companies
+            .save(&Company::new(
+                "Acme".to_string(),
+                2020,
+                "Makes things".to_string(),
+                "https://acme.example".to_string(),
+                Sector::Software,
+            ))
+            .await
+            .unwrap();
+
+        Fixture {
+            users,
+            companies,
+            employments,
+            user_id: user.id.unwrap(),
+            company_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn employments_need_a_user_and_a_company() {
+        let f = fixture().await;
+
+        assert!(f
+            .employments
+            .save(UserId::generate(), f.company_id, &Position::CEO)
+            .await
+            .is_err());
+        assert!(f
+            .employments
+            .save(f.user_id, CompanyId::generate(), &Position::CEO)
+            .await
+            .is_err());
+
+        f.employments
+            .save(f.user_id, f.company_id, &Position::CEO)
+            .await
+            .unwrap();
+        assert_eq!(
+            f.employments
+                .find_position(f.user_id, f.company_id)
+                .await
+                .unwrap(),
+            Some(Position::CEO)
+        );
+    }
+
+    #[tokio::test]
+    async fn employments_are_hidden_with_their_company_or_user() {
+        let f = fixture().await;
+
+        f.employments
+            .save(f.user_id, f.company_id, &Position::CEO)
+            .await
+            .unwrap();
+        f.companies.delete(f.company_id).await.unwrap();
+        assert_eq!(
+            f.employments
+                .find_position(f.user_id, f.company_id)
+                .await
+                .unwrap(),
+            None
+        );
+
+        let f = fixture().await;
+        f.employments
+            .save(f.user_id, f.company_id, &Position::Manager)
+            .await
+            .unwrap();
+        f.users.delete(f.user_id).await.unwrap();
+        assert_eq!(
+            f.employments
+                .find_position(f.user_id, f.company_id)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
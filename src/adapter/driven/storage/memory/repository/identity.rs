@@ -0,0 +1,93 @@
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::domain::entity::identity::UserIdentity;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::identity::IdentityRepo;
+
+/// Linked provider accounts kept in process memory, keyed by provider and
+/// subject. A user links at most one account per provider.
+pub struct IdentityRepository {
+    cache: MemCache<(String, String), UserIdentity>,
+}
+
+impl IdentityRepository {
+    pub fn new() -> Self {
+        Self {
+            cache: MemCache::new(),
+        }
+    }
+}
+
+impl Default for IdentityRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn key(provider: &str, subject: &str) -> (String, String) {
+    (provider.to_string(), subject.to_string())
+}
+
+#[async_trait]
+impl IdentityRepo for IdentityRepository {
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, Error> {
+        Ok(self.cache.get(&key(provider, subject)).await)
+    }
+
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<UserIdentity>, Error> {
+        let mut identities: Vec<UserIdentity> = self
+            .cache
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|identity| identity.user_id == user_id)
+            .collect();
+        identities.sort_by(|a, b| a.provider.cmp(&b.provider));
+
+        Ok(identities)
+    }
+
+    async fn save(&self, identity: &UserIdentity) -> Result<UserIdentity, Error> {
+        let linked = self
+            .find_by_user(identity.user_id)
+            .await?
+            .into_iter()
+            .any(|linked| linked.provider == identity.provider);
+        if linked {
+            return Err(anyhow!(
+                "user {} already linked {}",
+                identity.user_id,
+                identity.provider
+            ));
+        }
+
+        let added = self
+            .cache
+            .add_new(key(&identity.provider, &identity.subject), identity.clone())
+            .await;
+        if !added {
+            return Err(anyhow!(
+                "{} account {} is already linked",
+                identity.provider,
+                identity.subject
+            ));
+        }
+
+        Ok(identity.clone())
+    }
+
+    async fn delete(&self, user_id: UserId, provider: &str) -> Result<bool, Error> {
+        let mut deleted = false;
+        self.cache
+            .retain(|_, identity| {
+                let matches = identity.user_id == user_id && identity.provider == provider;
+                deleted |= matches;
+                !matches
+            })
+            .await;
+
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::domain::entity::mfa::{UsedCode, UserMfa};
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::mfa::MfaRepo;
+
+/// Second factor settings kept in process memory.
+pub struct MfaRepository {
+    cache: MemCache<UserId, UserMfa>,
+}
+
+impl MfaRepository {
+    pub fn new() -> Self {
+        Self {
+            cache: MemCache::new(),
+        }
+    }
+}
+
+impl Default for MfaRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MfaRepo for MfaRepository {
+    async fn find_by_user(&self, user_id: UserId) -> Result<Option<UserMfa>, Error> {
+        Ok(self.cache.get(&user_id).await)
+    }
+
+    async fn save(&self, mfa: &UserMfa) -> Result<UserMfa, Error> {
+        // Like the upsert in Postgres, an existing row keeps its created_at.
+        let saved = self
+            .cache
+            .update(mfa.user_id, mfa.clone(), |stored| {
+                *stored = UserMfa {
+                    created_at: stored.created_at.clone(),
+                    ..mfa.clone()
+                };
+            })
+            .await;
+
+        Ok(saved)
+    }
+
+    async fn record_use(&self, user_id: UserId, code: &UsedCode) -> Result<bool, Error> {
+        let recorded = self
+            .cache
+            .modify(&user_id, |stored| {
+                match code {
+                    UsedCode::TotpStep(step) => {
+                        if stored.last_used_step.is_some_and(|last| last >= *step) {
+                            return false;
+                        }
+                        stored.last_used_step = Some(*step);
+                    }
+                    UsedCode::RecoveryCode(hash) => {
+                        let Some(index) = stored.recovery_codes.iter().position(|h| h == hash)
+                        else {
+                            return false;
+                        };
+                        stored.recovery_codes.remove(index);
+                    }
+                }
+                stored.updated_at = Timestamp::now_utc();
+
+                true
+            })
+            .await;
+
+        Ok(recorded.unwrap_or(false))
+    }
+}
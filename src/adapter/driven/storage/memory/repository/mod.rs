@@ -1 +1,11 @@
+pub mod api_key;
+pub mod attempt;
+pub mod audit;
+pub mod authorization;
+pub mod cache;
+pub mod company;
+pub mod employment;
+pub mod identity;
+pub mod mfa;
+pub mod session;
 pub mod user;
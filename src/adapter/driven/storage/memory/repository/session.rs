@@ -0,0 +1,82 @@
+use std::cmp::Reverse;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::domain::entity::session::Session;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::session::SessionRepo;
+
+/// Sessions kept in process memory.
+pub struct SessionRepository {
+    cache: MemCache<Uuid, Session>,
+}
+
+impl SessionRepository {
+    pub fn new() -> Self {
+        Self {
+            cache: MemCache::new(),
+        }
+    }
+}
+
+impl Default for SessionRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SessionRepo for SessionRepository {
+    async fn save(&self, session: &Session) -> Result<Session, Error> {
+        if !self.cache.add_new(session.id, session.clone()).await {
+            return Err(anyhow!("session {} already exists", session.id));
+        }
+
+        Ok(session.clone())
+    }
+
+    async fn rotate(&self, session: &Session, previous_hash: &str) -> Result<bool, Error> {
+        let rotated = self
+            .cache
+            .modify(&session.id, |stored| {
+                if stored.refresh_token_hash != previous_hash || stored.revoked_at.is_some() {
+                    return false;
+                }
+                stored.refresh_token_hash = session.refresh_token_hash.clone();
+                stored.user_agent = session.user_agent.clone();
+                stored.ip = session.ip.clone();
+                stored.last_used_at = session.last_used_at.clone();
+                stored.expires_at = session.expires_at.clone();
+                true
+            })
+            .await;
+
+        Ok(rotated.unwrap_or(false))
+    }
+
+    async fn revoke(&self, id: Uuid) -> Result<(), Error> {
+        self.cache.modify(&id, Session::revoke).await;
+
+        Ok(())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Session>, Error> {
+        Ok(self.cache.get(&id).await)
+    }
+
+    async fn find_active_by_user(&self, user_id: UserId) -> Result<Vec<Session>, Error> {
+        let mut sessions: Vec<Session> = self
+            .cache
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|session| session.user_id == user_id && session.is_active())
+            .collect();
+        sessions.sort_by_key(|session| Reverse(session.last_used_at.datetime));
+
+        Ok(sessions)
+    }
+}
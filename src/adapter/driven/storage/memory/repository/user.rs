@@ -1,58 +1,399 @@
-use anyhow::Error;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Error};
 use async_trait::async_trait;
-use tokio::sync::Mutex;
-use uuid::Uuid;
 
-use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::adapter::driven::storage::memory::page::keyset_page;
+use crate::adapter::driven::storage::memory::store::MemStore;
 use crate::core::domain::entity::user::User;
-use crate::core::port::user::UserRepo;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::error::{AlreadyTaken, VersionConflict};
+use crate::core::port::page::{Page, PageRequest};
+use crate::core::port::user::{UserFilter, UserRepo, UserSort};
 
+/// Users kept in process memory, with the semantics of the Postgres
+/// repository: emails are unique, even among deleted users, `update` keeps
+/// fields that are `None`, and users saved without an id get one assigned. A
+/// deleted user is hidden until restored or purged; purging a user deletes its
+/// employments.
 pub struct UserRepository {
-    id_counter: Mutex<u64>,
-    cache: MemCache<Uuid, User>,
+    store: Arc<MemStore>,
 }
 
 impl UserRepository {
-    pub fn new() -> Self {
-        Self {
-            id_counter: Mutex::new(0),
-            cache: MemCache::new(),
+    pub fn new(store: Arc<MemStore>) -> Self {
+        UserRepository { store }
+    }
+
+    async fn ensure_email_free(&self, email: &Email, owner: Option<UserId>) -> Result<(), Error> {
+        let taken = self
+            .store
+            .users
+            .get_all()
+            .await
+            .into_iter()
+            .any(|user| user.email == *email && user.id != owner);
+
+        if taken {
+            return Err(AlreadyTaken::new("user", "email").into());
         }
+
+        Ok(())
     }
 }
 
+fn matches(filter: &UserFilter, user: &User) -> bool {
+    user.deleted_at.is_none()
+        && filter.role.as_ref().is_none_or(|role| *role == user.role)
+        && filter
+            .verified
+            .is_none_or(|verified| user.email_verified_at.is_some() == verified)
+        && filter
+            .blocked
+            .is_none_or(|blocked| user.blocked_at.is_some() == blocked)
+        && filter
+            .created_from
+            .as_ref()
+            .is_none_or(|from| user.created_at.datetime >= from.datetime)
+        && filter
+            .created_to
+            .as_ref()
+            .is_none_or(|to| user.created_at.datetime < to.datetime)
+}
+
 #[async_trait]
 impl UserRepo for UserRepository {
     async fn save(&self, user: &User) -> Result<User, Error> {
-        let mut counter = self.id_counter.lock().await;
-        let mut owned_user = user.clone();
+        let mut write = self.store.write().await;
 
-        *counter += 1;
+        let id = user.id.unwrap_or_else(|| write.next_id());
+        self.ensure_email_free(&user.email, None).await?;
+
+        let now = Timestamp::now_utc();
+        let saved_user = User {
+            id: Some(id),
+            reset_token: None,
+            reset_sent_at: None,
+            email_verification_token: None,
+            email_verification_sent_at: None,
+            email_verified_at: None,
+            blocked_at: None,
+            created_at: now.clone(),
+            updated_at: now,
+            version: 1,
+            deleted_at: None,
+            ..user.clone()
+        };
+        if !self.store.users.add_new(id, saved_user.clone()).await {
+            return Err(anyhow!("user {} already exists", id));
+        }
+
+        Ok(saved_user)
+    }
+
+    async fn update(&self, id: UserId, user: &User) -> Result<User, Error> {
+        let _write = self.store.write().await;
+
+        if self.find_by_id(id).await?.is_none() {
+            return Err(anyhow!("user {} not found", id));
+        }
+        self.ensure_email_free(&user.email, Some(id)).await?;
 
-        self.cache
-            .add(owned_user.id.unwrap(), owned_user.clone())
+        let updated_user = self
+            .store
+            .users
+            .modify(&id, |stored| {
+                if stored.version != user.version {
+                    return Err(VersionConflict::new("user", id, user.version));
+                }
+                stored.name = user.name.clone();
+                stored.surname = user.surname.clone();
+                stored.email = user.email.clone();
+                stored.role = user.role.clone();
+                stored.password_hash = user.password_hash.clone();
+                if user.reset_token.is_some() {
+                    stored.reset_token = user.reset_token.clone();
+                }
+                if user.reset_sent_at.is_some() {
+                    stored.reset_sent_at = user.reset_sent_at.clone();
+                }
+                if user.email_verification_token.is_some() {
+                    stored.email_verification_token = user.email_verification_token.clone();
+                }
+                if user.email_verification_sent_at.is_some() {
+                    stored.email_verification_sent_at = user.email_verification_sent_at.clone();
+                }
+                if user.email_verified_at.is_some() {
+                    stored.email_verified_at = user.email_verified_at.clone();
+                }
+                stored.blocked_at = user.blocked_at.clone();
+                stored.updated_at = Timestamp::now_utc();
+                stored.version += 1;
+                Ok(stored.clone())
+            })
+            .await
+            .ok_or_else(|| anyhow!("user {} not found", id))??;
+
+        Ok(updated_user)
+    }
+
+    async fn delete(&self, id: UserId) -> Result<(), Error> {
+        let _write = self.store.write().await;
+
+        self.store
+            .users
+            .modify(&id, |stored| {
+                if stored.deleted_at.is_none() {
+                    stored.deleted_at = Some(Timestamp::now_utc());
+                }
+            })
             .await;
 
-        Ok(owned_user)
+        Ok(())
     }
 
-    async fn update(&self, id_str: &str, entity: &User) -> Result<User, Error> {
-        todo!()
+    async fn restore(&self, id: UserId) -> Result<Option<User>, Error> {
+        let _write = self.store.write().await;
+
+        let restored = self
+            .store
+            .users
+            .modify(&id, |stored| {
+                stored.deleted_at.take()?;
+                stored.updated_at = Timestamp::now_utc();
+                stored.version += 1;
+                Some(stored.clone())
+            })
+            .await
+            .flatten();
+
+        Ok(restored)
     }
 
-    async fn delete(&self, id_str: &str) -> Result<(), Error> {
-        todo!()
+    async fn purge_deleted(&self, before: &Timestamp) -> Result<u64, Error> {
+        let _write = self.store.write().await;
+
+        let purged: Vec<UserId> = self
+            .store
+            .users
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|user| {
+                user.deleted_at
+                    .as_ref()
+                    .is_some_and(|deleted_at| deleted_at.datetime < before.datetime)
+            })
+            .filter_map(|user| user.id)
+            .collect();
+        for id in &purged {
+            self.store.users.remove(*id).await;
+        }
+        self.store
+            .employments
+            .retain(|_, employment| !purged.contains(&employment.user_id))
+            .await;
+
+        Ok(u64::try_from(purged.len())?)
     }
 
     async fn find_all(&self) -> Result<Vec<User>, Error> {
-        todo!()
+        let users = self
+            .store
+            .users
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|user| user.deleted_at.is_none())
+            .collect();
+
+        Ok(users)
+    }
+
+    async fn find_by_id(&self, id: UserId) -> Result<Option<User>, Error> {
+        let user = self.store.users.get(&id).await;
+
+        Ok(user.filter(|user| user.deleted_at.is_none()))
+    }
+
+    async fn find_by_email(&self, email: &Email) -> Result<Option<User>, Error> {
+        let user = self
+            .store
+            .users
+            .get_all()
+            .await
+            .into_iter()
+            .find(|user| user.email == *email && user.deleted_at.is_none());
+
+        Ok(user)
+    }
+
+    async fn find_by_filter(
+        &self,
+        filter: &UserFilter,
+        page: &PageRequest<UserSort>,
+    ) -> Result<Page<User>, Error> {
+        let users: Vec<User> = self
+            .store
+            .users
+            .get_all()
+            .await
+            .into_iter()
+            .filter(|user| matches(filter, user))
+            .collect();
+
+        Ok(keyset_page(users, page, |user| page.sort.cursor_key(user)))
     }
 
-    async fn find_by_id(&self, id_str: &str) -> Result<Option<User>, Error> {
-        todo!()
+    async fn count_by_filter(&self, filter: &UserFilter) -> Result<i64, Error> {
+        let count = self
+            .store
+            .users
+            .get_all()
+            .await
+            .iter()
+            .filter(|user| matches(filter, user))
+            .count();
+
+        Ok(i64::try_from(count)?)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::valueobject::password::HashedPassword;
+    use crate::core::domain::valueobject::role::Role;
+
+    fn user(email: &str) -> User {
+        User {
+            id: None,
+            name: "John".to_string(),
+            surname: "Doe".to_string(),
+            email: Email::parse(email).unwrap(),
+            role: Role::USER,
+            password_hash: HashedPassword::from("hash".to_string()),
+            reset_token: None,
+            reset_sent_at: None,
+            email_verification_token: None,
+            email_verification_sent_at: None,
+            email_verified_at: None,
+            blocked_at: None,
+            created_at: Timestamp::now_utc(),
+            updated_at: Timestamp::now_utc(),
+            version: 1,
+            deleted_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn saves_finds_updates_and_deletes() {
+        let repository = UserRepository::new(Arc::new(MemStore::new()));
+
+        let saved = repository.save(&user("john@example.com")).await.unwrap();
+        let id = saved.id.unwrap();
+        assert_eq!(
+            repository
+                .find_by_email(&Email::parse("john@example.com").unwrap())
+                .await
+                .unwrap(),
+            Some(saved.clone())
+        );
+
+        let mut changed = saved.clone();
+        changed.name = "Johnny".to_string();
+        changed.blocked_at = Some(Timestamp::now_utc());
+        let updated = repository.update(id, &changed).await.unwrap();
+        assert_eq!(updated.name, "Johnny");
+        assert!(updated.blocked_at.is_some());
+
+        // Tokens left out keep their values, unblocking clears `blocked_at`.
+        changed = updated;
+        changed.reset_token = Some("token".to_string());
+        let updated = repository.update(id, &changed).await.unwrap();
+        changed = updated;
+        changed.reset_token = None;
+        changed.blocked_at = None;
+        let updated = repository.update(id, &changed).await.unwrap();
+        assert_eq!(updated.reset_token.as_deref(), Some("token"));
+        assert!(updated.blocked_at.is_none());
+
+        repository.delete(id).await.unwrap();
+        assert_eq!(repository.find_by_id(id).await.unwrap(), None);
+        assert!(repository.update(id, &changed).await.is_err());
+        repository.delete(id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn emails_are_unique() {
+        let repository = UserRepository::new(Arc::new(MemStore::new()));
+
+        let john = repository.save(&user("john@example.com")).await.unwrap();
+        let jane = repository.save(&user("jane@example.com")).await.unwrap();
+        assert_ne!(john.id, jane.id);
+        assert!(repository.save(&user("john@example.com")).await.is_err());
+
+        let mut changed = jane.clone();
+        changed.email = john.email.clone();
+        let id = jane.id.unwrap();
+        assert!(repository.update(id, &changed).await.is_err());
+        assert!(repository.update(id, &jane).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn filters_newest_first() {
+        let repository = UserRepository::new(Arc::new(MemStore::new()));
+
+        for email in ["a@example.com", "b@example.com", "c@example.com"] {
+            repository.save(&user(email)).await.unwrap();
+        }
+        let blocked = repository
+            .find_by_email(&Email::parse("b@example.com").unwrap())
+            .await
+            .unwrap();
+        let mut blocked = blocked.unwrap();
+        blocked.blocked_at = Some(Timestamp::now_utc());
+        repository
+            .update(blocked.id.unwrap(), &blocked)
+            .await
+            .unwrap();
+
+        let filter = UserFilter {
+            blocked: Some(false),
+            ..UserFilter::default()
+        };
+        let all = PageRequest::new(Some(10), None, UserSort::CreatedAt).unwrap();
+        let emails: Vec<String> = repository
+            .find_by_filter(&filter, &all)
+            .await
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|user| user.email.to_string())
+            .collect();
 
-    async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error> {
-        todo!()
+        assert_eq!(emails, ["c@example.com", "a@example.com"]);
+        assert_eq!(repository.count_by_filter(&filter).await.unwrap(), 2);
+        let first = repository
+            .find_by_filter(
+                &filter,
+                &PageRequest::new(Some(1), None, UserSort::CreatedAt).unwrap(),
+            )
+            .await
+            .unwrap();
+        let cursor = first.next_cursor.unwrap().encode();
+        let second = PageRequest::new(Some(1), Some(&cursor), UserSort::CreatedAt).unwrap();
+        assert_eq!(
+            repository
+                .find_by_filter(&filter, &second)
+                .await
+                .unwrap()
+                .items[0]
+                .email
+                .as_str(),
+            "a@example.com"
+        );
     }
 }
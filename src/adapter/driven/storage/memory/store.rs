@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use uuid::Uuid;
+
+use crate::adapter::driven::storage::memory::cache::MemCache;
+use crate::core::domain::entity::company::Company;
+use crate::core::domain::entity::employment::Employment;
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::id::{CompanyId, EmploymentId, UserId};
+
+/// Rows of the memory repositories that reference each other, like the tables
+/// of one database: an employment needs an existing user and company, and is
+/// deleted with either of them. Repositories built on the same store see each
+/// other's rows.
+pub struct MemStore {
+    /// Source of assigned ids. Held for the whole of every write, so that
+    /// constraint checks and the write are atomic.
+    id_counter: Arc<Mutex<u64>>,
+    pub(super) users: MemCache<UserId, User>,
+    pub(super) companies: MemCache<CompanyId, Company>,
+    pub(super) employments: MemCache<EmploymentId, Employment>,
+}
+
+impl MemStore {
+    pub fn new() -> Self {
+        Self {
+            id_counter: Arc::new(Mutex::new(0)),
+            users: MemCache::new(),
+            companies: MemCache::new(),
+            employments: MemCache::new(),
+        }
+    }
+
+    /// Starts a write; the store takes no other write until the guard drops.
+    pub(super) async fn write(&self) -> WriteGuard {
+        WriteGuard {
+            id_counter: Arc::clone(&self.id_counter).lock_owned().await,
+        }
+    }
+
+    /// A copy of the store, taken during `write`.
+    pub(super) async fn snapshot(&self, write: &WriteGuard) -> MemStore {
+        MemStore {
+            id_counter: Arc::new(Mutex::new(*write.id_counter)),
+            users: self.users.copy().await,
+            companies: self.companies.copy().await,
+            employments: self.employments.copy().await,
+        }
+    }
+
+    /// Replaces the rows of the store with those of `snapshot`, during
+    /// `write`. Readers may see the tables change one after the other.
+    pub(super) async fn restore(&self, write: &mut WriteGuard, snapshot: &MemStore) {
+        *write.id_counter = *snapshot.id_counter.lock().await;
+        self.users.copy_from(&snapshot.users).await;
+        self.companies.copy_from(&snapshot.companies).await;
+        self.employments.copy_from(&snapshot.employments).await;
+    }
+}
+
+impl Default for MemStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(super) struct WriteGuard {
+    id_counter: OwnedMutexGuard<u64>,
+}
+
+impl WriteGuard {
+    /// The id of a row saved without one.
+    pub(super) fn next_id<Id: From<Uuid>>(&mut self) -> Id {
+        *self.id_counter += 1;
+        Id::from(Uuid::from_u128(u128::from(*self.id_counter)))
+    }
+}
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::adapter::driven::storage::memory::repository::company::CompanyRepository;
+use crate::adapter::driven::storage::memory::repository::employment::EmploymentRepository;
+use crate::adapter::driven::storage::memory::store::{MemStore, WriteGuard};
+use crate::core::port::company::CompanyRepo;
+use crate::core::port::employment::EmploymentRepo;
+use crate::core::port::unit_of_work::{UnitOfWork, UnitOfWorkFactory};
+
+/// Starts units of work on a [`MemStore`]. A unit works on a copy of the
+/// store and holds its write lock until it finishes, so units and other
+/// writes run one after the other.
+pub struct MemUnitOfWorkFactory {
+    store: Arc<MemStore>,
+}
+
+impl MemUnitOfWorkFactory {
+    pub fn new(store: Arc<MemStore>) -> Self {
+        MemUnitOfWorkFactory { store }
+    }
+}
+
+#[async_trait]
+impl UnitOfWorkFactory for MemUnitOfWorkFactory {
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork>, Error> {
+        let write = self.store.write().await;
+        let snapshot = Arc::new(self.store.snapshot(&write).await);
+
+        Ok(Box::new(MemUnitOfWork {
+            store: Arc::clone(&self.store),
+            write,
+            companies: CompanyRepository::new(Arc::clone(&snapshot)),
+            employments: EmploymentRepository::new(Arc::clone(&snapshot)),
+            snapshot,
+        }))
+    }
+}
+
+/// Repositories on a copy of the store, copied back on commit.
+pub struct MemUnitOfWork {
+    store: Arc<MemStore>,
+    write: WriteGuard,
+    snapshot: Arc<MemStore>,
+    companies: CompanyRepository,
+    employments: EmploymentRepository,
+}
+
+#[async_trait]
+impl UnitOfWork for MemUnitOfWork {
+    fn companies(&self) -> &dyn CompanyRepo {
+        &self.companies
+    }
+
+    fn employments(&self) -> &dyn EmploymentRepo {
+        &self.employments
+    }
+
+    async fn commit(mut self: Box<Self>) -> Result<(), Error> {
+        self.store.restore(&mut self.write, &self.snapshot).await;
+
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<(), Error> {
+        Ok(())
+    }
+}
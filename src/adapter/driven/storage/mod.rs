@@ -1,2 +1,6 @@
+pub mod cached;
 pub mod db;
 pub mod memory;
+
+#[cfg(test)]
+mod contract;
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::core::port::purge::DeletedPurging;
+use crate::shared::ctx::ctx::Ctx;
+
+/// Purges deleted users and companies every `every`, starting right away, as
+/// the root context, and at most once a second. A failed purge is logged and
+/// retried on the next tick.
+pub fn spawn_purge_job(purging: Arc<dyn DeletedPurging>, every: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticks = interval(every.max(Duration::from_secs(1)));
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticks.tick().await;
+            match purging.purge(&Ctx::root_ctx()).await {
+                Ok(report) => tracing::info!(
+                    users = report.users,
+                    companies = report.companies,
+                    "purged deleted rows"
+                ),
+                Err(error) => tracing::error!("Purging deleted rows failed: {}", error),
+            }
+        }
+    })
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use http::{header, HeaderValue, StatusCode};
+use serde_derive::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::page::{PageMeta, PageQuery};
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::audit::error::AuditError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::audit::{AuditFilter, AuditSort};
+use crate::core::port::user::UserManagement;
+
+const CSV_HEADER: &str = "id,created_at,action,actor_id,target_id,ip,user_agent,details";
+/// Set on exports that hit `MAX_EXPORT_ROWS` and miss older entries.
+pub const EXPORT_TRUNCATED_HEADER: &str = "x-export-truncated";
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct AuditLogQuery {
+    pub action: Option<AuditAction>,
+    pub actor_id: Option<UserId>,
+    pub target_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub sort: Option<AuditSort>,
+}
+
+impl AuditLogQuery {
+    fn page(&self) -> PageQuery<AuditSort> {
+        PageQuery {
+            limit: self.limit,
+            cursor: self.cursor.clone(),
+            sort: self.sort,
+        }
+    }
+}
+
+impl From<&AuditLogQuery> for AuditFilter {
+    fn from(query: &AuditLogQuery) -> Self {
+        AuditFilter {
+            action: query.action,
+            actor_id: query.actor_id,
+            target_id: query.target_id,
+            from: query.from.map(Timestamp::new),
+            to: query.to.map(Timestamp::new),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditEntry>,
+}
+
+impl From<AuditError> for ApiResponseData<ResponseError> {
+    fn from(value: AuditError) -> Self {
+        match value {
+            AuditError::DbInternalError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+/// Quotes a CSV field when needed. Fields that a spreadsheet would evaluate as
+/// a formula are prefixed with `'`, since user agents and details come from
+/// clients.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn to_csv(entries: &[AuditEntry]) -> String {
+    let optional = |value: Option<String>| value.map(|v| csv_field(&v)).unwrap_or_default();
+    let mut csv = String::from(CSV_HEADER);
+    csv.push_str("\r\n");
+
+    for entry in entries {
+        let fields = [
+            entry.id.to_string(),
+            entry.created_at.datetime.to_rfc3339(),
+            entry.action.as_str().to_string(),
+            optional(entry.actor_id.map(|id| id.to_string())),
+            optional(entry.target_id.map(|id| id.to_string())),
+            optional(entry.ip.clone()),
+            optional(entry.user_agent.clone()),
+            optional(entry.details.clone()),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// A page of the entries matching the query, newest first.
+pub async fn list_audit_log_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Query(query): Query<AuditLogQuery>,
+) -> ApiResponse<AuditLogResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let request = query.page().request()?;
+    let page = app
+        .audit_log
+        .search(&AuditFilter::from(&query), &request)
+        .await?;
+
+    let meta = PageMeta::new(&page, &request);
+    Ok(ApiResponseData::success_with_page(
+        AuditLogResponse {
+            entries: page.items,
+        },
+        meta,
+        StatusCode::OK,
+    ))
+}
+
+/// Same filters as the list, as a CSV download. `limit`, `cursor` and `sort`
+/// are ignored: the export holds up to `MAX_EXPORT_ROWS` entries, newest first,
+/// and carries `x-export-truncated: true` when older ones were left out.
+pub async fn export_audit_log_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let export = app.audit_log.export(&AuditFilter::from(&query)).await?;
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"audit-log.csv\"",
+            ),
+        ],
+        to_csv(&export.entries),
+    )
+        .into_response();
+    if export.truncated {
+        response
+            .headers_mut()
+            .insert(EXPORT_TRUNCATED_HEADER, HeaderValue::from_static("true"));
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_and_defused() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=HYPERLINK(1)"), "'=HYPERLINK(1)");
+        assert_eq!(csv_field("-1,2"), "\"'-1,2\"");
+    }
+
+    #[test]
+    fn csv_has_one_line_per_entry() {
+        let entry = AuditEntry::new(AuditAction::LoginFailed, None, None)
+            .with_client(Some("10.0.0.1".to_string()), Some("curl/8.0".to_string()))
+            .with_details("email=john.doe@example.com");
+
+        let csv = to_csv(&[entry.clone()]);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},{},login_failed,,,10.0.0.1,curl/8.0,email=john.doe@example.com",
+                entry.id,
+                entry.created_at.datetime.to_rfc3339()
+            )
+        );
+        assert_eq!(lines[2], "");
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::response::Response;
+use axum::Extension;
+use http::StatusCode;
+
+use crate::adapter::driving::presentation::http::handler::company::profile::CompanyResponse;
+use crate::adapter::driving::presentation::http::response::etag::with_etag;
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::ApiResponseData;
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::valueobject::id::CompanyId;
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+use crate::shared::ctx::ctx::Ctx;
+
+/// Brings back a deleted company, with its version as `ETag`.
+pub async fn restore_company_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    meta: SessionMeta,
+    Path(id): Path<CompanyId>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let company = app.company_service.restore(&ctx, id).await?;
+    let version = company.version;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::CompanyRestored,
+            Some(ctx.user_id()),
+            Some(id.into()),
+        )
+        .with_client(meta.ip, meta.user_agent),
+    )
+    .await;
+
+    Ok(with_etag(
+        ApiResponseData::<CompanyResponse>::success_with_data(company.into(), StatusCode::OK),
+        version,
+    ))
+}
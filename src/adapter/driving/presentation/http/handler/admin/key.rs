@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use http::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct RotateKeyRequest {
+    /// Configured key to activate. A new key is generated when omitted.
+    pub kid: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RotateKeyResponse {
+    pub kid: String,
+}
+
+pub async fn rotate_key_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    body: Option<Json<RotateKeyRequest>>,
+) -> ApiResponse<RotateKeyResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let Json(body) = body.unwrap_or_default();
+    let kid = app.admin_service.rotate_signing_key(body.kid).await?;
+
+    app.audit(
+        AuditEntry::new(AuditAction::SigningKeyRotated, Some(claims.sub), None)
+            .with_client(meta.ip, meta.user_agent)
+            .with_details(format!("kid={}", kid)),
+    )
+    .await;
+
+    Ok(ApiResponseData::success_with_data(
+        RotateKeyResponse { kid },
+        StatusCode::OK,
+    ))
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use http::StatusCode;
+use serde_derive::Serialize;
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::port::cache::CacheStats;
+use crate::core::port::user::UserManagement;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CacheMetricsResponse {
+    /// Lookups of users by id or email.
+    pub user: CacheStats,
+}
+
+/// Hits and misses of the caches of this instance since it started.
+pub async fn cache_metrics_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+) -> ApiResponse<CacheMetricsResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    Ok(ApiResponseData::success_with_data(
+        CacheMetricsResponse {
+            user: app.user_cache.stats(),
+        },
+        StatusCode::OK,
+    ))
+}
@@ -1 +1,5 @@
-
+pub mod audit;
+pub mod company;
+pub mod key;
+pub mod metrics;
+pub mod user;
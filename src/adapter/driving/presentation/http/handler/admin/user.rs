@@ -0,0 +1,395 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::Response;
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use http::{HeaderMap, StatusCode};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::session::SessionMeta;
+
+use crate::adapter::driving::presentation::http::response::etag::{
+    if_match, precondition_failed, with_etag,
+};
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::page::{PageMeta, PageQuery};
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::admin::error::AdminError;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::port::admin::UserPage;
+use crate::core::port::user::{UserFilter, UserManagement, UserSort};
+use crate::shared::worker::mailer::auth::service::AuthMailer;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ListUsersQuery {
+    pub role: Option<Role>,
+    pub verified: Option<bool>,
+    pub blocked: Option<bool>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub sort: Option<UserSort>,
+}
+
+impl ListUsersQuery {
+    fn page(&self) -> PageQuery<UserSort> {
+        PageQuery {
+            limit: self.limit,
+            cursor: self.cursor.clone(),
+            sort: self.sort,
+        }
+    }
+}
+
+impl From<&ListUsersQuery> for UserFilter {
+    fn from(query: &ListUsersQuery) -> Self {
+        UserFilter {
+            role: query.role.clone(),
+            verified: query.verified,
+            blocked: query.blocked,
+            created_from: query.created_from.map(Timestamp::new),
+            created_to: query.created_to.map(Timestamp::new),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpdateUserRoleRequest {
+    pub role: Role,
+}
+
+/// User as seen by administrators: everything but credentials and tokens.
+#[derive(Serialize, Debug, Clone)]
+pub struct AdminUserResponse {
+    pub id: Option<UserId>,
+    pub name: String,
+    pub surname: String,
+    pub email: Email,
+    pub role: Role,
+    pub email_verified_at: Option<Timestamp>,
+    pub blocked_at: Option<Timestamp>,
+    pub reset_sent_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+impl From<User> for AdminUserResponse {
+    fn from(user: User) -> Self {
+        AdminUserResponse {
+            id: user.id,
+            name: user.name,
+            surname: user.surname,
+            email: user.email,
+            role: user.role,
+            email_verified_at: user.email_verified_at,
+            blocked_at: user.blocked_at,
+            reset_sent_at: user.reset_sent_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AdminUserListResponse {
+    pub users: Vec<AdminUserResponse>,
+}
+
+/// Audit entry of an administrator acting on a user.
+fn admin_entry(
+    action: AuditAction,
+    claims: &Claims,
+    target: &User,
+    meta: &SessionMeta,
+) -> AuditEntry {
+    AuditEntry::new(action, Some(claims.sub), target.id.map(Into::into))
+        .with_client(meta.ip.clone(), meta.user_agent.clone())
+}
+
+fn parse_user_id(id: &str) -> Result<UserId, AdminError> {
+    id.parse().map_err(|_| AdminError::InvalidIdFormat)
+}
+
+impl From<AdminError> for ApiResponseData<ResponseError> {
+    fn from(value: AdminError) -> Self {
+        match value {
+            AdminError::UserNotFound => {
+                ApiResponseData::error(None, "user not found", StatusCode::NOT_FOUND)
+            }
+            AdminError::InvalidIdFormat => {
+                ApiResponseData::error(None, "invalid user id", StatusCode::BAD_REQUEST)
+            }
+            AdminError::VersionMismatch => precondition_failed(),
+            AdminError::SelfDemotion => ApiResponseData::error(
+                None,
+                "admins cannot demote themselves",
+                StatusCode::FORBIDDEN,
+            ),
+            AdminError::LastAdmin => ApiResponseData::error(
+                None,
+                "the last admin cannot be demoted or deleted",
+                StatusCode::CONFLICT,
+            ),
+            AdminError::SelfBlock => ApiResponseData::error(
+                None,
+                "admins cannot block themselves",
+                StatusCode::FORBIDDEN,
+            ),
+            AdminError::SelfDelete => ApiResponseData::error(
+                None,
+                "admins cannot delete themselves",
+                StatusCode::FORBIDDEN,
+            ),
+            AdminError::SigningKeyNotFound => {
+                ApiResponseData::error(None, "signing key not found", StatusCode::NOT_FOUND)
+            }
+            AdminError::SigningKeyError => ApiResponseData::error(
+                None,
+                "signing key cannot be generated, pass a configured kid",
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            AdminError::DbInternalError | AdminError::HashingError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+/// A page of the users matching the query, newest first unless sorted by
+/// email; `meta.total` counts the users of all pages.
+pub async fn list_users_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Query(query): Query<ListUsersQuery>,
+) -> ApiResponse<AdminUserListResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let request = query.page().request()?;
+    let UserPage { users, total } = app
+        .admin_service
+        .list_users(&UserFilter::from(&query), &request)
+        .await?;
+
+    let meta = PageMeta::new(&users, &request).with_total(total);
+    Ok(ApiResponseData::success_with_page(
+        AdminUserListResponse {
+            users: users.items.into_iter().map(Into::into).collect(),
+        },
+        meta,
+        StatusCode::OK,
+    ))
+}
+
+/// The user, with its version as `ETag` for a later `If-Match`.
+pub async fn get_user_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let user = app.admin_service.get_user(parse_user_id(&id)?).await?;
+    let version = user.version;
+
+    Ok(with_etag(
+        ApiResponseData::<AdminUserResponse>::success_with_data(user.into(), StatusCode::OK),
+        version,
+    ))
+}
+
+/// Changes the role only if the user still matches `If-Match`, when given.
+pub async fn update_user_role_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    headers: HeaderMap,
+    meta: SessionMeta,
+    Path(id): Path<String>,
+    Json(body): Json<UpdateUserRoleRequest>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let id = parse_user_id(&id)?;
+    let expected = if_match(&headers)?;
+    let user = app
+        .admin_service
+        .change_role(claims.sub, id, body.role, expected)
+        .await?;
+    let version = user.version;
+
+    app.audit(
+        admin_entry(AuditAction::RoleChanged, &claims, &user, &meta)
+            .with_details(format!("role={}", user.role.as_string())),
+    )
+    .await;
+
+    Ok(with_etag(
+        ApiResponseData::<AdminUserResponse>::success_with_data(user.into(), StatusCode::OK),
+        version,
+    ))
+}
+
+/// Soft-deletes the user, who can no longer log in until restored.
+pub async fn delete_user_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<String>,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    let id = parse_user_id(&id)?;
+    app.admin_service.delete_user(claims.sub, id).await?;
+
+    app.audit(
+        AuditEntry::new(AuditAction::UserDeleted, Some(claims.sub), Some(id.into()))
+            .with_client(meta.ip, meta.user_agent),
+    )
+    .await;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
+
+/// Brings back a deleted user, with its version as `ETag`.
+pub async fn restore_user_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<String>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let user = app.admin_service.restore_user(parse_user_id(&id)?).await?;
+    let version = user.version;
+
+    app.audit(admin_entry(
+        AuditAction::UserRestored,
+        &claims,
+        &user,
+        &meta,
+    ))
+    .await;
+
+    Ok(with_etag(
+        ApiResponseData::<AdminUserResponse>::success_with_data(user.into(), StatusCode::OK),
+        version,
+    ))
+}
+
+pub async fn force_password_reset_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<String>,
+) -> ApiResponse<AdminUserResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let user = app
+        .admin_service
+        .force_password_reset(parse_user_id(&id)?)
+        .await?;
+
+    app.audit(admin_entry(
+        AuditAction::PasswordResetForced,
+        &claims,
+        &user,
+        &meta,
+    ))
+    .await;
+
+    AuthMailer::forgot_password(&app.task_context, &user)
+        .await
+        .map_err(|_| ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR))?;
+
+    Ok(ApiResponseData::success_with_data(
+        user.into(),
+        StatusCode::OK,
+    ))
+}
+
+pub async fn block_user_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<String>,
+) -> ApiResponse<AdminUserResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let user = app
+        .admin_service
+        .block_user(claims.sub, parse_user_id(&id)?)
+        .await?;
+
+    app.audit(admin_entry(AuditAction::UserBlocked, &claims, &user, &meta))
+        .await;
+
+    Ok(ApiResponseData::success_with_data(
+        user.into(),
+        StatusCode::OK,
+    ))
+}
+
+pub async fn unblock_user_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<String>,
+) -> ApiResponse<AdminUserResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let user = app.admin_service.unblock_user(parse_user_id(&id)?).await?;
+
+    app.audit(admin_entry(
+        AuditAction::UserUnblocked,
+        &claims,
+        &user,
+        &meta,
+    ))
+    .await;
+
+    Ok(ApiResponseData::success_with_data(
+        user.into(),
+        StatusCode::OK,
+    ))
+}
+
+pub async fn clear_lockout_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<String>,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    let id = parse_user_id(&id)?;
+    app.admin_service.clear_lockout(id).await?;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::LockoutCleared,
+            Some(claims.sub),
+            Some(id.into()),
+        )
+        .with_client(meta.ip, meta.user_agent),
+    )
+    .await;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
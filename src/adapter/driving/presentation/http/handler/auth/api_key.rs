@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use http::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::api_key::error::ApiKeyError;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::domain::entity::api_key::ApiKey;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::scope::Scope;
+use crate::core::port::api_key::NewApiKey;
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<CreateApiKeyRequest> for NewApiKey {
+    fn from(request: CreateApiKeyRequest) -> Self {
+        NewApiKey {
+            name: request.name,
+            scopes: request.scopes,
+            expires_at: request.expires_at.map(Timestamp::new),
+        }
+    }
+}
+
+/// API key as shown to its owner; the key hash stays private.
+#[derive(Serialize, Debug, Clone)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<Scope>,
+    pub mfa: bool,
+    pub created_at: Timestamp,
+    pub last_used_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(api_key: ApiKey) -> Self {
+        ApiKeyResponse {
+            id: api_key.id,
+            name: api_key.name,
+            prefix: api_key.prefix,
+            scopes: api_key.scopes,
+            mfa: api_key.mfa,
+            created_at: api_key.created_at,
+            last_used_at: api_key.last_used_at,
+            expires_at: api_key.expires_at,
+        }
+    }
+}
+
+/// A newly created key, the only response that carries its plain text value.
+#[derive(Serialize, Debug, Clone)]
+pub struct CreatedApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    pub key: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ApiKeyListResponse {
+    pub api_keys: Vec<ApiKeyResponse>,
+}
+
+impl From<ApiKeyError> for ApiResponseData<ResponseError> {
+    fn from(value: ApiKeyError) -> Self {
+        match value {
+            ApiKeyError::InvalidKey => {
+                ApiResponseData::error(None, "invalid API key", StatusCode::UNAUTHORIZED)
+            }
+            ApiKeyError::KeyNotFound => {
+                ApiResponseData::error(None, "API key not found", StatusCode::NOT_FOUND)
+            }
+            ApiKeyError::InvalidName => ApiResponseData::error(
+                None,
+                "API key name must not be empty",
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            ApiKeyError::ExpiryInPast => ApiResponseData::error(
+                None,
+                "API key expiry must be in the future",
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            ApiKeyError::DbInternalError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+/// Creates a key for the caller. Keys created from a session that passed 2FA
+/// can call routes that require it.
+pub async fn create_api_key_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Json(body): Json<CreateApiKeyRequest>,
+) -> ApiResponse<CreatedApiKeyResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let (api_key, key) = app
+        .api_key_service
+        .create(claims.sub, claims.mfa, body.into())
+        .await?;
+
+    let scopes: Vec<&str> = api_key.scopes.iter().map(|scope| scope.as_str()).collect();
+    app.audit(
+        AuditEntry::new(
+            AuditAction::ApiKeyCreated,
+            Some(claims.sub),
+            Some(claims.sub.into()),
+        )
+        .with_client(meta.ip, meta.user_agent)
+        .with_details(format!("key={}; scopes={}", api_key.id, scopes.join(" "))),
+    )
+    .await;
+
+    Ok(ApiResponseData::success_with_data(
+        CreatedApiKeyResponse {
+            api_key: api_key.into(),
+            key,
+        },
+        StatusCode::CREATED,
+    ))
+}
+
+pub async fn list_api_keys_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResponse<ApiKeyListResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let api_keys = app.api_key_service.list(claims.sub).await?;
+
+    Ok(ApiResponseData::success_with_data(
+        ApiKeyListResponse {
+            api_keys: api_keys.into_iter().map(ApiKeyResponse::from).collect(),
+        },
+        StatusCode::OK,
+    ))
+}
+
+pub async fn revoke_api_key_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<Uuid>,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    app.api_key_service.revoke(claims.sub, id).await?;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::ApiKeyRevoked,
+            Some(claims.sub),
+            Some(claims.sub.into()),
+        )
+        .with_client(meta.ip, meta.user_agent)
+        .with_details(format!("key={}", id)),
+    )
+    .await;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
@@ -7,24 +7,33 @@ use serde::Serialize;
 use serde_derive::Deserialize;
 use tower_cookies::Cookies;
 
-use crate::adapter::driving::presentation::http::middleware::cookie::set_token_cookie;
+use crate::adapter::driving::presentation::http::middleware::cookie::{
+    set_token_cookie, REFRESH_TOKEN_PATH,
+};
 use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
 use crate::adapter::driving::presentation::http::response::response::{
     ApiResponse, ApiResponseData,
 };
 use crate::adapter::driving::presentation::http::router::AppState;
 use crate::core::application::usecase::auth::error::{LoginError, TokenError};
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::port::session::{SessionMeta, TokenPair};
 use crate::core::port::user::UserManagement;
+use crate::shared::config::config::{Config, REFRESH_TOKEN_COOKIE};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserLoginRequest {
     pub email: String,
     pub password: String,
+    /// TOTP or recovery code, for accounts with 2FA enabled.
+    #[serde(default)]
+    pub otp: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UserLoginResponse {
     pub token: String,
+    pub refresh_token: String,
 }
 
 impl<E> From<LoginError> for ApiResponseData<E>
@@ -33,16 +42,31 @@ where
 {
     fn from(value: LoginError) -> Self {
         match value {
-            LoginError::UserNotFound => {
-                ApiResponseData::error(None, "user not found", StatusCode::NOT_ACCEPTABLE)
-            }
             LoginError::BadCredentials => {
-                ApiResponseData::error(None, "bad credentials", StatusCode::FORBIDDEN)
+                ApiResponseData::error(None, "invalid email or password", StatusCode::UNAUTHORIZED)
+            }
+            LoginError::TooManyAttempts(_) => ApiResponseData::error(
+                None,
+                "too many failed login attempts, try again later",
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            LoginError::MfaRequired => {
+                ApiResponseData::error(None, "two-factor code required", StatusCode::UNAUTHORIZED)
             }
-            LoginError::UserProviderNotValid => {
-                ApiResponseData::error(None, "bad provider", StatusCode::BAD_REQUEST)
+            LoginError::InvalidMfaCode => {
+                ApiResponseData::error(None, "invalid two-factor code", StatusCode::UNAUTHORIZED)
             }
-            LoginError::DbInternalError | LoginError::JWTEncodingError => {
+            LoginError::UserProviderNotValid => ApiResponseData::error(
+                None,
+                "identity provider did not verify an email for this account",
+                StatusCode::FORBIDDEN,
+            ),
+            LoginError::UserBlocked => {
+                ApiResponseData::error(None, "account blocked", StatusCode::FORBIDDEN)
+            }
+            LoginError::DbInternalError
+            | LoginError::JWTEncodingError
+            | LoginError::HashingError => {
                 ApiResponseData::error(None, "internal error", StatusCode::INTERNAL_SERVER_ERROR)
             }
         }
@@ -55,17 +79,15 @@ where
 {
     fn from(value: TokenError) -> Self {
         match value {
-            TokenError::HmacFailNewFromSlice => {
+            TokenError::InvalidKey
+            | TokenError::KeyGenerationUnsupported
+            | TokenError::EncodingError => {
                 ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
             }
             TokenError::InvalidFormat => ApiResponseData::status_code(StatusCode::BAD_REQUEST),
-            TokenError::CannotDecodeIdent => ApiResponseData::status_code(StatusCode::BAD_REQUEST),
-            TokenError::CannotDecodeIat => ApiResponseData::status_code(StatusCode::BAD_REQUEST),
-            TokenError::CannotDecodeExp => ApiResponseData::status_code(StatusCode::BAD_REQUEST),
-            TokenError::SignatureNotMatching => {
+            TokenError::SignatureNotMatching | TokenError::UnknownKey => {
                 ApiResponseData::status_code(StatusCode::UNAUTHORIZED)
             }
-            TokenError::ExpNotIso => ApiResponseData::status_code(StatusCode::BAD_REQUEST),
             TokenError::Expired => ApiResponseData::status_code(StatusCode::UNAUTHORIZED),
         }
     }
@@ -82,21 +104,108 @@ where
     }
 }
 
+/// Why a login failed, as recorded in the audit log. `None` for failures of
+/// the server rather than of the attempt.
+pub fn failure_reason(error: &LoginError) -> Option<&'static str> {
+    match error {
+        LoginError::BadCredentials => Some("bad_credentials"),
+        LoginError::TooManyAttempts(_) => Some("locked_out"),
+        LoginError::MfaRequired => Some("mfa_required"),
+        LoginError::InvalidMfaCode => Some("invalid_mfa_code"),
+        LoginError::UserProviderNotValid => Some("provider_email_not_verified"),
+        LoginError::UserBlocked => Some("user_blocked"),
+        LoginError::DbInternalError | LoginError::JWTEncodingError | LoginError::HashingError => {
+            None
+        }
+    }
+}
+
+/// Hands a token pair to the client: in cookies when a cookie location is
+/// configured, and in the response body when a Bearer or query location is,
+/// since those clients send the token themselves.
+pub fn issue_tokens(
+    cookies: &Cookies,
+    pair: TokenPair,
+) -> ApiResponse<UserLoginResponse, ResponseError> {
+    let jwt = &Config::get().auth.jwt;
+
+    if let Some(name) = jwt.cookie_name() {
+        set_token_cookie(cookies, &name, &pair.access_token, "/", jwt.expiration);
+        set_token_cookie(
+            cookies,
+            REFRESH_TOKEN_COOKIE,
+            &pair.refresh_token,
+            REFRESH_TOKEN_PATH,
+            jwt.refresh_expiration,
+        );
+    }
+    if !jwt.has_header_location() {
+        return Ok(ApiResponseData::status_code(StatusCode::OK));
+    }
+
+    Ok(ApiResponseData::success_with_data(
+        UserLoginResponse {
+            token: pair.access_token,
+            refresh_token: pair.refresh_token,
+        },
+        StatusCode::OK,
+    ))
+}
+
 pub async fn login_handler<S>(
     State(app): State<Arc<AppState<S>>>,
     cookies: Cookies,
+    meta: SessionMeta,
     login_user: Json<UserLoginRequest>,
 ) -> ApiResponse<UserLoginResponse, ResponseError>
 where
     S: UserManagement,
 {
-    let result = app.user_service.login(&login_user).await;
+    let ip = meta.ip.as_deref();
+    let failure = |reason: &str| {
+        AuditEntry::new(AuditAction::LoginFailed, None, None)
+            .with_client(meta.ip.clone(), meta.user_agent.clone())
+            .with_details(format!("email={}; reason={}", login_user.email, reason))
+    };
+    let login = match app.login_throttle.check(&login_user.email, ip).await {
+        Ok(()) => app.user_service.login(&login_user).await,
+        Err(error) => Err(error),
+    };
+    let login = match login {
+        Ok(login) => login,
+        Err(error) => {
+            // Wrong second factors count too, or the 6 digit codes could be
+            // brute-forced once the password is known.
+            if matches!(
+                error,
+                LoginError::BadCredentials | LoginError::InvalidMfaCode
+            ) {
+                app.login_throttle
+                    .record_failure(&login_user.email, ip)
+                    .await?;
+            }
+            if let Some(reason) = failure_reason(&error) {
+                app.audit(failure(reason)).await;
+            }
+            return Err(error.into());
+        }
+    };
+    app.login_throttle.record_success(&login_user.email).await?;
 
-    match result {
-        Ok(user) => match set_token_cookie(&cookies, &login_user.email, user.id.unwrap()) {
-            Ok(()) => Ok(ApiResponseData::status_code(StatusCode::OK)),
-            Err(error) => Err(ApiResponseData::from(error)),
-        },
-        Err(error) => Err(ApiResponseData::from(error)),
-    }
+    let pair = app
+        .session_service
+        .start(&login.user, login.mfa, &meta)
+        .await?;
+    app.audit(
+        AuditEntry::new(
+            AuditAction::LoginSucceeded,
+            login.user.id,
+            login.user.id.map(Into::into),
+        )
+        .with_client(meta.ip.clone(), meta.user_agent.clone())
+        .with_details(format!("mfa={}", login.mfa)),
+    )
+    .await;
+
+    issue_tokens(&cookies, pair)
 }
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Extension;
+use http::StatusCode;
+use tower_cookies::Cookies;
+
+use crate::adapter::driving::presentation::http::middleware::cookie::{
+    remove_token_cookie, REFRESH_TOKEN_PATH,
+};
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+use crate::shared::config::config::{Config, REFRESH_TOKEN_COOKIE};
+
+/// Revokes the session of the access token and clears the token cookies. The
+/// access token itself stays valid until it expires.
+pub async fn logout_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    cookies: Cookies,
+    meta: SessionMeta,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    if let Some(session_id) = claims.sid {
+        app.session_service.revoke(claims.sub, session_id).await?;
+    }
+
+    if let Some(name) = Config::get().auth.jwt.cookie_name() {
+        remove_token_cookie(&cookies, &name, "/");
+    }
+    remove_token_cookie(&cookies, REFRESH_TOKEN_COOKIE, REFRESH_TOKEN_PATH);
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::Logout,
+            Some(claims.sub),
+            Some(claims.sub.into()),
+        )
+        .with_client(meta.ip, meta.user_agent),
+    )
+    .await;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
@@ -1,7 +1,7 @@
 use axum::extract::State;
 use axum::Extension;
 use http::StatusCode;
-use serde_derive::{Deserialize, Serialize};
+use serde_derive::Serialize;
 use std::sync::Arc;
 
 use crate::adapter::driving::presentation::http::middleware::auth::ExtError;
@@ -12,16 +12,48 @@ use crate::adapter::driving::presentation::http::response::response::{
 use crate::adapter::driving::presentation::http::router::AppState;
 use crate::core::application::usecase::auth::error::MeError;
 use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::role::Role;
 use crate::core::port::user::UserManagement;
+use crate::shared::ctx::ctx::Ctx;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// The caller's own user: everything but credentials and tokens, which API
+/// keys with the `ProfileRead` scope must not reveal.
+#[derive(Serialize, Debug, Clone)]
+pub struct UserProfile {
+    pub id: Option<UserId>,
+    pub name: String,
+    pub surname: String,
+    pub email: Email,
+    pub role: Role,
+    pub email_verified_at: Option<Timestamp>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+    pub version: i32,
+}
+
+#[derive(Serialize, Debug, Clone)]
 pub struct UserMeResponse {
-    pub user: User,
+    pub user: UserProfile,
 }
 
 impl From<User> for UserMeResponse {
-    fn from(value: User) -> Self {
-        UserMeResponse { user: value }
+    fn from(user: User) -> Self {
+        UserMeResponse {
+            user: UserProfile {
+                id: user.id,
+                name: user.name,
+                surname: user.surname,
+                email: user.email,
+                role: user.role,
+                email_verified_at: user.email_verified_at,
+                created_at: user.created_at,
+                updated_at: user.updated_at,
+                version: user.version,
+            },
+        }
     }
 }
 
@@ -45,31 +77,82 @@ impl From<ExtError> for ApiResponseData<ResponseError> {
             ExtError::TokenNotInCookieOrHeader
             | ExtError::TokenWrongFormat
             | ExtError::FailValidate
-            | ExtError::CannotSetTokenCookie => {
+            | ExtError::CannotSetTokenCookie
+            | ExtError::CtxNotInRequestExt => {
                 ApiResponseData::status_code(StatusCode::UNAUTHORIZED)
             }
+            ExtError::InsufficientRole => ApiResponseData::status_code(StatusCode::FORBIDDEN),
+            ExtError::ScopeNotGranted => ApiResponseData::error(
+                None,
+                "API key scope does not allow this route",
+                StatusCode::FORBIDDEN,
+            ),
+            ExtError::MfaRequired => ApiResponseData::error(
+                None,
+                "two-factor authentication required",
+                StatusCode::FORBIDDEN,
+            ),
             ExtError::UserNotFound => ApiResponseData::status_code(StatusCode::NOT_FOUND),
             ExtError::ModelAccessError(_) => {
                 ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
             }
-            ExtError::CtxNotInRequestExt | ExtError::CtxCreateFail(_) => {
-                ApiResponseData::status_code(StatusCode::BAD_REQUEST)
-            }
+            ExtError::CtxCreateFail(_) => ApiResponseData::status_code(StatusCode::BAD_REQUEST),
         }
     }
 }
 
 pub async fn me_handler<S>(
-    State(_state): State<Arc<AppState<S>>>,
-    Extension(user): Extension<User>,
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
 ) -> ApiResponse<UserMeResponse, ResponseError>
 where
     S: UserManagement,
 {
-    let user_response: UserMeResponse = user.into();
+    let user_response: UserMeResponse = app.user_service.me(&ctx).await?.into();
 
     Ok(ApiResponseData::success_with_data(
         user_response,
         StatusCode::OK,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::domain::valueobject::password::HashedPassword;
+
+    #[test]
+    fn the_profile_leaves_out_credentials_and_tokens() {
+        let user = User {
+            id: Some(UserId::generate()),
+            name: "John".to_string(),
+            surname: "Doe".to_string(),
+            email: Email::parse("john@example.com").unwrap(),
+            role: Role::USER,
+            password_hash: HashedPassword::from("hash".to_string()),
+            reset_token: Some("reset".to_string()),
+            reset_sent_at: Some(Timestamp::now_utc()),
+            email_verification_token: Some("verify".to_string()),
+            email_verification_sent_at: Some(Timestamp::now_utc()),
+            email_verified_at: None,
+            blocked_at: None,
+            created_at: Timestamp::now_utc(),
+            updated_at: Timestamp::now_utc(),
+            version: 1,
+            deleted_at: None,
+        };
+
+        let json = serde_json::to_value(UserMeResponse::from(user)).unwrap();
+
+        assert_eq!(json["user"]["email"], "john@example.com");
+        for field in [
+            "password_hash",
+            "reset_token",
+            "reset_sent_at",
+            "email_verification_token",
+            "email_verification_sent_at",
+        ] {
+            assert!(json["user"].get(field).is_none(), "{} is exposed", field);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use http::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::application::usecase::mfa::error::MfaError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::port::mfa::TotpEnrollment;
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TotpEnrollmentResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+impl From<TotpEnrollment> for TotpEnrollmentResponse {
+    fn from(enrollment: TotpEnrollment) -> Self {
+        TotpEnrollmentResponse {
+            secret: enrollment.secret,
+            otpauth_uri: enrollment.otpauth_uri,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+impl From<MfaError> for ApiResponseData<ResponseError> {
+    fn from(value: MfaError) -> Self {
+        match value {
+            MfaError::AlreadyEnabled => ApiResponseData::error(
+                None,
+                "two-factor authentication already enabled",
+                StatusCode::CONFLICT,
+            ),
+            MfaError::NotEnrolled => ApiResponseData::error(
+                None,
+                "no two-factor enrollment to confirm",
+                StatusCode::CONFLICT,
+            ),
+            MfaError::InvalidCode => ApiResponseData::error(
+                None,
+                "invalid two-factor code",
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            MfaError::DbInternalError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+pub async fn enroll_totp_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResponse<TotpEnrollmentResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let enrollment = app
+        .mfa_service
+        .enroll_totp(claims.sub, &claims.email)
+        .await?;
+
+    Ok(ApiResponseData::success_with_data(
+        enrollment.into(),
+        StatusCode::CREATED,
+    ))
+}
+
+/// Enables 2FA. Tokens issued before stay without the MFA claim: logging in
+/// again with a code is needed for routes that require it.
+pub async fn confirm_totp_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Json(body): Json<ConfirmTotpRequest>,
+) -> ApiResponse<RecoveryCodesResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let recovery_codes = app.mfa_service.confirm_totp(claims.sub, &body.code).await?;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::MfaEnabled,
+            Some(claims.sub),
+            Some(claims.sub.into()),
+        )
+        .with_client(meta.ip, meta.user_agent),
+    )
+    .await;
+
+    Ok(ApiResponseData::success_with_data(
+        RecoveryCodesResponse { recovery_codes },
+        StatusCode::OK,
+    ))
+}
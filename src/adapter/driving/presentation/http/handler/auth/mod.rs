@@ -1,4 +1,10 @@
+pub mod api_key;
 pub mod login;
 pub mod logout;
 pub mod me;
+pub mod mfa;
+pub mod oauth;
+pub mod password;
+pub mod refresh;
 pub mod register;
+pub mod session;
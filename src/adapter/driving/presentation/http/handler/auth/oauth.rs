@@ -0,0 +1,368 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::{Extension, Json};
+use http::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use crate::adapter::driving::presentation::http::handler::auth::login::{
+    failure_reason, issue_tokens, UserLoginResponse,
+};
+use crate::adapter::driving::presentation::http::middleware::cookie::{
+    remove_token_cookie, set_token_cookie, OAUTH2_STATE_COOKIE, OAUTH2_STATE_PATH,
+};
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::auth::error::LoginError;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::application::usecase::identity::error::IdentityError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::entity::identity::UserIdentity;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::port::identity::{AuthorizationStart, CallbackOutcome};
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OAuth2CallbackQuery {
+    pub state: String,
+    pub code: Option<String>,
+    /// Set by the provider instead of `code` when the login failed.
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OAuth2SecondFactorRequest {
+    pub ticket: String,
+    /// TOTP or recovery code.
+    pub otp: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SecondFactorResponse {
+    pub ticket: String,
+    pub expires_in: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AuthorizationUrlResponse {
+    pub authorization_url: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct IdentityResponse {
+    pub provider: String,
+    pub email: Option<String>,
+    pub created_at: Timestamp,
+}
+
+impl From<UserIdentity> for IdentityResponse {
+    fn from(identity: UserIdentity) -> Self {
+        IdentityResponse {
+            provider: identity.provider,
+            email: identity.email,
+            created_at: identity.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct IdentityListResponse {
+    pub identities: Vec<IdentityResponse>,
+}
+
+impl From<IdentityError> for ApiResponseData<ResponseError> {
+    fn from(value: IdentityError) -> Self {
+        match value {
+            IdentityError::UnknownProvider => {
+                ApiResponseData::error(None, "unknown identity provider", StatusCode::NOT_FOUND)
+            }
+            IdentityError::InvalidState => ApiResponseData::error(
+                None,
+                "invalid or expired login state",
+                StatusCode::BAD_REQUEST,
+            ),
+            IdentityError::ProviderError => {
+                ApiResponseData::error(None, "identity provider error", StatusCode::BAD_GATEWAY)
+            }
+            IdentityError::AlreadyLinked => ApiResponseData::error(
+                None,
+                "identity or provider already linked",
+                StatusCode::CONFLICT,
+            ),
+            IdentityError::UnverifiedAccount => ApiResponseData::error(
+                None,
+                "an unverified account uses this email, sign in with its password to link the provider",
+                StatusCode::CONFLICT,
+            ),
+            IdentityError::UserAlreadyRegistered => {
+                ApiResponseData::error(None, "user already registered", StatusCode::CONFLICT)
+            }
+            IdentityError::IdentityNotFound => {
+                ApiResponseData::error(None, "identity not found", StatusCode::NOT_FOUND)
+            }
+            IdentityError::DbInternalError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+            IdentityError::Login(error) => error.into(),
+        }
+    }
+}
+
+/// Why a provider login failed, as recorded in the audit log. `None` for
+/// failures of the server rather than of the attempt.
+fn identity_failure_reason(error: &IdentityError) -> Option<&'static str> {
+    match error {
+        IdentityError::UnknownProvider => Some("unknown_provider"),
+        IdentityError::InvalidState => Some("invalid_state"),
+        IdentityError::ProviderError => Some("provider_error"),
+        IdentityError::AlreadyLinked => Some("already_linked"),
+        IdentityError::UnverifiedAccount => Some("unverified_account"),
+        IdentityError::UserAlreadyRegistered => Some("user_already_registered"),
+        IdentityError::Login(error) => failure_reason(error),
+        IdentityError::IdentityNotFound | IdentityError::DbInternalError => None,
+    }
+}
+
+/// Audit entry of a failed provider login, `email` once the user is known.
+fn provider_failure(
+    meta: &SessionMeta,
+    provider: &str,
+    email: Option<&str>,
+    reason: &str,
+) -> AuditEntry {
+    let email = email.map(|email| format!("email={}; ", email));
+
+    AuditEntry::new(AuditAction::LoginFailed, None, None)
+        .with_client(meta.ip.clone(), meta.user_agent.clone())
+        .with_details(format!(
+            "{}reason={}; provider={}",
+            email.unwrap_or_default(),
+            reason,
+            provider
+        ))
+}
+
+/// Remembers the state in this browser, so that the callback only completes
+/// logins the browser started.
+fn set_state_cookie(cookies: &Cookies, start: &AuthorizationStart) {
+    set_token_cookie(
+        cookies,
+        OAUTH2_STATE_COOKIE,
+        &start.state,
+        OAUTH2_STATE_PATH,
+        start.expires_in,
+    );
+}
+
+pub async fn oauth2_authorize_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    cookies: Cookies,
+    Path(provider): Path<String>,
+) -> Result<Redirect, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let start = app.identity_service.authorize(&provider, None).await?;
+    set_state_cookie(&cookies, &start);
+
+    Ok(Redirect::to(&start.url))
+}
+
+pub async fn oauth2_callback_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    cookies: Cookies,
+    meta: SessionMeta,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuth2CallbackQuery>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let started_here = cookies
+        .get(OAUTH2_STATE_COOKIE)
+        .is_some_and(|cookie| cookie.value() == query.state);
+    remove_token_cookie(&cookies, OAUTH2_STATE_COOKIE, OAUTH2_STATE_PATH);
+    let outcome = match (started_here, query.code, query.error) {
+        (false, _, _) => Err(IdentityError::InvalidState),
+        (true, Some(code), None) => {
+            app.identity_service
+                .callback(&provider, &query.state, &code)
+                .await
+        }
+        _ => Err(IdentityError::ProviderError),
+    };
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            if let Some(reason) = identity_failure_reason(&error) {
+                app.audit(provider_failure(&meta, &provider, None, reason))
+                    .await;
+            }
+            return Err(error.into());
+        }
+    };
+
+    match outcome {
+        CallbackOutcome::LoggedIn(login) => {
+            let pair = app
+                .session_service
+                .start(&login.user, login.mfa, &meta)
+                .await?;
+            app.audit(
+                AuditEntry::new(
+                    AuditAction::LoginSucceeded,
+                    login.user.id,
+                    login.user.id.map(Into::into),
+                )
+                .with_client(meta.ip, meta.user_agent)
+                .with_details(format!("provider={}", provider)),
+            )
+            .await;
+
+            Ok(issue_tokens(&cookies, pair)?.into_response())
+        }
+        CallbackOutcome::SecondFactorRequired(challenge) => {
+            Ok(ApiResponseData::<SecondFactorResponse>::success_with_data(
+                SecondFactorResponse {
+                    ticket: challenge.ticket,
+                    expires_in: challenge.expires_in,
+                },
+                StatusCode::ACCEPTED,
+            )
+            .into_response())
+        }
+        CallbackOutcome::Linked(identity) => Ok(
+            ApiResponseData::<IdentityResponse>::success_with_data(identity.into(), StatusCode::OK)
+                .into_response(),
+        ),
+    }
+}
+
+/// Completes a provider login of a user with 2FA enabled. Wrong codes count
+/// as failed logins, like those of the password login.
+pub async fn oauth2_second_factor_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    cookies: Cookies,
+    meta: SessionMeta,
+    Path(provider): Path<String>,
+    Json(request): Json<OAuth2SecondFactorRequest>,
+) -> ApiResponse<UserLoginResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let user = match app
+        .identity_service
+        .take_second_factor(&provider, &request.ticket)
+        .await
+    {
+        Ok(user) => user,
+        Err(error) => {
+            if let Some(reason) = identity_failure_reason(&error) {
+                app.audit(provider_failure(&meta, &provider, None, reason))
+                    .await;
+            }
+            return Err(error.into());
+        }
+    };
+    let user_id = user.id.ok_or(LoginError::DbInternalError)?;
+    let email = user.email.as_str();
+    let ip = meta.ip.as_deref();
+
+    let mfa = match app.login_throttle.check(email, ip).await {
+        Ok(()) => {
+            app.user_service
+                .verify_second_factor(user_id, Some(&request.otp))
+                .await
+        }
+        Err(error) => Err(error),
+    };
+    let mfa = match mfa {
+        Ok(mfa) => mfa,
+        Err(error) => {
+            if matches!(error, LoginError::InvalidMfaCode) {
+                app.login_throttle.record_failure(email, ip).await?;
+            }
+            if let Some(reason) = failure_reason(&error) {
+                app.audit(provider_failure(&meta, &provider, Some(email), reason))
+                    .await;
+            }
+            return Err(error.into());
+        }
+    };
+    app.login_throttle.record_success(email).await?;
+
+    let pair = app.session_service.start(&user, mfa, &meta).await?;
+    app.audit(
+        AuditEntry::new(
+            AuditAction::LoginSucceeded,
+            user.id,
+            user.id.map(Into::into),
+        )
+        .with_client(meta.ip.clone(), meta.user_agent.clone())
+        .with_details(format!("provider={}; mfa={}", provider, mfa)),
+    )
+    .await;
+
+    issue_tokens(&cookies, pair)
+}
+
+/// Starts linking a provider to the signed in user. The client follows the
+/// returned URL; the provider redirects back to the login callback.
+pub async fn link_identity_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    cookies: Cookies,
+    Extension(claims): Extension<Claims>,
+    Path(provider): Path<String>,
+) -> ApiResponse<AuthorizationUrlResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let start = app
+        .identity_service
+        .authorize(&provider, Some(claims.sub))
+        .await?;
+    set_state_cookie(&cookies, &start);
+
+    Ok(ApiResponseData::success_with_data(
+        AuthorizationUrlResponse {
+            authorization_url: start.url,
+        },
+        StatusCode::OK,
+    ))
+}
+
+pub async fn list_identities_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResponse<IdentityListResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let identities = app.identity_service.list(claims.sub).await?;
+
+    Ok(ApiResponseData::success_with_data(
+        IdentityListResponse {
+            identities: identities.into_iter().map(Into::into).collect(),
+        },
+        StatusCode::OK,
+    ))
+}
+
+pub async fn unlink_identity_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    Path(provider): Path<String>,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    app.identity_service.unlink(claims.sub, &provider).await?;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
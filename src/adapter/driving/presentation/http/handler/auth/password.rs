@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use http::StatusCode;
+use serde_derive::{Deserialize, Serialize};
+use validator::{Validate, ValidationErrors};
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::auth::error::PasswordError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+use crate::shared::ctx::ctx::Ctx;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct ChangePasswordRequest {
+    #[serde(default)]
+    pub current_password: String,
+
+    #[serde(default)]
+    #[validate(length(
+        min = 8,
+        message = "Password is not valid. It should be at least 8 characters."
+    ))]
+    pub new_password: String,
+}
+
+impl From<PasswordError<ValidationErrors>> for ApiResponseData<ResponseError> {
+    fn from(value: PasswordError<ValidationErrors>) -> Self {
+        match value {
+            PasswordError::BadClientData(err) => ApiResponseData::error(
+                Some(ResponseError::from(err)),
+                "invalid data from client",
+                StatusCode::BAD_REQUEST,
+            ),
+            PasswordError::BadCredentials => {
+                ApiResponseData::error(None, "current password is wrong", StatusCode::FORBIDDEN)
+            }
+            PasswordError::UserNotFound => ApiResponseData::status_code(StatusCode::NOT_FOUND),
+            PasswordError::VersionMismatch => ApiResponseData::error(
+                None,
+                "user was changed in the meantime, try again",
+                StatusCode::CONFLICT,
+            ),
+            PasswordError::DbInternalError | PasswordError::HashingError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+pub async fn change_password_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    meta: SessionMeta,
+    Json(input): Json<ChangePasswordRequest>,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    input.validate().map_err(PasswordError::BadClientData)?;
+
+    app.user_service.change_password(&ctx, &input).await?;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::PasswordChanged,
+            Some(ctx.user_id()),
+            Some(ctx.user_id().into()),
+        )
+        .with_client(meta.ip, meta.user_agent),
+    )
+    .await;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
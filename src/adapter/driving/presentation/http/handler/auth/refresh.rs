@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::Json;
+use http::StatusCode;
+use serde_derive::Deserialize;
+use tower_cookies::Cookies;
+
+use crate::adapter::driving::presentation::http::handler::auth::login::{
+    issue_tokens, UserLoginResponse,
+};
+use crate::adapter::driving::presentation::http::middleware::cookie::{
+    remove_token_cookie, REFRESH_TOKEN_PATH,
+};
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+use crate::shared::config::config::REFRESH_TOKEN_COOKIE;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct RefreshRequest {
+    /// Falls back to the refresh token cookie when omitted.
+    pub refresh_token: Option<String>,
+}
+
+pub async fn refresh_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    cookies: Cookies,
+    meta: SessionMeta,
+    body: Option<Json<RefreshRequest>>,
+) -> ApiResponse<UserLoginResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let Json(body) = body.unwrap_or_default();
+    let refresh_token = body
+        .refresh_token
+        .or_else(|| {
+            cookies
+                .get(REFRESH_TOKEN_COOKIE)
+                .map(|c| c.value().to_string())
+        })
+        .ok_or(ApiResponseData::error(
+            None,
+            "refresh token missing",
+            StatusCode::UNAUTHORIZED,
+        ))?;
+
+    let pair = app
+        .session_service
+        .refresh(&refresh_token, &meta)
+        .await
+        .inspect_err(|_| remove_token_cookie(&cookies, REFRESH_TOKEN_COOKIE, REFRESH_TOKEN_PATH))?;
+
+    issue_tokens(&cookies, pair)
+}
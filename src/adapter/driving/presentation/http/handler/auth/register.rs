@@ -4,7 +4,6 @@ use axum::extract::State;
 use axum::Json;
 use http::StatusCode;
 use serde_derive::{Deserialize, Serialize};
-use uuid::Uuid;
 use validator::{Validate, ValidationErrors};
 
 use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
@@ -14,6 +13,7 @@ use crate::adapter::driving::presentation::http::response::response::{
 use crate::adapter::driving::presentation::http::router::AppState;
 use crate::core::application::usecase::auth::error::RegisterError;
 use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
 use crate::core::port::user::UserManagement;
 use crate::shared::worker::mailer::auth::service::AuthMailer;
 
@@ -49,7 +49,7 @@ pub struct UserRegisterRequest {
 
 #[derive(Serialize, Debug)]
 pub struct UserRegisterResponse {
-    pub user_id: Uuid,
+    pub user_id: UserId,
     pub name: String,
     pub surname: String,
     pub email: String,
@@ -68,7 +68,7 @@ impl From<RegisterError<ValidationErrors>> for ApiResponseData<ResponseError> {
                 StatusCode::BAD_REQUEST,
             ),
             RegisterError::UserAlreadyRegistered => {
-                ApiResponseData::error(None, "user already registered", StatusCode::FORBIDDEN)
+                ApiResponseData::error(None, "user already registered", StatusCode::CONFLICT)
             }
             RegisterError::DbInternalError
             | RegisterError::HashingError
@@ -98,10 +98,10 @@ where
                 .map_err(|e| RegisterError::InternalError)?;
 
             let res = UserRegisterResponse {
-                user_id: registered_user.id.unwrap(),
+                user_id: registered_user.id.ok_or(RegisterError::InternalError)?,
                 name: registered_user.name,
                 surname: registered_user.surname,
-                email: registered_user.email,
+                email: registered_user.email.into(),
                 role: registered_user.role.as_string(),
                 password: register_user.password.clone(),
                 created_at: registered_user.created_at,
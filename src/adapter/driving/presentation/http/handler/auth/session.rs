@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::Extension;
+use http::StatusCode;
+use serde_derive::Serialize;
+use uuid::Uuid;
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::application::usecase::session::error::SessionError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::entity::session::Session;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+
+/// Session as shown to its owner; the refresh token hash stays private.
+#[derive(Serialize, Debug, Clone)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: Timestamp,
+    pub last_used_at: Timestamp,
+    pub expires_at: Timestamp,
+    /// Whether the request was made with this session's access token.
+    pub current: bool,
+}
+
+impl SessionResponse {
+    fn new(session: Session, claims: &Claims) -> Self {
+        SessionResponse {
+            current: claims.sid == Some(session.id),
+            id: session.id,
+            user_agent: session.user_agent,
+            ip: session.ip,
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            expires_at: session.expires_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
+impl<E> From<SessionError> for ApiResponseData<E>
+where
+    E: serde::Serialize + 'static,
+{
+    fn from(value: SessionError) -> Self {
+        match value {
+            SessionError::InvalidRefreshToken => {
+                ApiResponseData::error(None, "invalid refresh token", StatusCode::UNAUTHORIZED)
+            }
+            SessionError::SessionExpired => {
+                ApiResponseData::error(None, "session expired", StatusCode::UNAUTHORIZED)
+            }
+            SessionError::RefreshTokenReused => ApiResponseData::error(
+                None,
+                "refresh token reused, session revoked",
+                StatusCode::UNAUTHORIZED,
+            ),
+            SessionError::SessionNotFound => {
+                ApiResponseData::error(None, "session not found", StatusCode::NOT_FOUND)
+            }
+            SessionError::DbInternalError | SessionError::TokenError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+pub async fn list_sessions_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+) -> ApiResponse<SessionListResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let sessions = app.session_service.list(claims.sub).await?;
+
+    Ok(ApiResponseData::success_with_data(
+        SessionListResponse {
+            sessions: sessions
+                .into_iter()
+                .map(|session| SessionResponse::new(session, &claims))
+                .collect(),
+        },
+        StatusCode::OK,
+    ))
+}
+
+pub async fn revoke_session_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(claims): Extension<Claims>,
+    meta: SessionMeta,
+    Path(id): Path<Uuid>,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    app.session_service.revoke(claims.sub, id).await?;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::SessionRevoked,
+            Some(claims.sub),
+            Some(claims.sub.into()),
+        )
+        .with_client(meta.ip, meta.user_agent)
+        .with_details(format!("session={}", id)),
+    )
+    .await;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
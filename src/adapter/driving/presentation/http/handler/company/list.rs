@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::Extension;
+use http::StatusCode;
+use serde_derive::Serialize;
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::page::{PageMeta, PageQuery};
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::domain::entity::company::Company;
+use crate::core::domain::entity::employment::Employment;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::{CompanyId, UserId};
+use crate::core::domain::valueobject::position::Position;
+use crate::core::port::company::CompanySort;
+use crate::core::port::employment::EmploymentSort;
+use crate::core::port::user::UserManagement;
+use crate::shared::ctx::ctx::Ctx;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CompanyListResponse {
+    pub companies: Vec<Company>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EmployeeResponse {
+    pub user_id: UserId,
+    pub position: Position,
+    pub since: Timestamp,
+}
+
+impl From<Employment> for EmployeeResponse {
+    fn from(employment: Employment) -> Self {
+        EmployeeResponse {
+            user_id: employment.user_id,
+            position: employment.position,
+            since: employment.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct EmployeeListResponse {
+    pub employees: Vec<EmployeeResponse>,
+}
+
+/// A page of companies, newest first unless sorted by name.
+pub async fn list_companies_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    Query(query): Query<PageQuery<CompanySort>>,
+) -> ApiResponse<CompanyListResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let request = query.request()?;
+    let page = app.company_service.list(&ctx, &request).await?;
+
+    let meta = PageMeta::new(&page, &request);
+    Ok(ApiResponseData::success_with_page(
+        CompanyListResponse {
+            companies: page.items,
+        },
+        meta,
+        StatusCode::OK,
+    ))
+}
+
+/// A page of the employees of a company, the most recently hired first.
+pub async fn list_employees_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    Path(id): Path<CompanyId>,
+    Query(query): Query<PageQuery<EmploymentSort>>,
+) -> ApiResponse<EmployeeListResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let request = query.request()?;
+    let page = app
+        .company_service
+        .list_employees(&ctx, id, &request)
+        .await?;
+
+    let meta = PageMeta::new(&page, &request);
+    Ok(ApiResponseData::success_with_page(
+        EmployeeListResponse {
+            employees: page.items.into_iter().map(Into::into).collect(),
+        },
+        meta,
+        StatusCode::OK,
+    ))
+}
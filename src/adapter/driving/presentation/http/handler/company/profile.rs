@@ -0,0 +1,248 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::response::Response;
+use axum::{Extension, Json};
+use http::{HeaderMap, StatusCode};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::adapter::driving::presentation::http::response::etag::{
+    if_match, precondition_failed, with_etag,
+};
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::{
+    ApiResponse, ApiResponseData,
+};
+use crate::adapter::driving::presentation::http::router::AppState;
+use crate::core::application::usecase::company::error::CompanyError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::entity::company::Company;
+use crate::core::domain::valueobject::id::CompanyId;
+use crate::core::domain::valueobject::position::Position;
+use crate::core::domain::valueobject::sector::Sector;
+use crate::core::port::company::{CompanyUpdate, NewCompany};
+use crate::core::port::session::SessionMeta;
+use crate::core::port::user::UserManagement;
+use crate::shared::ctx::ctx::Ctx;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct RegisterCompanyRequest {
+    pub name: String,
+    pub foundation_date: i16,
+    pub description: String,
+    pub url: String,
+    pub sector: Sector,
+}
+
+impl From<RegisterCompanyRequest> for NewCompany {
+    fn from(request: RegisterCompanyRequest) -> Self {
+        NewCompany {
+            name: request.name,
+            foundation_date: request.foundation_date,
+            description: request.description,
+            url: request.url,
+            sector: request.sector,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct UpdateCompanyRequest {
+    pub name: Option<String>,
+    pub foundation_date: Option<i16>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub sector: Option<Sector>,
+}
+
+impl UpdateCompanyRequest {
+    /// Names of the fields the request changes, for the audit log.
+    fn changed_fields(&self) -> Vec<&'static str> {
+        [
+            ("name", self.name.is_some()),
+            ("foundation_date", self.foundation_date.is_some()),
+            ("description", self.description.is_some()),
+            ("url", self.url.is_some()),
+            ("sector", self.sector.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+}
+
+impl From<UpdateCompanyRequest> for CompanyUpdate {
+    fn from(request: UpdateCompanyRequest) -> Self {
+        CompanyUpdate {
+            name: request.name,
+            foundation_date: request.foundation_date,
+            description: request.description,
+            url: request.url,
+            sector: request.sector,
+            version: None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CompanyResponse {
+    pub company: Company,
+}
+
+impl From<Company> for CompanyResponse {
+    fn from(company: Company) -> Self {
+        CompanyResponse { company }
+    }
+}
+
+impl From<CompanyError> for ApiResponseData<ResponseError> {
+    fn from(value: CompanyError) -> Self {
+        match value {
+            CompanyError::CompanyNotFound => {
+                ApiResponseData::error(None, "company not found", StatusCode::NOT_FOUND)
+            }
+            CompanyError::NameTaken => {
+                ApiResponseData::error(None, "company name already taken", StatusCode::CONFLICT)
+            }
+            CompanyError::UrlTaken => {
+                ApiResponseData::error(None, "company url already taken", StatusCode::CONFLICT)
+            }
+            CompanyError::InvalidName => ApiResponseData::error(
+                None,
+                "company name must not be empty",
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            CompanyError::NotCompanyCeo => ApiResponseData::error(
+                None,
+                "only the CEO can manage this company",
+                StatusCode::FORBIDDEN,
+            ),
+            CompanyError::NotAdmin => ApiResponseData::error(
+                None,
+                "only administrators can restore companies",
+                StatusCode::FORBIDDEN,
+            ),
+            CompanyError::VersionMismatch => precondition_failed(),
+            CompanyError::DbInternalError => {
+                ApiResponseData::status_code(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        }
+    }
+}
+
+/// Registers a company with the caller as its CEO.
+pub async fn register_company_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    meta: SessionMeta,
+    Json(body): Json<RegisterCompanyRequest>,
+) -> ApiResponse<CompanyResponse, ResponseError>
+where
+    S: UserManagement,
+{
+    let company = app.company_service.register(&ctx, body.into()).await?;
+
+    let entry = |action, target_id| {
+        AuditEntry::new(action, Some(ctx.user_id()), target_id)
+            .with_client(meta.ip.clone(), meta.user_agent.clone())
+    };
+    app.audit(entry(
+        AuditAction::CompanyRegistered,
+        company.id.map(Into::into),
+    ))
+    .await;
+    app.audit(
+        entry(AuditAction::EmploymentChanged, Some(ctx.user_id().into())).with_details(format!(
+            "company={}; position={}",
+            company.id.map(|id| id.to_string()).unwrap_or_default(),
+            Position::CEO.as_str()
+        )),
+    )
+    .await;
+
+    Ok(ApiResponseData::success_with_data(
+        company.into(),
+        StatusCode::CREATED,
+    ))
+}
+
+/// The company, with its version as `ETag` for a later `If-Match`.
+pub async fn get_company_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    Path(id): Path<CompanyId>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let company = app.company_service.get_profile(&ctx, id).await?;
+    let version = company.version;
+
+    Ok(with_etag(
+        ApiResponseData::<CompanyResponse>::success_with_data(company.into(), StatusCode::OK),
+        version,
+    ))
+}
+
+/// Applies the changes only if the company still matches `If-Match`, when
+/// given; the response carries the `ETag` of the updated company.
+pub async fn update_company_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    headers: HeaderMap,
+    meta: SessionMeta,
+    Path(id): Path<CompanyId>,
+    Json(body): Json<UpdateCompanyRequest>,
+) -> Result<Response, ApiResponseData<ResponseError>>
+where
+    S: UserManagement,
+{
+    let changed_fields = body.changed_fields().join(" ");
+    let input = CompanyUpdate {
+        version: if_match(&headers)?,
+        ..body.into()
+    };
+    let company = app.company_service.update(&ctx, id, input).await?;
+    let version = company.version;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::CompanyUpdated,
+            Some(ctx.user_id()),
+            Some(id.into()),
+        )
+        .with_client(meta.ip, meta.user_agent)
+        .with_details(format!("fields={}", changed_fields)),
+    )
+    .await;
+
+    Ok(with_etag(
+        ApiResponseData::<CompanyResponse>::success_with_data(company.into(), StatusCode::OK),
+        version,
+    ))
+}
+
+/// Soft-deletes the company. Administrators can restore it until it is purged.
+pub async fn delete_company_handler<S>(
+    State(app): State<Arc<AppState<S>>>,
+    Extension(ctx): Extension<Ctx>,
+    meta: SessionMeta,
+    Path(id): Path<CompanyId>,
+) -> ApiResponse<(), ResponseError>
+where
+    S: UserManagement,
+{
+    app.company_service.delete(&ctx, id).await?;
+
+    app.audit(
+        AuditEntry::new(
+            AuditAction::CompanyDeleted,
+            Some(ctx.user_id()),
+            Some(id.into()),
+        )
+        .with_client(meta.ip, meta.user_agent),
+    )
+    .await;
+
+    Ok(ApiResponseData::status_code(StatusCode::NO_CONTENT))
+}
@@ -1,25 +1,32 @@
-use std::convert::Infallible;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::Arc;
 
 use axum::body::Body;
-use axum::http::Request;
+use axum::http::{header, HeaderMap, Request, Uri};
 
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::middleware::Next;
 use axum::response::Response;
-use axum::Extension;
-use axum_extra::extract::cookie::Cookie;
 use serde_derive::Serialize;
 use tower_cookies::Cookies;
+use uuid::Uuid;
 
-use crate::adapter::driving::presentation::http::middleware::cookie::{
-    set_token_cookie, AUTH_TOKEN,
-};
-use crate::adapter::driving::presentation::http::router::AppState;
-use crate::core::application::usecase::auth::token::{validate_web_token, Token};
-use crate::core::domain::entity::user::User;
-use crate::core::port::user::UserManagement;
+use crate::adapter::driving::presentation::http::middleware::cookie::remove_token_cookie;
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::ApiResponseData;
+use crate::core::application::usecase::api_key::error::ApiKeyError;
+use crate::core::application::usecase::auth::error::TokenError;
+use crate::core::application::usecase::auth::token::{validate_web_token, Claims};
+use crate::core::domain::valueobject::scope::Scope;
+use crate::core::port::api_key::ApiKeyManagement;
+use crate::core::port::session::SessionManagement;
+use crate::shared::config::config::{Config, JWTLocation};
+use crate::shared::ctx::ctx::Ctx;
+
+/// Header carrying the id of a request, set by the caller or the proxy in
+/// front of the server.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
 // pub async fn is_verified<S>(
 //     State(app): State<Arc<AppState<S>>>,
@@ -55,59 +62,344 @@ pub enum ExtError {
     CannotSetTokenCookie,
     CtxNotInRequestExt,
     CtxCreateFail(String),
+    InsufficientRole,
+    MfaRequired,
+    ScopeNotGranted,
+}
+
+/// Put into the request extensions, next to the `Claims`, when the caller
+/// authenticated with an API key.
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuth {
+    pub key_id: Uuid,
+    pub scopes: Vec<Scope>,
+}
+
+/// What [`is_authenticated`] resolves callers with.
+#[derive(Clone)]
+pub struct Authenticators {
+    pub api_keys: Arc<dyn ApiKeyManagement>,
+    pub sessions: Arc<dyn SessionManagement>,
 }
 
-pub async fn is_authenticated<S>(
-    State(app): State<Arc<AppState<S>>>,
+/// Resolves the caller and puts its `Claims`, and the [`Ctx`] use cases run
+/// with, into the request extensions.
+///
+/// Requests with an `Authorization: ApiKey <key>` header are resolved from the
+/// key and its owner, and also get an [`ApiKeyAuth`] extension. Other requests
+/// carry an access token whose claims hold the user id, email and role; only
+/// its session is looked up, so that revoking a session also rejects the
+/// access tokens issued for it.
+pub async fn is_authenticated(
+    State(auth): State<Authenticators>,
     cookies: Cookies,
     mut req: Request<Body>,
     next: Next,
-) -> Result<Response, Infallible>
-where
-    S: UserManagement + 'static,
-{
-    let app_extension = Extension(app);
-    let ctx_ext_result = ctx_resolve(app_extension, &cookies).await;
-
-    if ctx_ext_result.is_err() && !matches!(ctx_ext_result, Err(ExtError::TokenNotInCookieOrHeader))
-    {
-        cookies.remove(Cookie::from(AUTH_TOKEN));
-    }
+) -> Result<Response, ApiResponseData<ResponseError>> {
+    let request_id = request_id(req.headers());
 
-    if let Ok(user) = ctx_ext_result {
-        // Insert the authenticated user into request extensions
-        req.extensions_mut().insert(user);
+    let claims = if let Some(key) = api_key(req.headers()) {
+        let (claims, key_auth) = api_key_resolve(auth.api_keys.as_ref(), &key).await?;
+        req.extensions_mut().insert(key_auth);
+        claims
     } else {
-        req.extensions_mut().insert(ctx_ext_result);
-    }
+        let ctx_ext_result = match ctx_resolve(&cookies, req.headers(), req.uri()) {
+            Ok(claims) => session_check(auth.sessions.as_ref(), claims).await,
+            Err(error) => Err(error),
+        };
+
+        if ctx_ext_result.is_err()
+            && !matches!(ctx_ext_result, Err(ExtError::TokenNotInCookieOrHeader))
+        {
+            if let Some(name) = Config::get().auth.jwt.cookie_name() {
+                remove_token_cookie(&cookies, &name, "/");
+            }
+        }
+        ctx_ext_result?
+    };
+
+    let ctx = Ctx::new(claims.sub, claims.role.clone(), request_id)
+        .map_err(|e| ExtError::CtxCreateFail(e.to_string()))?;
+    req.extensions_mut().insert(claims);
+    req.extensions_mut().insert(ctx);
 
     Ok(next.run(req).await)
 }
 
-async fn ctx_resolve<S>(
-    Extension(app_state): Extension<Arc<AppState<S>>>,
+/// Id of the request from [`REQUEST_ID_HEADER`], or a new one when it is
+/// missing or not a UUID.
+fn request_id(headers: &HeaderMap) -> Uuid {
+    headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value.trim()).ok())
+        .unwrap_or_else(Uuid::new_v4)
+}
+
+/// Looks for the token in each configured location, in order, and returns the
+/// first one found.
+fn extract_token(
+    locations: &[JWTLocation],
     cookies: &Cookies,
-) -> Result<User, ExtError>
-where
-    S: UserManagement + 'static,
-{
-    let token = cookies
-        .get(AUTH_TOKEN)
-        .map(|c| c.value().to_string())
-        .ok_or(ExtError::TokenNotInCookieOrHeader)?;
-
-    let token: Token = token.parse().map_err(|_| ExtError::TokenWrongFormat)?;
-
-    let user = app_state
-        .user_service
-        .me(&token.ident)
+    headers: &HeaderMap,
+    uri: &Uri,
+) -> Result<String, ExtError> {
+    locations
+        .iter()
+        .find_map(|location| match location {
+            JWTLocation::Bearer => bearer_token(headers),
+            JWTLocation::Query { name } => query_token(uri, name),
+            JWTLocation::Cookie { name } => cookies.get(name).map(|c| c.value().to_string()),
+        })
+        .ok_or(ExtError::TokenNotInCookieOrHeader)
+}
+
+/// Credentials of the `Authorization` header, if it uses `scheme`.
+fn authorization(headers: &HeaderMap, scheme: &str) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (found, credentials) = value.split_once(' ')?;
+
+    if found.eq_ignore_ascii_case(scheme) && !credentials.trim().is_empty() {
+        Some(credentials.trim().to_string())
+    } else {
+        None
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    authorization(headers, "Bearer")
+}
+
+fn api_key(headers: &HeaderMap) -> Option<String> {
+    authorization(headers, "ApiKey")
+}
+
+fn query_token(uri: &Uri, name: &str) -> Option<String> {
+    let Query(mut params) = Query::<HashMap<String, String>>::try_from_uri(uri).ok()?;
+
+    params.remove(name).filter(|token| !token.is_empty())
+}
+
+async fn api_key_resolve(
+    api_keys: &dyn ApiKeyManagement,
+    key: &str,
+) -> Result<(Claims, ApiKeyAuth), ExtError> {
+    let (api_key, user) = api_keys.authenticate(key).await.map_err(|e| match e {
+        ApiKeyError::DbInternalError => ExtError::ModelAccessError(e.to_string()),
+        _ => ExtError::FailValidate,
+    })?;
+    let user_id = user.id.ok_or(ExtError::UserNotFound)?;
+
+    let mut claims = Claims::new(user_id, user.email.as_str(), user.role, 0);
+    claims.jti = api_key.id;
+    claims.mfa = api_key.mfa;
+    let auth = ApiKeyAuth {
+        key_id: api_key.id,
+        scopes: api_key.scopes,
+    };
+
+    Ok((claims, auth))
+}
+
+/// Rejects tokens of a session that was revoked or expired.
+async fn session_check(
+    sessions: &dyn SessionManagement,
+    claims: Claims,
+) -> Result<Claims, ExtError> {
+    let Some(sid) = claims.sid else {
+        return Ok(claims);
+    };
+    let active = sessions
+        .is_active(sid)
         .await
-        .map_err(|_| ExtError::UserNotFound)?;
+        .map_err(|e| ExtError::ModelAccessError(e.to_string()))?;
+
+    if active {
+        Ok(claims)
+    } else {
+        Err(ExtError::FailValidate)
+    }
+}
+
+fn ctx_resolve(cookies: &Cookies, headers: &HeaderMap, uri: &Uri) -> Result<Claims, ExtError> {
+    let locations = Config::get().auth.jwt.locations();
+    let token = extract_token(&locations, cookies, headers, uri)?;
+
+    // Access tokens are never re-issued here: clients renew them with their
+    // refresh token.
+    validate_web_token(&token).map_err(|e| match e {
+        TokenError::InvalidFormat => ExtError::TokenWrongFormat,
+        _ => ExtError::FailValidate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+    use tower_cookies::Cookie;
+
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::session::SessionRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+    use crate::core::application::usecase::session::service::SessionService;
+    use crate::core::domain::entity::session::Session;
+    use crate::core::domain::entity::user::User;
+    use crate::core::domain::valueobject::date::Timestamp;
+    use crate::core::domain::valueobject::email::Email;
+    use crate::core::domain::valueobject::password::HashedPassword;
+    use crate::core::domain::valueobject::role::Role;
+    use crate::core::port::session::SessionRepo;
+    use crate::core::port::user::UserRepo;
+
+    fn all_locations() -> Vec<JWTLocation> {
+        vec![
+            JWTLocation::Bearer,
+            JWTLocation::Query {
+                name: "access_token".to_string(),
+            },
+            JWTLocation::Cookie {
+                name: "token".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn extract_token_follows_location_order() {
+        let cookies = Cookies::default();
+        cookies.add(Cookie::new("token", "from-cookie"));
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer from-header"),
+        );
+        let uri: Uri = "/api/v1/users/me?access_token=from-query".parse().unwrap();
+
+        let token = extract_token(&all_locations(), &cookies, &headers, &uri).unwrap();
+        assert_eq!(token, "from-header");
+
+        let token = extract_token(&all_locations(), &cookies, &HeaderMap::new(), &uri).unwrap();
+        assert_eq!(token, "from-query");
+
+        let token = extract_token(
+            &all_locations(),
+            &cookies,
+            &HeaderMap::new(),
+            &"/api/v1/users/me".parse().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(token, "from-cookie");
+    }
 
-    validate_web_token(&token, user.id.unwrap()).map_err(|_| ExtError::FailValidate)?;
+    #[test]
+    fn extract_token_ignores_unconfigured_locations() {
+        let cookies = Cookies::default();
+        cookies.add(Cookie::new("token", "from-cookie"));
 
-    set_token_cookie(&cookies, &user.email, user.id.unwrap())
-        .map_err(|_| ExtError::CannotSetTokenCookie)?;
+        let result = extract_token(
+            &[JWTLocation::Bearer],
+            &cookies,
+            &HeaderMap::new(),
+            &"/".parse().unwrap(),
+        );
 
-    Ok(user)
+        assert!(matches!(result, Err(ExtError::TokenNotInCookieOrHeader)));
+    }
+
+    #[test]
+    fn bearer_token_requires_bearer_scheme() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Basic dXNlcjpwYXNz"),
+        );
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("bearer abc"),
+        );
+        assert_eq!(bearer_token(&headers).as_deref(), Some("abc"));
+        assert_eq!(api_key(&headers), None);
+    }
+
+    #[test]
+    fn api_key_requires_api_key_scheme() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("ApiKey mm_0123456789ab_secret"),
+        );
+
+        assert_eq!(api_key(&headers).as_deref(), Some("mm_0123456789ab_secret"));
+        assert_eq!(bearer_token(&headers), None);
+    }
+
+    #[tokio::test]
+    async fn session_check_rejects_tokens_of_revoked_sessions_and_blocked_users() {
+        let repository = Arc::new(SessionRepository::new());
+        let users = Arc::new(UserRepository::new(Arc::new(MemStore::new())));
+        let sessions = SessionService::new(Arc::clone(&repository), Arc::clone(&users));
+        let mut user = users
+            .save(&User {
+                id: None,
+                name: "John".to_string(),
+                surname: "Doe".to_string(),
+                email: Email::parse("john@example.com").unwrap(),
+                role: Role::USER,
+                password_hash: HashedPassword::from("hash".to_string()),
+                reset_token: None,
+                reset_sent_at: None,
+                email_verification_token: None,
+                email_verification_sent_at: None,
+                email_verified_at: None,
+                blocked_at: None,
+                created_at: Timestamp::now_utc(),
+                updated_at: Timestamp::now_utc(),
+                version: 1,
+                deleted_at: None,
+            })
+            .await
+            .unwrap();
+        let user_id = user.id.unwrap();
+        let (session, _) = Session::new(user_id, false, None, None, 60);
+        let session = repository.save(&session).await.unwrap();
+        let mut claims = Claims::new(user_id, "john@example.com", Role::USER, 60);
+
+        assert!(session_check(&sessions, claims.clone()).await.is_ok());
+        claims.sid = Some(session.id);
+        assert!(session_check(&sessions, claims.clone()).await.is_ok());
+
+        user.block();
+        user = users.update(user_id, &user).await.unwrap();
+        assert!(matches!(
+            session_check(&sessions, claims.clone()).await,
+            Err(ExtError::FailValidate)
+        ));
+        user.unblock();
+        users.update(user_id, &user).await.unwrap();
+        assert!(session_check(&sessions, claims.clone()).await.is_ok());
+
+        sessions.revoke(user_id, session.id).await.unwrap();
+        assert!(matches!(
+            session_check(&sessions, claims.clone()).await,
+            Err(ExtError::FailValidate)
+        ));
+        claims.sid = Some(Uuid::new_v4());
+        assert!(session_check(&sessions, claims).await.is_err());
+    }
+
+    #[test]
+    fn request_id_is_kept_when_valid() {
+        let id = Uuid::new_v4();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            REQUEST_ID_HEADER,
+            HeaderValue::from_str(&id.to_string()).unwrap(),
+        );
+        assert_eq!(request_id(&headers), id);
+
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("not-a-uuid"));
+        assert_ne!(request_id(&headers), Uuid::nil());
+    }
 }
@@ -1,28 +1,28 @@
-use crate::core::application::usecase::auth::error::TokenError;
-use crate::core::application::usecase::auth::token::generate_web_token;
+use crate::shared::config::config::DEFAULT_TOKEN_COOKIE;
 use tower_cookies::{Cookie, Cookies};
-use uuid::Uuid;
 
-pub const AUTH_TOKEN: &str = "token";
+pub const AUTH_TOKEN: &str = DEFAULT_TOKEN_COOKIE;
 
-pub fn set_token_cookie(cookies: &Cookies, user: &str, salt: Uuid) -> Result<(), TokenError> {
-    let token = generate_web_token(user, salt)?;
+/// Path the refresh token cookie is scoped to, so it is only sent to the
+/// refresh endpoint.
+pub const REFRESH_TOKEN_PATH: &str = "/api/v1/auth/refresh";
 
-    let mut cookie = Cookie::new(AUTH_TOKEN, token.to_string());
+/// Holds the state of a started provider login until its callback.
+pub const OAUTH2_STATE_COOKIE: &str = "oauth2_state";
+pub const OAUTH2_STATE_PATH: &str = "/api/v1/auth/oauth2";
+
+pub fn set_token_cookie(cookies: &Cookies, name: &str, token: &str, path: &str, max_age_sec: u64) {
+    let mut cookie = Cookie::new(name.to_string(), token.to_string());
     cookie.set_http_only(true);
-    cookie.set_path("/");
-    cookie.set_max_age(time::Duration::seconds(604800));
+    cookie.set_path(path.to_string());
+    cookie.set_max_age(time::Duration::seconds(max_age_sec as i64));
 
     cookies.add(cookie);
-
-    Ok(())
 }
 
-pub fn remove_token_cookie(cookies: &Cookies) -> Result<(), TokenError> {
-    let mut cookie = Cookie::from(AUTH_TOKEN);
-    cookie.set_path("/");
+pub fn remove_token_cookie(cookies: &Cookies, name: &str, path: &str) {
+    let mut cookie = Cookie::from(name.to_string());
+    cookie.set_path(path.to_string());
 
     cookies.remove(cookie);
-
-    Ok(())
 }
@@ -1,3 +1,5 @@
 pub mod auth;
 pub mod cookie;
+pub mod remote_ip;
+pub mod role;
 pub mod trace;
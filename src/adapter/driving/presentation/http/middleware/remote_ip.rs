@@ -0,0 +1,228 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::async_trait;
+use axum::extract::{ConnectInfo, FromRequestParts};
+use http::request::Parts;
+use http::{header, HeaderMap};
+
+use crate::core::port::session::SessionMeta;
+use crate::shared::config::config::{Config, RemoteIPConfig};
+
+/// Proxies trusted when `trusted_proxies` is not configured: loopback and
+/// private networks.
+const DEFAULT_TRUSTED_PROXIES: &[&str] = &[
+    "127.0.0.0/8",
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "::1/128",
+    "fc00::/7",
+];
+
+/// An IP network, written `address/prefix`, or a single address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IpNet {
+    address: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    fn parse(net: &str) -> Option<Self> {
+        let (address, prefix) = match net.trim().split_once('/') {
+            Some((address, prefix)) => (address.parse().ok()?, prefix.parse().ok()?),
+            None => {
+                let address: IpAddr = net.trim().parse().ok()?;
+                (address, if address.is_ipv4() { 32 } else { 128 })
+            }
+        };
+        let max = if matches!(address, IpAddr::V4(_)) {
+            32
+        } else {
+            128
+        };
+
+        (prefix <= max).then_some(IpNet { address, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.address, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Peers whose `X-Forwarded-For` and `X-Real-IP` headers are believed, from
+/// `server.middlewares.remote_ip`. Nobody is trusted unless it is enabled.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<IpNet>,
+}
+
+impl TrustedProxies {
+    pub fn from_config(config: Option<&RemoteIPConfig>) -> Self {
+        let Some(config) = config.filter(|config| config.enable) else {
+            return Self::default();
+        };
+        let networks = match &config.trusted_proxies {
+            Some(proxies) => proxies.iter().filter_map(|net| IpNet::parse(net)).collect(),
+            None => DEFAULT_TRUSTED_PROXIES
+                .iter()
+                .filter_map(|net| IpNet::parse(net))
+                .collect(),
+        };
+
+        Self { networks }
+    }
+
+    fn trusts(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|net| net.contains(ip))
+    }
+
+    /// Address of the client that sent a request through `peer`. Forwarding
+    /// headers only count when `peer` is trusted; then the client is the last
+    /// `X-Forwarded-For` entry that is not a trusted proxy itself, since
+    /// earlier entries are whatever the client claimed.
+    pub fn client_ip(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+        let peer = peer?;
+        if !self.trusts(peer) {
+            return Some(peer);
+        }
+
+        let forwarded: Vec<IpAddr> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|ip| ip.trim().parse().ok())
+            .collect();
+        if let Some(client) = forwarded.iter().rev().find(|ip| !self.trusts(**ip)) {
+            return Some(*client);
+        }
+
+        headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|ip| ip.trim().parse().ok())
+            .or(forwarded.first().copied())
+            .or(Some(peer))
+    }
+}
+
+/// Device metadata of the request: its user agent, and the client address
+/// resolved by [`TrustedProxies::client_ip`] from the socket peer.
+#[async_trait]
+impl<S> FromRequestParts<S> for SessionMeta
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let trusted =
+            TrustedProxies::from_config(Config::get().server.middlewares.remote_ip.as_ref());
+
+        Ok(SessionMeta {
+            user_agent: parts
+                .headers
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string),
+            ip: trusted
+                .client_ip(&parts.headers, peer)
+                .map(|ip| ip.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn proxies(networks: &[&str]) -> TrustedProxies {
+        TrustedProxies::from_config(Some(&RemoteIPConfig {
+            enable: true,
+            trusted_proxies: Some(networks.iter().map(|net| net.to_string()).collect()),
+        }))
+    }
+
+    fn forwarded(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static(value));
+        headers
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn forwarding_headers_of_untrusted_peers_are_ignored() {
+        let headers = forwarded("203.0.113.7");
+
+        assert_eq!(
+            TrustedProxies::default().client_ip(&headers, Some(ip("198.51.100.1"))),
+            Some(ip("198.51.100.1"))
+        );
+        assert_eq!(
+            proxies(&["10.0.0.0/8"]).client_ip(&headers, Some(ip("198.51.100.1"))),
+            Some(ip("198.51.100.1"))
+        );
+        assert_eq!(TrustedProxies::default().client_ip(&headers, None), None);
+    }
+
+    #[test]
+    fn trusted_proxies_forward_the_last_untrusted_hop() {
+        let proxies = proxies(&["10.0.0.0/8", "192.0.2.1"]);
+        // The client made up the first entry; the proxies appended the rest.
+        let headers = forwarded("1.2.3.4, 203.0.113.7, 10.0.0.2");
+
+        assert_eq!(
+            proxies.client_ip(&headers, Some(ip("192.0.2.1"))),
+            Some(ip("203.0.113.7"))
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-real-ip", HeaderValue::from_static("203.0.113.9"));
+        assert_eq!(
+            proxies.client_ip(&headers, Some(ip("10.1.2.3"))),
+            Some(ip("203.0.113.9"))
+        );
+        assert_eq!(
+            proxies.client_ip(&HeaderMap::new(), Some(ip("10.1.2.3"))),
+            Some(ip("10.1.2.3"))
+        );
+    }
+
+    #[test]
+    fn networks_match_by_prefix() {
+        let net = IpNet::parse("172.16.0.0/12").unwrap();
+
+        assert!(net.contains(ip("172.31.255.255")));
+        assert!(!net.contains(ip("172.32.0.0")));
+        assert!(!net.contains(ip("::1")));
+        assert!(IpNet::parse("fc00::/7").unwrap().contains(ip("fd12::1")));
+        assert!(IpNet::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert_eq!(IpNet::parse("10.0.0.0/33"), None);
+    }
+}
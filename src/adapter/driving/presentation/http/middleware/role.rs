@@ -0,0 +1,347 @@
+use axum::body::Body;
+use axum::extract::{MatchedPath, State};
+use axum::http::{Method, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::adapter::driving::presentation::http::middleware::auth::{ApiKeyAuth, ExtError};
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::ApiResponseData;
+use crate::core::application::usecase::auth::token::Claims;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::domain::valueobject::scope::Scope;
+
+/// A single entry of the declarative route table: which roles may call
+/// `method` on `path`, whether their token must have passed 2FA, and which
+/// scope an API key needs to call it. `path` is the axum route pattern, not the
+/// raw URI.
+#[derive(Debug, Clone)]
+pub struct RoutePermission {
+    pub method: Method,
+    pub path: &'static str,
+    pub roles: &'static [Role],
+    pub mfa: bool,
+    pub scope: Option<Scope>,
+}
+
+impl RoutePermission {
+    pub const fn new(method: Method, path: &'static str, roles: &'static [Role]) -> Self {
+        Self {
+            method,
+            path,
+            roles,
+            mfa: false,
+            scope: None,
+        }
+    }
+
+    /// Lets API keys holding `scope` call the route.
+    pub const fn with_scope(mut self, scope: Scope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Also requires a token issued after a verified second factor.
+    pub const fn with_mfa(mut self) -> Self {
+        self.mfa = true;
+        self
+    }
+
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        self.method == *method && self.path == path
+    }
+}
+
+/// Checks that the authenticated caller, if any, holds one of `roles`.
+pub fn check_role(claims: Option<&Claims>, roles: &[Role]) -> Result<(), ExtError> {
+    let claims = claims.ok_or(ExtError::CtxNotInRequestExt)?;
+
+    if roles.contains(&claims.role) {
+        Ok(())
+    } else {
+        Err(ExtError::InsufficientRole)
+    }
+}
+
+/// Checks that the caller's token was issued after a verified second factor.
+pub fn check_mfa(claims: Option<&Claims>) -> Result<(), ExtError> {
+    let claims = claims.ok_or(ExtError::CtxNotInRequestExt)?;
+
+    if claims.mfa {
+        Ok(())
+    } else {
+        Err(ExtError::MfaRequired)
+    }
+}
+
+/// Checks that an API key caller was granted `scope`. Routes without a scope
+/// cannot be called with a key.
+pub fn check_scope(api_key: &ApiKeyAuth, scope: Option<Scope>) -> Result<(), ExtError> {
+    match scope {
+        Some(scope) if api_key.scopes.contains(&scope) => Ok(()),
+        _ => Err(ExtError::ScopeNotGranted),
+    }
+}
+
+/// Per-route guard, used as
+/// `from_fn_with_state(Role::ADMINS, require_role)`.
+///
+/// Must run after `is_authenticated`, which puts the token `Claims` into the
+/// request extensions.
+pub async fn require_role(
+    State(roles): State<&'static [Role]>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, ApiResponseData<ResponseError>> {
+    check_role(req.extensions().get::<Claims>(), roles)?;
+
+    Ok(next.run(req).await)
+}
+
+/// Router-wide guard driven by the route table. Routes that are not listed in
+/// the table are refused, so forgetting an entry fails closed.
+///
+/// Must be installed with `route_layer` so that `MatchedPath` is available.
+pub async fn authorize(
+    State(table): State<&'static [RoutePermission]>,
+    matched_path: MatchedPath,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, ApiResponseData<ResponseError>> {
+    let permission = table
+        .iter()
+        .find(|permission| permission.matches(req.method(), matched_path.as_str()));
+    let claims = req.extensions().get::<Claims>();
+
+    check_role(claims, permission.map(|p| p.roles).unwrap_or_default())?;
+    if permission.is_some_and(|p| p.mfa) {
+        check_mfa(claims)?;
+    }
+    if let Some(api_key) = req.extensions().get::<ApiKeyAuth>() {
+        check_scope(api_key, permission.and_then(|p| p.scope))?;
+    }
+
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::middleware::from_fn_with_state;
+    use axum::routing::on;
+    use axum::routing::MethodFilter;
+    use axum::Router;
+    use http::StatusCode;
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::adapter::driving::presentation::http::router::ROUTE_PERMISSIONS;
+    use crate::core::domain::valueobject::id::UserId;
+
+    fn user_with_role(role: Role) -> Claims {
+        let mut claims = Claims::new(UserId::generate(), "john.doe@example.com", role, 60);
+        claims.mfa = true;
+        claims
+    }
+
+    /// Mirrors the protected part of `make_router`, with stub handlers and a
+    /// stub authentication layer that injects `user` claims when present.
+    fn protected_router(user: Option<Claims>) -> Router {
+        protected_router_with_key(user, None)
+    }
+
+    /// Same as `protected_router`, but the caller may also present an API key.
+    fn protected_router_with_key(user: Option<Claims>, api_key: Option<ApiKeyAuth>) -> Router {
+        let mut router = Router::new();
+        for permission in ROUTE_PERMISSIONS {
+            let filter = MethodFilter::try_from(permission.method.clone()).unwrap();
+            router = router.route(permission.path, on(filter, || async { "ok" }));
+        }
+
+        router
+            .route_layer(from_fn_with_state(ROUTE_PERMISSIONS, authorize))
+            .route_layer(axum::middleware::from_fn(
+                move |mut req: Request<Body>, next: Next| {
+                    let user = user.clone();
+                    let api_key = api_key.clone();
+                    async move {
+                        if let Some(user) = user {
+                            req.extensions_mut().insert(user);
+                        }
+                        if let Some(api_key) = api_key {
+                            req.extensions_mut().insert(api_key);
+                        }
+                        next.run(req).await
+                    }
+                },
+            ))
+    }
+
+    async fn call(router: Router, permission: &RoutePermission) -> StatusCode {
+        let uri = permission.path.replace(":id", &Uuid::new_v4().to_string());
+        let req = Request::builder()
+            .method(permission.method.clone())
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+
+        router.oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn protected_routes_reject_anonymous_requests() {
+        for permission in ROUTE_PERMISSIONS {
+            let status = call(protected_router(None), permission).await;
+            assert_eq!(
+                status,
+                StatusCode::UNAUTHORIZED,
+                "{} {}",
+                permission.method,
+                permission.path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn protected_routes_enforce_roles() {
+        for permission in ROUTE_PERMISSIONS {
+            for role in Role::ALL {
+                let router = protected_router(Some(user_with_role(role.clone())));
+                let status = call(router, permission).await;
+                let expected = if permission.roles.contains(role) {
+                    StatusCode::OK
+                } else {
+                    StatusCode::FORBIDDEN
+                };
+                assert_eq!(
+                    status, expected,
+                    "{} {} as {:?}",
+                    permission.method, permission.path, role
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn mfa_routes_refuse_tokens_without_second_factor() {
+        for permission in ROUTE_PERMISSIONS {
+            for role in permission.roles {
+                let mut user = user_with_role(role.clone());
+                user.mfa = false;
+                let status = call(protected_router(Some(user)), permission).await;
+                let expected = if permission.mfa {
+                    StatusCode::FORBIDDEN
+                } else {
+                    StatusCode::OK
+                };
+                assert_eq!(
+                    status, expected,
+                    "{} {} as {:?}",
+                    permission.method, permission.path, role
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn api_keys_only_reach_routes_in_scope() {
+        for permission in ROUTE_PERMISSIONS {
+            let role = permission.roles[0].clone();
+            let all_scopes = ApiKeyAuth {
+                key_id: Uuid::new_v4(),
+                scopes: Scope::ALL.to_vec(),
+            };
+            let no_scopes = ApiKeyAuth {
+                key_id: Uuid::new_v4(),
+                scopes: Vec::new(),
+            };
+
+            let router =
+                protected_router_with_key(Some(user_with_role(role.clone())), Some(all_scopes));
+            let status = call(router, permission).await;
+            let expected = if permission.scope.is_some() {
+                StatusCode::OK
+            } else {
+                StatusCode::FORBIDDEN
+            };
+            assert_eq!(
+                status, expected,
+                "{} {}",
+                permission.method, permission.path
+            );
+
+            let router = protected_router_with_key(Some(user_with_role(role)), Some(no_scopes));
+            let status = call(router, permission).await;
+            assert_eq!(
+                status,
+                StatusCode::FORBIDDEN,
+                "{} {}",
+                permission.method,
+                permission.path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_missing_from_table_are_forbidden() {
+        let router = Router::new()
+            .route("/api/v1/unlisted", on(MethodFilter::GET, || async { "ok" }))
+            .route_layer(from_fn_with_state(ROUTE_PERMISSIONS, authorize))
+            .layer(axum::middleware::from_fn(
+                |mut req: Request<Body>, next: Next| async move {
+                    req.extensions_mut().insert(user_with_role(Role::ADMIN));
+                    next.run(req).await
+                },
+            ));
+        let req = Request::builder()
+            .uri("/api/v1/unlisted")
+            .body(Body::empty())
+            .unwrap();
+
+        let status = router.oneshot(req).await.unwrap().status();
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn require_role_guards_a_single_route() {
+        let router = |user: Option<Claims>| {
+            Router::new()
+                .route("/admin", on(MethodFilter::GET, || async { "ok" }))
+                .route_layer(from_fn_with_state(Role::ADMINS, require_role))
+                .layer(axum::middleware::from_fn(
+                    move |mut req: Request<Body>, next: Next| {
+                        let user = user.clone();
+                        async move {
+                            if let Some(user) = user {
+                                req.extensions_mut().insert(user);
+                            }
+                            next.run(req).await
+                        }
+                    },
+                ))
+        };
+        let req = || {
+            Request::builder()
+                .uri("/admin")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let anonymous = router(None).oneshot(req()).await.unwrap().status();
+        let user = router(Some(user_with_role(Role::USER)))
+            .oneshot(req())
+            .await
+            .unwrap()
+            .status();
+        let admin = router(Some(user_with_role(Role::ADMIN)))
+            .oneshot(req())
+            .await
+            .unwrap()
+            .status();
+
+        assert_eq!(anonymous, StatusCode::UNAUTHORIZED);
+        assert_eq!(user, StatusCode::FORBIDDEN);
+        assert_eq!(admin, StatusCode::OK);
+    }
+}
@@ -0,0 +1,68 @@
+use axum::response::{IntoResponse, Response};
+use http::header::{ETAG, IF_MATCH};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use serde::Serialize;
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::ApiResponseData;
+
+/// The `ETag` of an entity at `version`: the version as a strong tag, `"3"`.
+pub fn etag(version: i32) -> HeaderValue {
+    HeaderValue::try_from(format!("\"{}\"", version)).expect("a quoted number is a header value")
+}
+
+/// `response` with the `ETag` of `version`.
+pub fn with_etag<T: Serialize>(response: ApiResponseData<T>, version: i32) -> Response {
+    ([(ETAG, etag(version))], response).into_response()
+}
+
+/// The version the `If-Match` header requires, if any. `*` matches every
+/// version. Only single strong tags are ever issued, so anything else, a weak
+/// tag or a list, cannot match and fails the precondition.
+pub fn if_match(headers: &HeaderMap) -> Result<Option<i32>, ApiResponseData<ResponseError>> {
+    let Some(value) = headers.get(IF_MATCH) else {
+        return Ok(None);
+    };
+    let value = value.to_str().map_err(|_| precondition_failed())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+
+    value
+        .strip_prefix('"')
+        .and_then(|tag| tag.strip_suffix('"'))
+        .and_then(|version| version.parse().ok())
+        .map(Some)
+        .ok_or_else(precondition_failed)
+}
+
+pub fn precondition_failed() -> ApiResponseData<ResponseError> {
+    ApiResponseData::error(
+        None,
+        "the resource was changed, fetch it again",
+        StatusCode::PRECONDITION_FAILED,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_match_header(value: &str) -> Result<Option<i32>, StatusCode> {
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_MATCH, HeaderValue::from_str(value).unwrap());
+
+        if_match(&headers).map_err(|e| e.into_response().status())
+    }
+
+    #[test]
+    fn if_match_reads_the_tags_etag_writes() {
+        assert_eq!(if_match(&HeaderMap::new()).ok(), Some(None));
+        assert_eq!(if_match_header("*"), Ok(None));
+        assert_eq!(if_match_header(etag(3).to_str().unwrap()), Ok(Some(3)));
+
+        for value in ["3", "W/\"3\"", "\"3\", \"4\"", "\"three\""] {
+            assert_eq!(if_match_header(value), Err(StatusCode::PRECONDITION_FAILED));
+        }
+    }
+}
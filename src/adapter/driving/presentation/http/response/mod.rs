@@ -1,3 +1,5 @@
 pub mod error;
+pub mod etag;
 pub mod field_error;
+pub mod page;
 pub mod response;
@@ -0,0 +1,59 @@
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::driving::presentation::http::response::field_error::ResponseError;
+use crate::adapter::driving::presentation::http::response::response::ApiResponseData;
+use crate::core::port::page::{InvalidCursor, Page, PageRequest, SortKey};
+
+/// Query of a list endpoint: `?limit=20&sort=name&cursor=...`. The cursor is
+/// the `next_cursor` of the previous page, for the same sort.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PageQuery<S> {
+    pub limit: Option<i64>,
+    pub cursor: Option<String>,
+    pub sort: Option<S>,
+}
+
+impl<S: SortKey + Default> PageQuery<S> {
+    pub fn request(&self) -> Result<PageRequest<S>, InvalidCursor> {
+        PageRequest::new(
+            self.limit,
+            self.cursor.as_deref(),
+            self.sort.unwrap_or_default(),
+        )
+    }
+}
+
+/// The `meta` of a page in the response envelope. Clients pass
+/// `next_cursor` back as `cursor` until it is `null`, on the last page.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PageMeta {
+    pub limit: i64,
+    pub sort: &'static str,
+    pub next_cursor: Option<String>,
+    /// Rows of all pages, where counting them is cheap enough.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+}
+
+impl PageMeta {
+    pub fn new<T, S: SortKey>(page: &Page<T>, request: &PageRequest<S>) -> Self {
+        PageMeta {
+            limit: page.limit,
+            sort: request.sort.as_str(),
+            next_cursor: page.next_cursor.as_ref().map(|cursor| cursor.encode()),
+            total: None,
+        }
+    }
+
+    pub fn with_total(mut self, total: i64) -> Self {
+        self.total = Some(total);
+        self
+    }
+}
+
+impl From<InvalidCursor> for ApiResponseData<ResponseError> {
+    fn from(_: InvalidCursor) -> Self {
+        ApiResponseData::error(None, "invalid page cursor", StatusCode::BAD_REQUEST)
+    }
+}
@@ -1,9 +1,10 @@
-use axum::{http::StatusCode, Json, response::IntoResponse};
+use axum::{http::StatusCode, response::IntoResponse, Json};
 use serde::Serialize;
 
 use crate::adapter::driving::presentation::http::response::error::{
     ApiResponseError, ApiResponseErrorObject,
 };
+use crate::adapter::driving::presentation::http::response::page::PageMeta;
 
 // Response types
 pub enum ApiResponseType {
@@ -23,6 +24,12 @@ pub enum ApiResponseData<T: Serialize> {
         data: T,
         status: StatusCode,
     },
+    /// One page of a list, with the metadata to fetch the next.
+    Page {
+        data: T,
+        meta: PageMeta,
+        status: StatusCode,
+    },
     Error {
         error: ApiResponseError,
         status: StatusCode,
@@ -38,6 +45,10 @@ where
         Self::Data { data, status }
     }
 
+    pub fn success_with_page(data: T, meta: PageMeta, status: StatusCode) -> Self {
+        Self::Page { data, meta, status }
+    }
+
     pub fn status_code(status: StatusCode) -> Self {
         Self::StatusCode(status)
     }
@@ -66,6 +77,16 @@ where
                 status,
                 Json(ApiResponseObject::<T> {
                     data: Some(data),
+                    meta: None,
+                    error: None,
+                }),
+            )
+                .into_response(),
+            ApiResponseData::Page { data, meta, status } => (
+                status,
+                Json(ApiResponseObject::<T> {
+                    data: Some(data),
+                    meta: Some(meta),
                     error: None,
                 }),
             )
@@ -74,6 +95,7 @@ where
                 status,
                 Json(ApiResponseObject::<T> {
                     data: None,
+                    meta: None,
                     error: Some(error.into()),
                 }),
             )
@@ -90,6 +112,9 @@ where
     T: Serialize,
 {
     data: Option<T>,
+    /// Set on pages of a list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meta: Option<PageMeta>,
     error: Option<ApiResponseErrorObject>,
 }
 
@@ -1,16 +1,63 @@
 use std::sync::Arc;
 
 use axum::middleware::from_fn_with_state;
-use axum::routing::{get, post};
+use axum::routing::{delete, get, patch, post, put};
 use axum::Router;
+use http::Method;
 use tower_cookies::CookieManagerLayer;
 
 use crate::adapter::driving::presentation::http::handler::_default::health_check_handler::health_checker_handler;
+use crate::adapter::driving::presentation::http::handler::admin::audit::{
+    export_audit_log_handler, list_audit_log_handler,
+};
+use crate::adapter::driving::presentation::http::handler::admin::company::restore_company_handler;
+use crate::adapter::driving::presentation::http::handler::admin::key::rotate_key_handler;
+use crate::adapter::driving::presentation::http::handler::admin::metrics::cache_metrics_handler;
+use crate::adapter::driving::presentation::http::handler::admin::user::{
+    block_user_handler, clear_lockout_handler, delete_user_handler, force_password_reset_handler,
+    get_user_handler, list_users_handler, restore_user_handler, unblock_user_handler,
+    update_user_role_handler,
+};
 use crate::adapter::driving::presentation::http::handler::auth;
+use crate::adapter::driving::presentation::http::handler::auth::api_key::{
+    create_api_key_handler, list_api_keys_handler, revoke_api_key_handler,
+};
 use crate::adapter::driving::presentation::http::handler::auth::login::login_handler;
+use crate::adapter::driving::presentation::http::handler::auth::logout::logout_handler;
 use crate::adapter::driving::presentation::http::handler::auth::me::me_handler;
-use crate::adapter::driving::presentation::http::handler::auth::register::register_handler;
-use crate::adapter::driving::presentation::http::middleware::auth::is_authenticated;
+use crate::adapter::driving::presentation::http::handler::auth::mfa::{
+    confirm_totp_handler, enroll_totp_handler,
+};
+use crate::adapter::driving::presentation::http::handler::auth::oauth::{
+    link_identity_handler, list_identities_handler, oauth2_authorize_handler,
+    oauth2_callback_handler, oauth2_second_factor_handler, unlink_identity_handler,
+};
+use crate::adapter::driving::presentation::http::handler::auth::refresh::refresh_handler;
+use crate::adapter::driving::presentation::http::handler::auth::session::{
+    list_sessions_handler, revoke_session_handler,
+};
+use crate::adapter::driving::presentation::http::handler::company::list::{
+    list_companies_handler, list_employees_handler,
+};
+use crate::adapter::driving::presentation::http::handler::company::profile::{
+    delete_company_handler, get_company_handler, register_company_handler, update_company_handler,
+};
+use crate::adapter::driving::presentation::http::middleware::auth::{
+    is_authenticated, Authenticators,
+};
+use crate::adapter::driving::presentation::http::middleware::role::{authorize, RoutePermission};
+use crate::core::domain::entity::audit::AuditEntry;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::domain::valueobject::scope::Scope;
+use crate::core::port::admin::AdminManagement;
+use crate::core::port::api_key::ApiKeyManagement;
+use crate::core::port::audit::AuditLog;
+use crate::core::port::cache::CacheCounters;
+use crate::core::port::company::CompanyManagement;
+use crate::core::port::identity::IdentityManagement;
+use crate::core::port::mfa::MfaManagement;
+use crate::core::port::session::SessionManagement;
+use crate::core::port::throttle::LoginThrottling;
 use crate::core::port::user::UserManagement;
 use crate::shared::worker::service::TaskContext;
 
@@ -19,6 +66,15 @@ where
     S: UserManagement + 'static,
 {
     pub user_service: Arc<S>,
+    pub company_service: Arc<dyn CompanyManagement>,
+    pub admin_service: Arc<dyn AdminManagement>,
+    pub session_service: Arc<dyn SessionManagement>,
+    pub mfa_service: Arc<dyn MfaManagement>,
+    pub identity_service: Arc<dyn IdentityManagement>,
+    pub api_key_service: Arc<dyn ApiKeyManagement>,
+    pub audit_log: Arc<dyn AuditLog>,
+    pub login_throttle: Arc<dyn LoginThrottling>,
+    pub user_cache: Arc<CacheCounters>,
     pub task_context: TaskContext,
 }
 
@@ -26,27 +82,235 @@ impl<S> AppState<S>
 where
     S: UserManagement + 'static,
 {
-    pub fn new(user_service: Arc<S>, task_context: TaskContext) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_service: Arc<S>,
+        company_service: Arc<dyn CompanyManagement>,
+        admin_service: Arc<dyn AdminManagement>,
+        session_service: Arc<dyn SessionManagement>,
+        mfa_service: Arc<dyn MfaManagement>,
+        identity_service: Arc<dyn IdentityManagement>,
+        api_key_service: Arc<dyn ApiKeyManagement>,
+        audit_log: Arc<dyn AuditLog>,
+        login_throttle: Arc<dyn LoginThrottling>,
+        user_cache: Arc<CacheCounters>,
+        task_context: TaskContext,
+    ) -> Self {
         Self {
             user_service,
+            company_service,
+            admin_service,
+            session_service,
+            mfa_service,
+            identity_service,
+            api_key_service,
+            audit_log,
+            login_throttle,
+            user_cache,
             task_context,
         }
     }
+
+    /// Appends `entry` to the audit log. Failures are logged rather than
+    /// returned, since the action the entry describes already happened.
+    pub async fn audit(&self, entry: AuditEntry) {
+        if let Err(error) = self.audit_log.record(entry.clone()).await {
+            tracing::error!("Audit entry not recorded: {}, {:?}", error, entry);
+        }
+    }
 }
 
+/// Roles required by every protected endpoint, whether it needs a token that
+/// passed 2FA, and the scope an API key needs to call it. Each route registered
+/// in the protected router must have an entry here, otherwise it answers 403.
+pub const ROUTE_PERMISSIONS: &[RoutePermission] = &[
+    RoutePermission::new(Method::GET, "/api/v1/users/me", Role::ALL).with_scope(Scope::ProfileRead),
+    RoutePermission::new(Method::POST, "/api/v1/auth/logout", Role::ALL),
+    RoutePermission::new(Method::GET, "/api/v1/users/me/sessions", Role::ALL),
+    RoutePermission::new(Method::DELETE, "/api/v1/users/me/sessions/:id", Role::ALL),
+    RoutePermission::new(Method::PUT, "/api/v1/users/me/password", Role::ALL),
+    RoutePermission::new(Method::POST, "/api/v1/users/me/mfa/totp", Role::ALL),
+    RoutePermission::new(Method::POST, "/api/v1/users/me/mfa/totp/confirm", Role::ALL),
+    RoutePermission::new(Method::GET, "/api/v1/users/me/identities", Role::ALL),
+    RoutePermission::new(
+        Method::POST,
+        "/api/v1/users/me/identities/:provider",
+        Role::ALL,
+    ),
+    RoutePermission::new(
+        Method::DELETE,
+        "/api/v1/users/me/identities/:provider",
+        Role::ALL,
+    ),
+    RoutePermission::new(Method::GET, "/api/v1/users/me/api-keys", Role::ALL),
+    RoutePermission::new(Method::POST, "/api/v1/users/me/api-keys", Role::ALL),
+    RoutePermission::new(Method::DELETE, "/api/v1/users/me/api-keys/:id", Role::ALL),
+    RoutePermission::new(Method::GET, "/api/v1/companies", Role::ALL)
+        .with_scope(Scope::CompaniesRead),
+    RoutePermission::new(Method::POST, "/api/v1/companies/register", Role::ALL)
+        .with_scope(Scope::CompaniesWrite),
+    RoutePermission::new(Method::GET, "/api/v1/companies/:id", Role::ALL)
+        .with_scope(Scope::CompaniesRead),
+    RoutePermission::new(Method::PATCH, "/api/v1/companies/:id", Role::ALL)
+        .with_scope(Scope::CompaniesWrite),
+    RoutePermission::new(Method::DELETE, "/api/v1/companies/:id", Role::ALL)
+        .with_scope(Scope::CompaniesWrite),
+    RoutePermission::new(Method::GET, "/api/v1/companies/:id/employees", Role::ALL)
+        .with_scope(Scope::CompaniesRead),
+    RoutePermission::new(Method::GET, "/api/v1/admin/users", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminUsersRead),
+    RoutePermission::new(Method::GET, "/api/v1/admin/users/:id", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminUsersRead),
+    RoutePermission::new(Method::DELETE, "/api/v1/admin/users/:id", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminUsersWrite),
+    RoutePermission::new(
+        Method::POST,
+        "/api/v1/admin/users/:id/restore",
+        Role::ADMINS,
+    )
+    .with_mfa()
+    .with_scope(Scope::AdminUsersWrite),
+    RoutePermission::new(Method::PATCH, "/api/v1/admin/users/:id/role", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminUsersWrite),
+    RoutePermission::new(
+        Method::POST,
+        "/api/v1/admin/users/:id/password-reset",
+        Role::ADMINS,
+    )
+    .with_mfa()
+    .with_scope(Scope::AdminUsersWrite),
+    RoutePermission::new(Method::POST, "/api/v1/admin/users/:id/block", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminUsersWrite),
+    RoutePermission::new(
+        Method::DELETE,
+        "/api/v1/admin/users/:id/block",
+        Role::ADMINS,
+    )
+    .with_mfa()
+    .with_scope(Scope::AdminUsersWrite),
+    RoutePermission::new(
+        Method::DELETE,
+        "/api/v1/admin/users/:id/lockout",
+        Role::ADMINS,
+    )
+    .with_mfa()
+    .with_scope(Scope::AdminUsersWrite),
+    RoutePermission::new(
+        Method::POST,
+        "/api/v1/admin/companies/:id/restore",
+        Role::ADMINS,
+    )
+    .with_mfa()
+    .with_scope(Scope::AdminCompaniesWrite),
+    RoutePermission::new(Method::POST, "/api/v1/admin/keys/rotate", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminKeys),
+    RoutePermission::new(Method::GET, "/api/v1/admin/audit-log", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminAuditRead),
+    RoutePermission::new(Method::GET, "/api/v1/admin/audit-log/export", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminAuditRead),
+    RoutePermission::new(Method::GET, "/api/v1/admin/metrics/cache", Role::ADMINS)
+        .with_mfa()
+        .with_scope(Scope::AdminUsersRead),
+];
+
 pub fn make_router<S>(app_state: Arc<AppState<S>>) -> Router
 where
     S: UserManagement + 'static,
 {
     let protected_routes = Router::new()
+        .route("/api/v1/users/me", get(me_handler))
+        .route("/api/v1/auth/logout", post(logout_handler))
+        .route("/api/v1/users/me/sessions", get(list_sessions_handler))
         .route(
-            "/api/v1/users/me",
-            get(me_handler).layer(from_fn_with_state(app_state.clone(), is_authenticated)),
+            "/api/v1/users/me/sessions/:id",
+            delete(revoke_session_handler),
         )
         .route(
-            "/api/v1/companies/register",
-            post(register_handler).layer(from_fn_with_state(app_state.clone(), is_authenticated)),
-        );
+            "/api/v1/users/me/password",
+            put(auth::password::change_password_handler),
+        )
+        .route("/api/v1/users/me/mfa/totp", post(enroll_totp_handler))
+        .route(
+            "/api/v1/users/me/mfa/totp/confirm",
+            post(confirm_totp_handler),
+        )
+        .route("/api/v1/users/me/identities", get(list_identities_handler))
+        .route(
+            "/api/v1/users/me/identities/:provider",
+            post(link_identity_handler).delete(unlink_identity_handler),
+        )
+        .route(
+            "/api/v1/users/me/api-keys",
+            get(list_api_keys_handler).post(create_api_key_handler),
+        )
+        .route(
+            "/api/v1/users/me/api-keys/:id",
+            delete(revoke_api_key_handler),
+        )
+        .route("/api/v1/companies", get(list_companies_handler))
+        .route("/api/v1/companies/register", post(register_company_handler))
+        .route(
+            "/api/v1/companies/:id",
+            get(get_company_handler)
+                .patch(update_company_handler)
+                .delete(delete_company_handler),
+        )
+        .route(
+            "/api/v1/companies/:id/employees",
+            get(list_employees_handler),
+        )
+        .route("/api/v1/admin/users", get(list_users_handler))
+        .route(
+            "/api/v1/admin/users/:id",
+            get(get_user_handler).delete(delete_user_handler),
+        )
+        .route(
+            "/api/v1/admin/users/:id/restore",
+            post(restore_user_handler),
+        )
+        .route(
+            "/api/v1/admin/users/:id/role",
+            patch(update_user_role_handler),
+        )
+        .route(
+            "/api/v1/admin/users/:id/password-reset",
+            post(force_password_reset_handler),
+        )
+        .route(
+            "/api/v1/admin/users/:id/block",
+            post(block_user_handler).delete(unblock_user_handler),
+        )
+        .route(
+            "/api/v1/admin/users/:id/lockout",
+            delete(clear_lockout_handler),
+        )
+        .route(
+            "/api/v1/admin/companies/:id/restore",
+            post(restore_company_handler),
+        )
+        .route("/api/v1/admin/keys/rotate", post(rotate_key_handler))
+        .route("/api/v1/admin/audit-log", get(list_audit_log_handler))
+        .route(
+            "/api/v1/admin/audit-log/export",
+            get(export_audit_log_handler),
+        )
+        .route("/api/v1/admin/metrics/cache", get(cache_metrics_handler))
+        .route_layer(from_fn_with_state(ROUTE_PERMISSIONS, authorize))
+        .route_layer(from_fn_with_state(
+            Authenticators {
+                api_keys: Arc::clone(&app_state.api_key_service),
+                sessions: Arc::clone(&app_state.session_service),
+            },
+            is_authenticated,
+        ));
 
     let public_routes = Router::new()
         .route("/api/v1/healthchecker", get(health_checker_handler))
@@ -54,7 +318,20 @@ where
             "/api/v1/auth/register",
             post(auth::register::register_handler),
         )
-        .route("/api/v1/auth/login", post(login_handler));
+        .route("/api/v1/auth/login", post(login_handler))
+        .route("/api/v1/auth/refresh", post(refresh_handler))
+        .route(
+            "/api/v1/auth/oauth2/:provider",
+            get(oauth2_authorize_handler),
+        )
+        .route(
+            "/api/v1/auth/oauth2/:provider/callback",
+            get(oauth2_callback_handler),
+        )
+        .route(
+            "/api/v1/auth/oauth2/:provider/mfa",
+            post(oauth2_second_factor_handler),
+        );
 
     Router::new()
         .merge(public_routes)
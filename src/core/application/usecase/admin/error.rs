@@ -0,0 +1,41 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum AdminError {
+    UserNotFound,
+    InvalidIdFormat,
+    /// The user changed since the version the request was based on.
+    VersionMismatch,
+    /// Admins cannot take their own admin role away.
+    SelfDemotion,
+    /// The role change or deletion would leave no admin.
+    LastAdmin,
+    /// Admins cannot block themselves.
+    SelfBlock,
+    /// Admins cannot delete themselves.
+    SelfDelete,
+    DbInternalError,
+    HashingError,
+    SigningKeyNotFound,
+    SigningKeyError,
+}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminError::UserNotFound => write!(f, "User not found"),
+            AdminError::InvalidIdFormat => write!(f, "Invalid ID format"),
+            AdminError::VersionMismatch => write!(f, "User was changed in the meantime"),
+            AdminError::SelfDemotion => write!(f, "Admins cannot demote themselves"),
+            AdminError::LastAdmin => write!(f, "The last admin cannot be demoted or deleted"),
+            AdminError::SelfBlock => write!(f, "Admins cannot block themselves"),
+            AdminError::SelfDelete => write!(f, "Admins cannot delete themselves"),
+            AdminError::DbInternalError => write!(f, "Database internal error"),
+            AdminError::HashingError => write!(f, "Password hashing error"),
+            AdminError::SigningKeyNotFound => write!(f, "Signing key not found"),
+            AdminError::SigningKeyError => write!(f, "Signing key cannot be rotated"),
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::core::application::usecase::admin::error::AdminError;
+use crate::core::application::usecase::auth::error::TokenError;
+use crate::core::application::usecase::auth::token::rotate_signing_key;
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::port::admin::{AdminManagement, UserPage};
+use crate::core::port::error::VersionConflict;
+use crate::core::port::page::PageRequest;
+use crate::core::port::throttle::LoginThrottling;
+use crate::core::port::user::{UserFilter, UserRepo, UserSort};
+
+#[derive(Clone)]
+pub struct AdminService<K>
+where
+    K: UserRepo,
+{
+    user_repository: Arc<K>,
+    login_throttle: Arc<dyn LoginThrottling>,
+}
+
+impl<K> AdminService<K>
+where
+    K: UserRepo,
+{
+    pub fn new(user_repository: Arc<K>, login_throttle: Arc<dyn LoginThrottling>) -> Self {
+        Self {
+            user_repository,
+            login_throttle,
+        }
+    }
+
+    async fn find_user(&self, id: UserId) -> Result<User, AdminError> {
+        self.user_repository
+            .find_by_id(id)
+            .await
+            .map_err(|_| AdminError::DbInternalError)?
+            .ok_or(AdminError::UserNotFound)
+    }
+
+    async fn update_user(&self, id: UserId, user: &User) -> Result<User, AdminError> {
+        self.user_repository.update(id, user).await.map_err(|e| {
+            if e.is::<VersionConflict>() {
+                AdminError::VersionMismatch
+            } else {
+                AdminError::DbInternalError
+            }
+        })
+    }
+
+    /// Fails unless there is an admin besides the one about to be demoted or
+    /// deleted.
+    async fn ensure_other_admin(&self) -> Result<(), AdminError> {
+        let admins = UserFilter {
+            role: Some(Role::ADMIN),
+            ..UserFilter::default()
+        };
+        let count = self
+            .user_repository
+            .count_by_filter(&admins)
+            .await
+            .map_err(|_| AdminError::DbInternalError)?;
+
+        if count <= 1 {
+            Err(AdminError::LastAdmin)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl<K> AdminManagement for AdminService<K>
+where
+    K: UserRepo,
+{
+    async fn list_users(
+        &self,
+        filter: &UserFilter,
+        page: &PageRequest<UserSort>,
+    ) -> Result<UserPage, AdminError> {
+        let users = self
+            .user_repository
+            .find_by_filter(filter, page)
+            .await
+            .map_err(|_| AdminError::DbInternalError)?;
+        let total = self
+            .user_repository
+            .count_by_filter(filter)
+            .await
+            .map_err(|_| AdminError::DbInternalError)?;
+
+        Ok(UserPage { users, total })
+    }
+
+    async fn get_user(&self, id: UserId) -> Result<User, AdminError> {
+        self.find_user(id).await
+    }
+
+    async fn change_role(
+        &self,
+        actor: UserId,
+        id: UserId,
+        role: Role,
+        version: Option<i32>,
+    ) -> Result<User, AdminError> {
+        let mut user = self.find_user(id).await?;
+        if version.is_some_and(|version| version != user.version) {
+            return Err(AdminError::VersionMismatch);
+        }
+        if user.role == Role::ADMIN && role != Role::ADMIN {
+            if actor == id {
+                return Err(AdminError::SelfDemotion);
+            }
+            self.ensure_other_admin().await?;
+        }
+        user.update_role(Some(role))
+            .map_err(|_| AdminError::DbInternalError)?;
+
+        self.update_user(id, &user).await
+    }
+
+    async fn delete_user(&self, actor: UserId, id: UserId) -> Result<(), AdminError> {
+        if actor == id {
+            return Err(AdminError::SelfDelete);
+        }
+        let user = self.find_user(id).await?;
+        if user.role == Role::ADMIN {
+            self.ensure_other_admin().await?;
+        }
+
+        self.user_repository
+            .delete(id)
+            .await
+            .map_err(|_| AdminError::DbInternalError)
+    }
+
+    async fn restore_user(&self, id: UserId) -> Result<User, AdminError> {
+        self.user_repository
+            .restore(id)
+            .await
+            .map_err(|_| AdminError::DbInternalError)?
+            .ok_or(AdminError::UserNotFound)
+    }
+
+    async fn force_password_reset(&self, id: UserId) -> Result<User, AdminError> {
+        let mut user = self.find_user(id).await?;
+        user.force_password_reset()
+            .map_err(|_| AdminError::HashingError)?;
+
+        self.update_user(id, &user).await
+    }
+
+    async fn block_user(&self, actor: UserId, id: UserId) -> Result<User, AdminError> {
+        if actor == id {
+            return Err(AdminError::SelfBlock);
+        }
+        let mut user = self.find_user(id).await?;
+        user.block();
+
+        self.update_user(id, &user).await
+    }
+
+    async fn unblock_user(&self, id: UserId) -> Result<User, AdminError> {
+        let mut user = self.find_user(id).await?;
+        user.unblock();
+
+        self.update_user(id, &user).await
+    }
+
+    async fn clear_lockout(&self, id: UserId) -> Result<(), AdminError> {
+        let user = self.find_user(id).await?;
+
+        self.login_throttle
+            .clear(user.email.as_str())
+            .await
+            .map_err(|_| AdminError::DbInternalError)
+    }
+
+    async fn rotate_signing_key(&self, kid: Option<String>) -> Result<String, AdminError> {
+        rotate_signing_key(kid.as_deref()).map_err(|e| match e {
+            TokenError::UnknownKey => AdminError::SigningKeyNotFound,
+            _ => AdminError::SigningKeyError,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::attempt::AttemptRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+    use crate::core::application::usecase::auth::throttle::LoginThrottleService;
+    use crate::core::domain::valueobject::date::Timestamp;
+    use crate::core::domain::valueobject::email::Email;
+    use crate::core::domain::valueobject::password::HashedPassword;
+    use crate::shared::config::config::Config;
+    use crate::shared::config::environment::Environment;
+
+    fn service() -> AdminService<UserRepository> {
+        AdminService::new(
+            Arc::new(UserRepository::new(Arc::new(MemStore::new()))),
+            Arc::new(LoginThrottleService::new(
+                Arc::new(AttemptRepository::new()),
+            )),
+        )
+    }
+
+    async fn save(service: &AdminService<UserRepository>, email: &str, role: Role) -> User {
+        service
+            .user_repository
+            .save(&User {
+                id: None,
+                name: "John".to_string(),
+                surname: "Doe".to_string(),
+                email: Email::parse(email).unwrap(),
+                role,
+                password_hash: HashedPassword::from("hash".to_string()),
+                reset_token: None,
+                reset_sent_at: None,
+                email_verification_token: None,
+                email_verification_sent_at: None,
+                email_verified_at: None,
+                blocked_at: None,
+                created_at: Timestamp::now_utc(),
+                updated_at: Timestamp::now_utc(),
+                version: 1,
+                deleted_at: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn changes_roles_at_the_expected_version() {
+        let service = service();
+        let admin = save(&service, "admin@example.com", Role::ADMIN).await;
+        let user = save(&service, "user@example.com", Role::USER).await;
+        let (admin_id, user_id) = (admin.id.unwrap(), user.id.unwrap());
+
+        assert!(matches!(
+            service
+                .change_role(admin_id, user_id, Role::MODERATOR, Some(user.version + 1))
+                .await,
+            Err(AdminError::VersionMismatch)
+        ));
+        let changed = service
+            .change_role(admin_id, user_id, Role::MODERATOR, Some(user.version))
+            .await
+            .unwrap();
+        assert_eq!(changed.role, Role::MODERATOR);
+        assert_eq!(changed.version, user.version + 1);
+        assert!(matches!(
+            service
+                .change_role(admin_id, UserId::generate(), Role::USER, None)
+                .await,
+            Err(AdminError::UserNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn admins_cannot_demote_themselves_or_the_last_admin() {
+        let service = service();
+        let admin = save(&service, "admin@example.com", Role::ADMIN).await;
+        let admin_id = admin.id.unwrap();
+
+        assert!(matches!(
+            service
+                .change_role(admin_id, admin_id, Role::USER, None)
+                .await,
+            Err(AdminError::SelfDemotion)
+        ));
+        assert!(matches!(
+            service
+                .change_role(UserId::generate(), admin_id, Role::USER, None)
+                .await,
+            Err(AdminError::LastAdmin)
+        ));
+
+        let other = save(&service, "other@example.com", Role::ADMIN).await;
+        let demoted = service
+            .change_role(other.id.unwrap(), admin_id, Role::USER, None)
+            .await
+            .unwrap();
+        assert_eq!(demoted.role, Role::USER);
+        assert!(service
+            .change_role(admin_id, admin_id, Role::ADMIN, None)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn admins_cannot_delete_themselves_or_the_last_admin() {
+        let service = service();
+        let admin = save(&service, "admin@example.com", Role::ADMIN).await;
+        let admin_id = admin.id.unwrap();
+
+        assert!(matches!(
+            service.delete_user(admin_id, admin_id).await,
+            Err(AdminError::SelfDelete)
+        ));
+        assert!(matches!(
+            service.delete_user(UserId::generate(), admin_id).await,
+            Err(AdminError::LastAdmin)
+        ));
+
+        let other = save(&service, "other@example.com", Role::ADMIN).await;
+        let other_id = other.id.unwrap();
+        service.delete_user(other_id, admin_id).await.unwrap();
+        assert!(matches!(
+            service.get_user(admin_id).await,
+            Err(AdminError::UserNotFound)
+        ));
+        assert!(matches!(
+            service.delete_user(UserId::generate(), other_id).await,
+            Err(AdminError::LastAdmin)
+        ));
+        let user = save(&service, "user@example.com", Role::USER).await;
+        assert!(service
+            .delete_user(other_id, user.id.unwrap())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn forced_resets_replace_the_password_and_issue_a_token() {
+        // Hashing reads its parameters from the configuration.
+        let _ = Config::new(&Environment::Test);
+        let service = service();
+        let user = save(&service, "user@example.com", Role::USER).await;
+
+        let reset = service
+            .force_password_reset(user.id.unwrap())
+            .await
+            .unwrap();
+
+        assert_ne!(reset.password_hash, user.password_hash);
+        assert!(reset.reset_token.is_some());
+        assert!(reset.reset_sent_at.is_some());
+        assert_eq!(
+            service
+                .get_user(user.id.unwrap())
+                .await
+                .unwrap()
+                .reset_token,
+            reset.reset_token
+        );
+    }
+
+    #[tokio::test]
+    async fn blocks_and_unblocks_users() {
+        let service = service();
+        let admin = save(&service, "admin@example.com", Role::ADMIN).await;
+        let user = save(&service, "user@example.com", Role::USER).await;
+        let (admin_id, user_id) = (admin.id.unwrap(), user.id.unwrap());
+
+        assert!(matches!(
+            service.block_user(admin_id, admin_id).await,
+            Err(AdminError::SelfBlock)
+        ));
+        let blocked = service.block_user(admin_id, user_id).await.unwrap();
+        assert!(blocked.is_blocked());
+        let again = service.block_user(admin_id, user_id).await.unwrap();
+        assert_eq!(again.blocked_at, blocked.blocked_at);
+
+        let unblocked = service.unblock_user(user_id).await.unwrap();
+        assert!(!unblocked.is_blocked());
+        assert!(!service.get_user(user_id).await.unwrap().is_blocked());
+    }
+
+    #[tokio::test]
+    async fn lists_a_page_and_the_total() {
+        let service = service();
+        for email in ["a@example.com", "b@example.com", "c@example.com"] {
+            save(&service, email, Role::USER).await;
+        }
+
+        let page = service
+            .list_users(
+                &UserFilter::default(),
+                &PageRequest::new(Some(2), None, UserSort::Email).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(page.users.items.len(), 2);
+        assert!(page.users.next_cursor.is_some());
+        assert_eq!(page.total, 3);
+    }
+}
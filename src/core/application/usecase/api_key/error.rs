@@ -0,0 +1,25 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ApiKeyError {
+    /// Unknown, revoked or expired key, or its owner is gone.
+    InvalidKey,
+    KeyNotFound,
+    InvalidName,
+    ExpiryInPast,
+    DbInternalError,
+}
+
+impl fmt::Display for ApiKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKeyError::InvalidKey => write!(f, "Invalid API key"),
+            ApiKeyError::KeyNotFound => write!(f, "API key not found"),
+            ApiKeyError::InvalidName => write!(f, "API key name must not be empty"),
+            ApiKeyError::ExpiryInPast => write!(f, "API key expiry is in the past"),
+            ApiKeyError::DbInternalError => write!(f, "Database internal error"),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::core::application::usecase::api_key::error::ApiKeyError;
+use crate::core::domain::entity::api_key::ApiKey;
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::api_key::{ApiKeyManagement, ApiKeyRepo, NewApiKey};
+use crate::core::port::user::UserRepo;
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyService<K, U>
+where
+    K: ApiKeyRepo,
+    U: UserRepo,
+{
+    api_key_repository: Arc<K>,
+    user_repository: Arc<U>,
+}
+
+impl<K, U> ApiKeyService<K, U>
+where
+    K: ApiKeyRepo,
+    U: UserRepo,
+{
+    pub fn new(api_key_repository: Arc<K>, user_repository: Arc<U>) -> Self {
+        Self {
+            api_key_repository,
+            user_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl<K, U> ApiKeyManagement for ApiKeyService<K, U>
+where
+    K: ApiKeyRepo,
+    U: UserRepo,
+{
+    async fn create(
+        &self,
+        user_id: UserId,
+        mfa: bool,
+        input: NewApiKey,
+    ) -> Result<(ApiKey, String), ApiKeyError> {
+        let name = input.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiKeyError::InvalidName);
+        }
+        if input
+            .expires_at
+            .as_ref()
+            .is_some_and(|expires_at| expires_at.datetime <= Timestamp::now_utc().datetime)
+        {
+            return Err(ApiKeyError::ExpiryInPast);
+        }
+
+        let mut scopes = input.scopes;
+        scopes.sort_by_key(|scope| scope.as_str());
+        scopes.dedup();
+        let (api_key, key) = ApiKey::new(user_id, name, scopes, mfa, input.expires_at);
+
+        let api_key = self
+            .api_key_repository
+            .save(&api_key)
+            .await
+            .map_err(|_| ApiKeyError::DbInternalError)?;
+
+        Ok((api_key, key))
+    }
+
+    async fn list(&self, user_id: UserId) -> Result<Vec<ApiKey>, ApiKeyError> {
+        self.api_key_repository
+            .find_by_user(user_id)
+            .await
+            .map_err(|_| ApiKeyError::DbInternalError)
+    }
+
+    async fn revoke(&self, user_id: UserId, id: Uuid) -> Result<(), ApiKeyError> {
+        let revoked = self
+            .api_key_repository
+            .revoke(user_id, id)
+            .await
+            .map_err(|_| ApiKeyError::DbInternalError)?;
+
+        if !revoked {
+            return Err(ApiKeyError::KeyNotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn authenticate(&self, key: &str) -> Result<(ApiKey, User), ApiKeyError> {
+        let prefix = ApiKey::prefix_of(key).ok_or(ApiKeyError::InvalidKey)?;
+        let api_key = self
+            .api_key_repository
+            .find_by_prefix(prefix)
+            .await
+            .map_err(|_| ApiKeyError::DbInternalError)?
+            .filter(|api_key| api_key.matches(key) && api_key.is_active())
+            .ok_or(ApiKeyError::InvalidKey)?;
+
+        // The owner's current role applies, not the one at creation.
+        let user = self
+            .user_repository
+            .find_by_id(api_key.user_id)
+            .await
+            .map_err(|_| ApiKeyError::DbInternalError)?
+            .filter(|user| !user.is_blocked())
+            .ok_or(ApiKeyError::InvalidKey)?;
+
+        if let Err(error) = self.api_key_repository.touch(api_key.id).await {
+            tracing::warn!("API key {} last use not recorded: {:?}", api_key.id, error);
+        }
+
+        Ok((api_key, user))
+    }
+}
@@ -0,0 +1,16 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum AuditError {
+    DbInternalError,
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditError::DbInternalError => write!(f, "Database internal error"),
+        }
+    }
+}
@@ -4,13 +4,12 @@ use serde_derive::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum TokenError {
-    HmacFailNewFromSlice,
+    InvalidKey,
+    UnknownKey,
+    KeyGenerationUnsupported,
+    EncodingError,
     InvalidFormat,
-    CannotDecodeIdent,
-    CannotDecodeIat,
-    CannotDecodeExp,
     SignatureNotMatching,
-    ExpNotIso,
     Expired,
 }
 
@@ -31,11 +30,32 @@ pub enum RegisterError<T> {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum LoginError {
-    UserNotFound,
+    /// Unknown email or wrong password; the two are not told apart.
     BadCredentials,
+    /// The account or IP is locked, for this many more seconds.
+    TooManyAttempts(u64),
+    /// Password accepted, the account also needs a TOTP or recovery code.
+    MfaRequired,
+    InvalidMfaCode,
+    /// The provider identity has no verified email to sign in with.
     UserProviderNotValid,
+    /// Correct credentials of a blocked user.
+    UserBlocked,
     DbInternalError,
     JWTEncodingError,
+    HashingError,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PasswordError<T> {
+    BadClientData(T),
+    /// The current password does not match.
+    BadCredentials,
+    UserNotFound,
+    /// The user changed while the password was being changed.
+    VersionMismatch,
+    DbInternalError,
+    HashingError,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -1,44 +1,94 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use validator::ValidationErrors;
+use validator::{ValidationError, ValidationErrors};
 
 use crate::adapter::driving::presentation::http::handler::auth::login::UserLoginRequest;
+use crate::adapter::driving::presentation::http::handler::auth::password::ChangePasswordRequest;
 use crate::adapter::driving::presentation::http::handler::auth::register::UserRegisterRequest;
-use crate::core::application::usecase::auth::error::{LoginError, MeError, RegisterError};
+use crate::core::application::usecase::auth::error::{
+    LoginError, MeError, PasswordError, RegisterError,
+};
 use crate::core::domain::entity::user::User;
+use crate::core::domain::error::DomainError;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::password::HashedPassword;
 use crate::core::domain::valueobject::role;
-use crate::core::port::user::{UserManagement, UserRepo};
+use crate::core::port::error::{AlreadyTaken, VersionConflict};
+use crate::core::port::mfa::MfaRepo;
+use crate::core::port::user::{LoginSuccess, UserManagement, UserRepo};
+use crate::shared::ctx::ctx::Ctx;
 
 #[derive(Debug, Clone)]
-pub struct UserService<K>
+pub struct UserService<K, M>
 where
     K: UserRepo,
+    M: MfaRepo,
 {
     user_repository: Arc<K>,
+    mfa_repository: Arc<M>,
 }
 
-impl<K> UserService<K>
+impl<K, M> UserService<K, M>
 where
     K: UserRepo,
+    M: MfaRepo,
 {
-    pub fn new(user_repository: Arc<K>) -> Self {
-        Self { user_repository }
+    pub fn new(user_repository: Arc<K>, mfa_repository: Arc<M>) -> Self {
+        Self {
+            user_repository,
+            mfa_repository,
+        }
+    }
+
+    /// Moves the stored hash to the current parameters if they changed.
+    async fn rehash_if_outdated(&self, user: User, password: &str) -> User {
+        if !user.password_hash.needs_rehash() {
+            return user;
+        }
+
+        // The password is known right now, so this is the only chance to move
+        // the stored hash to the current parameters. Failing to do so must not
+        // fail the login.
+        let mut rehashed_user = user.clone();
+        let rehashed = match rehashed_user.set_password(password) {
+            Ok(()) => match user.id {
+                Some(id) => self.user_repository.update(id, &rehashed_user).await,
+                None => Err(DomainError::MissingId("user").into()),
+            },
+            Err(error) => Err(error),
+        };
+
+        match rehashed {
+            Ok(user) => user,
+            Err(error) => {
+                tracing::warn!("Password rehash failed: {:?}", error);
+                user
+            }
+        }
     }
 }
 
 #[async_trait]
-impl<K> UserManagement for UserService<K>
+impl<K, M> UserManagement for UserService<K, M>
 where
     K: UserRepo,
+    M: MfaRepo,
 {
     async fn register(
         &self,
         input: &UserRegisterRequest,
     ) -> Result<User, RegisterError<ValidationErrors>> {
+        let email = Email::parse(&input.email).map_err(|_| {
+            let mut errors = ValidationErrors::new();
+            errors.add("email", ValidationError::new("email"));
+            RegisterError::BadClientData(errors)
+        })?;
         let found_user = self
             .user_repository
-            .find_by_email(input.email.as_str())
+            .find_by_email(&email)
             .await
             .map_err(|_| RegisterError::DbInternalError)?;
 
@@ -49,51 +99,175 @@ where
         let new_user = User::new(
             input.name.clone(),
             input.surname.clone(),
-            input.email.clone(),
+            email,
             input.password.clone(),
             role::Role::USER,
-        );
+        )
+        .map_err(|_| RegisterError::HashingError)?;
 
-        let registered_user = self
-            .user_repository
-            .save(&new_user)
-            .await
-            .map_err(|_| RegisterError::DbInternalError)?;
+        // The lookup above misses deleted users, whose email stays taken.
+        let registered_user = self.user_repository.save(&new_user).await.map_err(|e| {
+            if e.is::<AlreadyTaken>() {
+                RegisterError::UserAlreadyRegistered
+            } else {
+                RegisterError::DbInternalError
+            }
+        })?;
 
         Ok(registered_user)
     }
 
-    async fn login(&self, input: &UserLoginRequest) -> Result<User, LoginError> {
-        let found_user = self
-            .user_repository
-            .find_by_email(input.email.as_str())
-            .await
-            .map_err(|_| LoginError::DbInternalError)?;
+    async fn login(&self, input: &UserLoginRequest) -> Result<LoginSuccess, LoginError> {
+        let found_user = match Email::parse(&input.email) {
+            Ok(email) => self
+                .user_repository
+                .find_by_email(&email)
+                .await
+                .map_err(|_| LoginError::DbInternalError)?,
+            Err(_) => None,
+        };
 
         let found_user = match found_user {
             Some(user) => user,
-            None => return Err(LoginError::UserNotFound),
+            None => {
+                HashedPassword::verify_dummy(&input.password);
+                return Err(LoginError::BadCredentials);
+            }
         };
 
         match found_user.password_hash.verify_password(&input.password) {
-            Ok(true) => Ok(found_user),
-            Ok(false) => Err(LoginError::BadCredentials),
-            Err(_) => Err(LoginError::JWTEncodingError),
+            Ok(true) => {}
+            Ok(false) => return Err(LoginError::BadCredentials),
+            Err(_) => return Err(LoginError::HashingError),
+        }
+        if found_user.is_blocked() {
+            return Err(LoginError::UserBlocked);
+        }
+
+        let user_id = found_user.id.ok_or(LoginError::DbInternalError)?;
+        let mfa = self
+            .verify_second_factor(user_id, input.otp.as_deref())
+            .await?;
+        let user = self.rehash_if_outdated(found_user, &input.password).await;
+
+        Ok(LoginSuccess { user, mfa })
+    }
+
+    async fn verify_second_factor(
+        &self,
+        user_id: UserId,
+        code: Option<&str>,
+    ) -> Result<bool, LoginError> {
+        let mfa = self
+            .mfa_repository
+            .find_by_user(user_id)
+            .await
+            .map_err(|_| LoginError::DbInternalError)?;
+        let mut mfa = match mfa {
+            Some(mfa) if mfa.is_enabled() => mfa,
+            _ => return Ok(false),
+        };
+
+        let code = code.ok_or(LoginError::MfaRequired)?;
+        let used = mfa
+            .verify(code, Timestamp::now_utc().to_unix_timestamp())
+            .ok_or(LoginError::InvalidMfaCode)?;
+
+        // Records the used step or recovery code, so neither works twice, even
+        // for logins verifying it at the same time.
+        let recorded = self
+            .mfa_repository
+            .record_use(user_id, &used)
+            .await
+            .map_err(|_| LoginError::DbInternalError)?;
+        if !recorded {
+            return Err(LoginError::InvalidMfaCode);
         }
+
+        Ok(true)
     }
 
-    async fn me(&self, email: &str) -> Result<User, MeError> {
+    async fn me(&self, ctx: &Ctx) -> Result<User, MeError> {
         let user = self
             .user_repository
-            .find_by_email(email)
+            .find_by_id(ctx.user_id())
             .await
             .map_err(|_| MeError::DbInternalError)?
             .ok_or(MeError::UserNotFound)?;
         Ok(user)
     }
 
+    async fn change_password(
+        &self,
+        ctx: &Ctx,
+        input: &ChangePasswordRequest,
+    ) -> Result<User, PasswordError<ValidationErrors>> {
+        let mut user = self
+            .user_repository
+            .find_by_id(ctx.user_id())
+            .await
+            .map_err(|_| PasswordError::DbInternalError)?
+            .ok_or(PasswordError::UserNotFound)?;
+
+        match user.password_hash.verify_password(&input.current_password) {
+            Ok(true) => {}
+            Ok(false) => return Err(PasswordError::BadCredentials),
+            Err(_) => return Err(PasswordError::HashingError),
+        }
+        user.set_password(&input.new_password)
+            .map_err(|_| PasswordError::HashingError)?;
+
+        self.user_repository
+            .update(ctx.user_id(), &user)
+            .await
+            .map_err(|e| {
+                if e.is::<VersionConflict>() {
+                    PasswordError::VersionMismatch
+                } else {
+                    PasswordError::DbInternalError
+                }
+            })
+    }
+
     // async fn update_profile(&self, input: &UpdateUserPofileInput) -> Result<(), Error> {
     //     todo!()
     // }
     //
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::mfa::MfaRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+    use crate::shared::config::config::Config;
+    use crate::shared::config::environment::Environment;
+
+    fn request(email: &str) -> UserRegisterRequest {
+        UserRegisterRequest {
+            name: "John".to_string(),
+            surname: "Doe".to_string(),
+            email: email.to_string(),
+            password: "password".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn deleted_users_keep_their_email_registered() {
+        let _ = Config::new(&Environment::Test);
+        let users = Arc::new(UserRepository::new(Arc::new(MemStore::new())));
+        let service = UserService::new(Arc::clone(&users), Arc::new(MfaRepository::new()));
+        let user = service
+            .register(&request("john@example.com"))
+            .await
+            .unwrap();
+
+        users.delete(user.id.unwrap()).await.unwrap();
+
+        assert!(matches!(
+            service.register(&request("john@example.com")).await,
+            Err(RegisterError::UserAlreadyRegistered)
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::core::application::usecase::auth::error::LoginError;
+use crate::core::port::throttle::{AttemptRepo, LoginThrottling};
+use crate::shared::config::config::{Config, Lockout};
+
+#[derive(Debug, Clone)]
+pub struct LoginThrottleService<K>
+where
+    K: AttemptRepo,
+{
+    attempt_repository: Arc<K>,
+}
+
+impl<K> LoginThrottleService<K>
+where
+    K: AttemptRepo,
+{
+    pub fn new(attempt_repository: Arc<K>) -> Self {
+        Self { attempt_repository }
+    }
+
+    async fn fail(&self, key: &str, max_attempts: u32, config: &Lockout) -> Result<(), LoginError> {
+        let failures = self
+            .attempt_repository
+            .increment(key, config.max_duration)
+            .await
+            .map_err(|_| LoginError::DbInternalError)?;
+
+        if let Some(duration) = lockout_duration(failures, max_attempts, config) {
+            self.attempt_repository
+                .lock(key, duration)
+                .await
+                .map_err(|_| LoginError::DbInternalError)?;
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_unlocked(&self, key: &str) -> Result<(), LoginError> {
+        match self
+            .attempt_repository
+            .lock_remaining(key)
+            .await
+            .map_err(|_| LoginError::DbInternalError)?
+        {
+            Some(retry_after) => Err(LoginError::TooManyAttempts(retry_after)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<K> LoginThrottling for LoginThrottleService<K>
+where
+    K: AttemptRepo,
+{
+    async fn check(&self, email: &str, ip: Option<&str>) -> Result<(), LoginError> {
+        self.ensure_unlocked(&account_key(email)).await?;
+
+        match ip {
+            Some(ip) => self.ensure_unlocked(&ip_key(ip)).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn record_failure(&self, email: &str, ip: Option<&str>) -> Result<(), LoginError> {
+        let config = &Config::get().auth.lockout;
+
+        self.fail(&account_key(email), config.max_attempts, config)
+            .await?;
+
+        match ip {
+            Some(ip) => self.fail(&ip_key(ip), config.ip_max_attempts, config).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn record_success(&self, email: &str) -> Result<(), LoginError> {
+        self.clear(email).await
+    }
+
+    async fn clear(&self, email: &str) -> Result<(), LoginError> {
+        self.attempt_repository
+            .clear(&account_key(email))
+            .await
+            .map_err(|_| LoginError::DbInternalError)
+    }
+}
+
+// Accounts are keyed by email whether they exist or not, so lockouts do not
+// tell which emails are registered.
+fn account_key(email: &str) -> String {
+    format!("login:account:{}", email.trim().to_lowercase())
+}
+
+fn ip_key(ip: &str) -> String {
+    format!("login:ip:{}", ip)
+}
+
+/// Lock duration after `failures` failed attempts, if any: `base_duration`
+/// once `max_attempts` is reached, doubling with every further failure.
+fn lockout_duration(failures: u32, max_attempts: u32, config: &Lockout) -> Option<u64> {
+    if failures < max_attempts {
+        return None;
+    }
+
+    let doublings = (failures - max_attempts).min(32);
+    let duration = config.base_duration.saturating_mul(1 << doublings);
+
+    Some(duration.min(config.max_duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lockout_doubles_up_to_max_duration() {
+        let config = Lockout {
+            max_attempts: 3,
+            ip_max_attempts: 10,
+            base_duration: 60,
+            max_duration: 600,
+        };
+
+        let durations: Vec<_> = (1..=8)
+            .map(|failures| lockout_duration(failures, config.max_attempts, &config))
+            .collect();
+
+        assert_eq!(
+            durations,
+            vec![
+                None,
+                None,
+                Some(60),
+                Some(120),
+                Some(240),
+                Some(480),
+                Some(600),
+                Some(600)
+            ]
+        );
+        assert_eq!(lockout_duration(u32::MAX, 3, &config), Some(600));
+    }
+
+    #[test]
+    fn account_keys_ignore_case() {
+        assert_eq!(
+            account_key(" John.Doe@Example.com"),
+            account_key("john.doe@example.com")
+        );
+    }
+}
@@ -1,143 +1,538 @@
-use crate::core::application::usecase::auth::error::TokenError;
+use std::fs;
+use std::sync::{OnceLock, RwLock};
 
-use crate::core::domain::valueobject::date::parse_utc;
-use crate::shared::config::config::Config;
-use crate::shared::data::base64::{b64u_decode_to_string, b64u_encode};
-use crate::shared::data::date::{now_utc, now_utc_plus_sec_str};
-use hmac::Hmac;
-use sha2::digest::Mac;
-use sha2::Sha512;
-use std::fmt::Display;
-use std::str::FromStr;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use rand::RngCore;
+use serde_derive::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug)]
-#[cfg_attr(test, derive(PartialEq))]
-pub struct Token {
-    pub ident: String,     // Identifier (username for example).
-    pub exp: String,       // Expiration date in Rfc3339.
-    pub sign_b64u: String, // Signature, base64url encoded.
+use crate::core::application::usecase::auth::error::TokenError;
+use crate::core::domain::entity::session::Session;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::role::Role;
+use crate::shared::config::config::{Config, JWTAlgorithm, JWTKey, JWT};
+
+// region:    --- Claims
+
+/// Claims carried by access tokens. Everything the auth middleware needs is in
+/// here, so authorizing a request only looks up whether its session is still
+/// active.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Claims {
+    /// User id.
+    pub sub: UserId,
+    pub email: String,
+    pub role: Role,
+    /// Issued at, seconds since the epoch.
+    pub iat: i64,
+    /// Expiration, seconds since the epoch.
+    pub exp: i64,
+    /// Unique token id.
+    pub jti: Uuid,
+    /// Session the token was issued for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Whether a second factor was verified when the session started.
+    #[serde(default)]
+    pub mfa: bool,
 }
 
-impl FromStr for Token {
-    type Err = TokenError;
+impl Claims {
+    pub fn new(user_id: UserId, email: &str, role: Role, duration_sec: u64) -> Self {
+        let iat = Timestamp::now_utc().to_unix_timestamp() as i64;
 
-    fn from_str(token_str: &str) -> std::result::Result<Self, Self::Err> {
-        let splits: Vec<&str> = token_str.split('.').collect();
-        if splits.len() != 3 {
-            return Err(TokenError::InvalidFormat);
+        Self {
+            sub: user_id,
+            email: email.to_string(),
+            role,
+            iat,
+            exp: iat + duration_sec as i64,
+            jti: Uuid::new_v4(),
+            sid: None,
+            mfa: false,
         }
-        let (ident_b64u, exp_b64u, sign_b64u) = (splits[0], splits[1], splits[2]);
+    }
+}
 
-        Ok(Self {
-            ident: b64u_decode_to_string(ident_b64u).map_err(|_| TokenError::CannotDecodeIdent)?,
+// endregion: --- Claims
+
+// region:    --- Keys
+
+/// A key of the keyring, resolved from an `auth.jwt` key config.
+pub struct SigningKey {
+    kid: String,
+    algorithm: Algorithm,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    /// Set once the key stopped signing: it keeps verifying tokens until then.
+    verify_until: Option<i64>,
+    /// Generated by a rotation rather than configured: dropped once retired.
+    generated: bool,
+}
+
+impl SigningKey {
+    pub fn from_config(key: &JWTKey, default_algorithm: JWTAlgorithm) -> Result<Self, TokenError> {
+        match key.algorithm.unwrap_or(default_algorithm) {
+            algorithm @ (JWTAlgorithm::HS256 | JWTAlgorithm::HS512) => {
+                if key.secret.is_empty() {
+                    return Err(TokenError::InvalidKey);
+                }
+
+                Ok(Self::from_secret(
+                    &key.kid,
+                    algorithm,
+                    key.secret.as_bytes(),
+                ))
+            }
+            JWTAlgorithm::RS256 => {
+                let private_pem = read_pem(key.private_key.as_deref())?;
+                let public_pem = read_pem(key.public_key.as_deref())?;
+
+                Ok(Self {
+                    kid: key.kid.clone(),
+                    algorithm: Algorithm::RS256,
+                    encoding: EncodingKey::from_rsa_pem(&private_pem)
+                        .map_err(|_| TokenError::InvalidKey)?,
+                    decoding: DecodingKey::from_rsa_pem(&public_pem)
+                        .map_err(|_| TokenError::InvalidKey)?,
+                    verify_until: None,
+                    generated: false,
+                })
+            }
+        }
+    }
 
-            exp: b64u_decode_to_string(exp_b64u).map_err(|_| TokenError::CannotDecodeExp)?,
+    /// Generates a random secret key. Only `HS*` keys can be generated, RSA
+    /// keys have to be configured.
+    fn generate(algorithm: Algorithm) -> Result<Self, TokenError> {
+        let algorithm = match algorithm {
+            Algorithm::HS256 => JWTAlgorithm::HS256,
+            Algorithm::HS512 => JWTAlgorithm::HS512,
+            _ => return Err(TokenError::KeyGenerationUnsupported),
+        };
+        let mut secret = [0u8; 64];
+        rand::thread_rng().fill_bytes(&mut secret);
 
-            sign_b64u: sign_b64u.to_string(),
+        Ok(Self {
+            generated: true,
+            ..Self::from_secret(&Uuid::new_v4().to_string(), algorithm, &secret)
         })
     }
+
+    fn from_secret(kid: &str, algorithm: JWTAlgorithm, secret: &[u8]) -> Self {
+        let algorithm = if algorithm == JWTAlgorithm::HS256 {
+            Algorithm::HS256
+        } else {
+            Algorithm::HS512
+        };
+
+        Self {
+            kid: kid.to_string(),
+            algorithm,
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            verify_until: None,
+            generated: false,
+        }
+    }
+
+    fn verifies_at(&self, now: i64) -> bool {
+        self.verify_until.is_none_or(|until| now < until)
+    }
 }
 
-impl Display for Token {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}.{}.{}",
-            b64u_encode(&self.ident),
-            b64u_encode(&self.exp),
-            self.sign_b64u
-        )
+fn read_pem(path: Option<&str>) -> Result<Vec<u8>, TokenError> {
+    let path = path.ok_or(TokenError::InvalidKey)?;
+    fs::read(path).map_err(|_| TokenError::InvalidKey)
+}
+
+/// Signing keys by `kid`. The active key signs new tokens; every key verifies
+/// the tokens that carry its `kid`.
+pub struct Keyring {
+    active: String,
+    keys: Vec<SigningKey>,
+}
+
+impl Keyring {
+    pub fn from_config(jwt: &JWT) -> Result<Self, TokenError> {
+        let keys = jwt
+            .keyring()
+            .iter()
+            .map(|key| SigningKey::from_config(key, jwt.algorithm))
+            .collect::<Result<Vec<_>, _>>()?;
+        let active = match &jwt.active_kid {
+            Some(kid) => kid.clone(),
+            None => keys.last().ok_or(TokenError::InvalidKey)?.kid.clone(),
+        };
+
+        if !keys.iter().any(|key| key.kid == active) {
+            return Err(TokenError::UnknownKey);
+        }
+
+        Ok(Self { active, keys })
+    }
+
+    pub fn active_kid(&self) -> &str {
+        &self.active
+    }
+
+    fn active(&self) -> &SigningKey {
+        self.keys
+            .iter()
+            .find(|key| key.kid == self.active)
+            .expect("active key is always in the keyring")
+    }
+
+    /// Key for a token's `kid`. Tokens without one predate the keyring and are
+    /// checked against the active key.
+    fn verifying(&self, kid: Option<&str>, now: i64) -> Result<&SigningKey, TokenError> {
+        let kid = kid.unwrap_or(&self.active);
+
+        self.keys
+            .iter()
+            .find(|key| key.kid == kid && key.verifies_at(now))
+            .ok_or(TokenError::UnknownKey)
+    }
+
+    /// Makes `kid` the signing key, or a newly generated key when `kid` is
+    /// `None`. The previous key keeps verifying for `grace_sec`, so tokens it
+    /// signed stay valid until they expire. Returns the new active `kid`.
+    ///
+    /// Generated keys are dropped once their grace period is over. Configured
+    /// keys stay in the keyring so it keeps matching `auth.jwt.keys`: they just
+    /// stop verifying, until a rotation makes them active again.
+    pub fn rotate(
+        &mut self,
+        kid: Option<&str>,
+        now: i64,
+        grace_sec: u64,
+    ) -> Result<String, TokenError> {
+        let next = match kid {
+            Some(kid) => self
+                .keys
+                .iter()
+                .find(|key| key.kid == kid)
+                .map(|key| key.kid.clone())
+                .ok_or(TokenError::UnknownKey)?,
+            None => {
+                let key = SigningKey::generate(self.active().algorithm)?;
+                let kid = key.kid.clone();
+                self.keys.push(key);
+                kid
+            }
+        };
+
+        if next != self.active {
+            for key in self.keys.iter_mut() {
+                if key.kid == self.active {
+                    key.verify_until = Some(now + grace_sec as i64);
+                } else if key.kid == next {
+                    key.verify_until = None;
+                }
+            }
+            self.active = next;
+        }
+        self.keys
+            .retain(|key| !key.generated || key.verifies_at(now));
+
+        Ok(self.active.clone())
     }
 }
 
-// endregion: --- Token Type
+static KEYRING: OnceLock<RwLock<Keyring>> = OnceLock::new();
+
+/// Keyring for the global config, loaded once.
+fn keyring() -> Result<&'static RwLock<Keyring>, TokenError> {
+    if let Some(keyring) = KEYRING.get() {
+        return Ok(keyring);
+    }
+
+    let keyring = Keyring::from_config(&Config::get().auth.jwt)?;
+    Ok(KEYRING.get_or_init(|| RwLock::new(keyring)))
+}
+
+// endregion: --- Keys
 
 // region:    --- Web Token Gen and Validation
 
-pub fn generate_web_token(user: &str, salt: Uuid) -> Result<Token, TokenError> {
+pub fn generate_web_token(
+    user_id: UserId,
+    email: &str,
+    role: &Role,
+    session: &Session,
+) -> Result<String, TokenError> {
     let config = Config::get();
-    _generate_token(
-        user,
-        &config.auth.jwt.expiration,
-        salt,
-        (&config.auth.jwt.secret).as_ref(),
-    )
+    let mut claims = Claims::new(user_id, email, role.clone(), config.auth.jwt.expiration);
+    claims.sid = Some(session.id);
+    claims.mfa = session.mfa;
+    let keyring = keyring()?.read().map_err(|_| TokenError::InvalidKey)?;
+
+    _generate_token(&claims, &keyring)
 }
 
-pub fn validate_web_token(origin_token: &Token, salt: Uuid) -> Result<(), TokenError> {
-    let config = Config::get();
-    _validate_token_sign_and_exp(origin_token, salt, &config.auth.jwt.secret)?;
+pub fn validate_web_token(token: &str) -> Result<Claims, TokenError> {
+    let keyring = keyring()?.read().map_err(|_| TokenError::InvalidKey)?;
+
+    _validate_token(token, &keyring, now())
+}
+
+/// Rolls the active signing key, see [`Keyring::rotate`]. Tokens signed with
+/// the previous key stay valid for the token lifetime.
+///
+/// Generated keys only live in this process: configure the keys in
+/// `auth.jwt.keys` and pass their `kid` when several instances share tokens.
+pub fn rotate_signing_key(kid: Option<&str>) -> Result<String, TokenError> {
+    let grace_sec = Config::get().auth.jwt.expiration;
+    let mut keyring = keyring()?.write().map_err(|_| TokenError::InvalidKey)?;
 
-    Ok(())
+    keyring.rotate(kid, now(), grace_sec)
 }
 
 // endregion: --- Web Token Gen and Validation
 
 // region:    --- (private) Token Gen and Validation
 
-fn _generate_token(
-    ident: &str,
-    duration_sec: &u64,
-    salt: Uuid,
-    key: &str,
-) -> Result<Token, TokenError> {
-    // -- Compute the two first components.
-    let ident = ident.to_string();
-    let exp = now_utc_plus_sec_str(duration_sec);
-
-    // -- Sign the two first components.
-    let sign_b64u = _token_sign_into_b64u(&ident, &exp, salt, key)?;
-
-    Ok(Token {
-        ident,
-        exp,
-        sign_b64u,
-    })
+fn now() -> i64 {
+    Timestamp::now_utc().to_unix_timestamp() as i64
+}
+
+fn _generate_token(claims: &Claims, keyring: &Keyring) -> Result<String, TokenError> {
+    let key = keyring.active();
+    let mut header = Header::new(key.algorithm);
+    header.kid = Some(key.kid.clone());
+
+    encode(&header, claims, &key.encoding).map_err(|_| TokenError::EncodingError)
 }
 
-fn _validate_token_sign_and_exp(
-    origin_token: &Token,
-    salt: Uuid,
-    key: &str,
-) -> Result<(), TokenError> {
-    let new_sign_b64u = _token_sign_into_b64u(&origin_token.ident, &origin_token.exp, salt, key)?;
+fn _validate_token(token: &str, keyring: &Keyring, now: i64) -> Result<Claims, TokenError> {
+    let header = decode_header(token).map_err(|_| TokenError::InvalidFormat)?;
+    let key = keyring.verifying(header.kid.as_deref(), now)?;
 
-    if new_sign_b64u != origin_token.sign_b64u {
-        return Err(TokenError::SignatureNotMatching);
+    let mut validation = Validation::new(key.algorithm);
+    validation.leeway = 0;
+    validation.set_required_spec_claims(&["sub", "iat", "exp"]);
+
+    let data = decode::<Claims>(token, &key.decoding, &validation).map_err(|e| match e.kind() {
+        ErrorKind::ExpiredSignature => TokenError::Expired,
+        ErrorKind::InvalidSignature | ErrorKind::InvalidAlgorithm => {
+            TokenError::SignatureNotMatching
+        }
+        _ => TokenError::InvalidFormat,
+    })?;
+
+    Ok(data.claims)
+}
+
+// endregion: --- (private) Token Gen and Validation
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt_config(algorithm: JWTAlgorithm, secret: &str) -> JWT {
+        JWT {
+            location: None,
+            algorithm,
+            secret: secret.to_string(),
+            private_key: None,
+            public_key: None,
+            keys: vec![],
+            active_kid: None,
+            expiration: 60,
+            refresh_expiration: 120,
+        }
     }
 
-    let origin_exp = parse_utc(&origin_token.exp).map_err(|_| TokenError::ExpNotIso)?;
-    let now = now_utc();
+    fn hs_keyring(algorithm: JWTAlgorithm, secret: &str) -> Keyring {
+        Keyring::from_config(&jwt_config(algorithm, secret)).unwrap()
+    }
 
-    if origin_exp < now {
-        return Err(TokenError::Expired);
+    fn key(kid: &str, secret: &str) -> JWTKey {
+        JWTKey {
+            kid: kid.to_string(),
+            algorithm: None,
+            secret: secret.to_string(),
+            private_key: None,
+            public_key: None,
+            retired: false,
+        }
     }
 
-    Ok(())
-}
+    fn claims(duration_sec: u64) -> Claims {
+        Claims::new(
+            UserId::generate(),
+            "john.doe@example.com",
+            Role::USER,
+            duration_sec,
+        )
+    }
 
-fn _token_sign_into_b64u(
-    ident: &str,
-    exp: &str,
-    salt: Uuid,
-    key: &str,
-) -> Result<String, TokenError> {
-    let content = format!("{}.{}", b64u_encode(ident), b64u_encode(exp));
+    #[test]
+    fn token_round_trips_claims() {
+        for algorithm in [JWTAlgorithm::HS256, JWTAlgorithm::HS512] {
+            let keyring = hs_keyring(algorithm, "secret");
+            let claims = claims(60);
+
+            let token = _generate_token(&claims, &keyring).unwrap();
+
+            assert_eq!(_validate_token(&token, &keyring, now()).unwrap(), claims);
+        }
+    }
+
+    #[test]
+    fn token_signed_with_another_secret_is_rejected() {
+        let keyring = hs_keyring(JWTAlgorithm::HS512, "secret");
+        let other = hs_keyring(JWTAlgorithm::HS512, "other");
+
+        let token = _generate_token(&claims(60), &other).unwrap();
+
+        assert!(matches!(
+            _validate_token(&token, &keyring, now()),
+            Err(TokenError::SignatureNotMatching)
+        ));
+    }
+
+    #[test]
+    fn token_with_unexpected_algorithm_is_rejected() {
+        let keyring = hs_keyring(JWTAlgorithm::HS512, "secret");
+        let hs256 = hs_keyring(JWTAlgorithm::HS256, "secret");
+
+        let token = _generate_token(&claims(60), &hs256).unwrap();
+
+        assert!(_validate_token(&token, &keyring, now()).is_err());
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let keyring = hs_keyring(JWTAlgorithm::HS512, "secret");
+        let mut claims = claims(0);
+        claims.iat -= 120;
+        claims.exp -= 60;
 
-    // -- Create a HMAC-SHA-512 from key.
-    let mut hmac_sha512 = Hmac::<Sha512>::new_from_slice(key.as_ref())
-        .map_err(|_| TokenError::HmacFailNewFromSlice)?; // `?` operatörünü kullanarak hatayı ele al
+        let token = _generate_token(&claims, &keyring).unwrap();
 
-    // -- Add content.
-    hmac_sha512.update(content.as_bytes());
-    hmac_sha512.update(salt.as_bytes());
+        assert!(matches!(
+            _validate_token(&token, &keyring, now()),
+            Err(TokenError::Expired)
+        ));
+    }
+
+    #[test]
+    fn garbage_token_is_rejected() {
+        let keyring = hs_keyring(JWTAlgorithm::HS512, "secret");
+
+        assert!(matches!(
+            _validate_token("not.a.token", &keyring, now()),
+            Err(TokenError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn rs256_requires_key_files() {
+        let result = Keyring::from_config(&jwt_config(JWTAlgorithm::RS256, ""));
+
+        assert!(matches!(result, Err(TokenError::InvalidKey)));
+    }
+
+    #[test]
+    fn tokens_carry_the_active_kid() {
+        let mut config = jwt_config(JWTAlgorithm::HS512, "");
+        config.keys = vec![key("old", "old-secret"), key("new", "new-secret")];
+        config.active_kid = Some("old".to_string());
+        let keyring = Keyring::from_config(&config).unwrap();
+
+        let token = _generate_token(&claims(60), &keyring).unwrap();
+
+        assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some("old"));
+    }
+
+    #[test]
+    fn active_kid_must_be_configured() {
+        let mut config = jwt_config(JWTAlgorithm::HS512, "");
+        config.keys = vec![key("current", "secret")];
+        config.active_kid = Some("missing".to_string());
+
+        assert!(matches!(
+            Keyring::from_config(&config),
+            Err(TokenError::UnknownKey)
+        ));
+    }
 
-    // -- Finalize and b64u encode.
-    let hmac_result = hmac_sha512.finalize();
-    let result_bytes = hmac_result.into_bytes();
-    let result = b64u_encode(result_bytes);
+    #[test]
+    fn retired_keys_do_not_verify() {
+        let mut config = jwt_config(JWTAlgorithm::HS512, "");
+        config.keys = vec![key("old", "old-secret"), key("new", "new-secret")];
+        config.active_kid = Some("old".to_string());
+        let token = _generate_token(&claims(60), &Keyring::from_config(&config).unwrap()).unwrap();
 
-    Ok(result)
+        config.keys[0].retired = true;
+        config.active_kid = Some("new".to_string());
+        let keyring = Keyring::from_config(&config).unwrap();
+
+        assert!(matches!(
+            _validate_token(&token, &keyring, now()),
+            Err(TokenError::UnknownKey)
+        ));
+    }
+
+    #[test]
+    fn rotation_keeps_previous_key_for_verification() {
+        let mut keyring = hs_keyring(JWTAlgorithm::HS512, "secret");
+        let before = _generate_token(&claims(60), &keyring).unwrap();
+
+        let kid = keyring.rotate(None, now(), 60).unwrap();
+        let after = _generate_token(&claims(60), &keyring).unwrap();
+
+        assert_ne!(kid, JWT::DEFAULT_KID);
+        assert_eq!(keyring.active_kid(), kid);
+        assert_eq!(decode_header(&after).unwrap().kid, Some(kid));
+        assert!(_validate_token(&before, &keyring, now()).is_ok());
+        assert!(_validate_token(&after, &keyring, now()).is_ok());
+        assert!(matches!(
+            _validate_token(&before, &keyring, now() + 60),
+            Err(TokenError::UnknownKey)
+        ));
+    }
+
+    #[test]
+    fn rotation_to_configured_key() {
+        let mut config = jwt_config(JWTAlgorithm::HS512, "");
+        config.keys = vec![key("old", "old-secret"), key("new", "new-secret")];
+        config.active_kid = Some("old".to_string());
+        let mut keyring = Keyring::from_config(&config).unwrap();
+
+        assert_eq!(keyring.rotate(Some("new"), now(), 60).unwrap(), "new");
+        assert!(matches!(
+            keyring.rotate(Some("missing"), now(), 60),
+            Err(TokenError::UnknownKey)
+        ));
+        assert_eq!(keyring.active_kid(), "new");
+    }
+
+    #[test]
+    fn rotation_keeps_configured_keys_past_their_grace() {
+        let mut config = jwt_config(JWTAlgorithm::HS512, "");
+        config.keys = vec![key("old", "old-secret"), key("new", "new-secret")];
+        config.active_kid = Some("old".to_string());
+        let mut keyring = Keyring::from_config(&config).unwrap();
+        let token = _generate_token(&claims(60), &keyring).unwrap();
+
+        keyring.rotate(Some("new"), now(), 60).unwrap();
+        let generated = keyring.rotate(None, now() + 60, 60).unwrap();
+
+        assert!(matches!(
+            _validate_token(&token, &keyring, now() + 60),
+            Err(TokenError::UnknownKey)
+        ));
+        assert_eq!(keyring.rotate(Some("old"), now() + 120, 60).unwrap(), "old");
+        assert_eq!(keyring.rotate(Some("new"), now() + 180, 60).unwrap(), "new");
+        assert!(matches!(
+            keyring.rotate(Some(&generated), now() + 180, 60),
+            Err(TokenError::UnknownKey)
+        ));
+    }
 }
-// endregion: --- (private) Token Gen and Validation
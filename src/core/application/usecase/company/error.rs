@@ -0,0 +1,33 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum CompanyError {
+    CompanyNotFound,
+    NameTaken,
+    UrlTaken,
+    InvalidName,
+    /// The caller is not the CEO of the company.
+    NotCompanyCeo,
+    /// The caller is not an administrator.
+    NotAdmin,
+    /// The company changed since the version the update was based on.
+    VersionMismatch,
+    DbInternalError,
+}
+
+impl fmt::Display for CompanyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompanyError::CompanyNotFound => write!(f, "Company not found"),
+            CompanyError::NameTaken => write!(f, "Company name already taken"),
+            CompanyError::UrlTaken => write!(f, "Company url already taken"),
+            CompanyError::InvalidName => write!(f, "Company name must not be empty"),
+            CompanyError::NotCompanyCeo => write!(f, "Only the CEO can manage the company"),
+            CompanyError::NotAdmin => write!(f, "Only administrators can restore companies"),
+            CompanyError::VersionMismatch => write!(f, "Company was changed in the meantime"),
+            CompanyError::DbInternalError => write!(f, "Database internal error"),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::core::application::usecase::company::error::CompanyError;
+use crate::core::domain::entity::company::Company;
+use crate::core::domain::entity::employment::Employment;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::CompanyId;
+use crate::core::domain::valueobject::position::Position;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::port::company::{
+    CompanyManagement, CompanyRepo, CompanySort, CompanyUpdate, NewCompany,
+};
+use crate::core::port::employment::{EmploymentRepo, EmploymentSort};
+use crate::core::port::error::{AlreadyTaken, VersionConflict};
+use crate::core::port::page::{Page, PageRequest};
+use crate::core::port::unit_of_work::UnitOfWorkFactory;
+use crate::shared::ctx::ctx::Ctx;
+
+#[derive(Debug, Clone)]
+pub struct CompanyService<C, E, W>
+where
+    C: CompanyRepo,
+    E: EmploymentRepo,
+    W: UnitOfWorkFactory,
+{
+    company_repository: Arc<C>,
+    employment_repository: Arc<E>,
+    unit_of_work: Arc<W>,
+}
+
+impl<C, E, W> CompanyService<C, E, W>
+where
+    C: CompanyRepo,
+    E: EmploymentRepo,
+    W: UnitOfWorkFactory,
+{
+    pub fn new(
+        company_repository: Arc<C>,
+        employment_repository: Arc<E>,
+        unit_of_work: Arc<W>,
+    ) -> Self {
+        Self {
+            company_repository,
+            employment_repository,
+            unit_of_work,
+        }
+    }
+
+    async fn find_company(&self, id: CompanyId) -> Result<Company, CompanyError> {
+        self.company_repository
+            .find_by_id(id)
+            .await
+            .map_err(|_| CompanyError::DbInternalError)?
+            .ok_or(CompanyError::CompanyNotFound)
+    }
+
+    /// Fails unless `name` is free, or already belongs to `company_id`.
+    async fn ensure_name_free(
+        &self,
+        name: &str,
+        company_id: Option<CompanyId>,
+    ) -> Result<(), CompanyError> {
+        let existing = self
+            .company_repository
+            .find_by_name(name)
+            .await
+            .map_err(|_| CompanyError::DbInternalError)?;
+
+        match existing {
+            Some(existing) if existing.id != company_id => Err(CompanyError::NameTaken),
+            _ => Ok(()),
+        }
+    }
+
+    async fn ensure_ceo(&self, ctx: &Ctx, company_id: CompanyId) -> Result<(), CompanyError> {
+        if ctx.is_root() {
+            return Ok(());
+        }
+        let position = self
+            .employment_repository
+            .find_position(ctx.user_id(), company_id)
+            .await
+            .map_err(|_| CompanyError::DbInternalError)?;
+
+        check_ceo(position.as_ref())
+    }
+}
+
+fn check_ceo(position: Option<&Position>) -> Result<(), CompanyError> {
+    match position {
+        Some(Position::CEO) => Ok(()),
+        _ => Err(CompanyError::NotCompanyCeo),
+    }
+}
+
+fn check_admin(ctx: &Ctx) -> Result<(), CompanyError> {
+    if ctx.is_root() || Role::ADMINS.contains(ctx.role()) {
+        Ok(())
+    } else {
+        Err(CompanyError::NotAdmin)
+    }
+}
+
+/// Maps a failed save or update. Names held by deleted companies, URLs, and
+/// concurrent registrations are only caught by the repository.
+fn write_error(error: Error) -> CompanyError {
+    match error.downcast_ref::<AlreadyTaken>() {
+        Some(taken) if taken.field == "url" => CompanyError::UrlTaken,
+        Some(_) => CompanyError::NameTaken,
+        None if error.is::<VersionConflict>() => CompanyError::VersionMismatch,
+        None => CompanyError::DbInternalError,
+    }
+}
+
+fn valid_name(name: &str) -> Result<String, CompanyError> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err(CompanyError::InvalidName)
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+#[async_trait]
+impl<C, E, W> CompanyManagement for CompanyService<C, E, W>
+where
+    C: CompanyRepo,
+    E: EmploymentRepo,
+    W: UnitOfWorkFactory,
+{
+    async fn register(&self, ctx: &Ctx, input: NewCompany) -> Result<Company, CompanyError> {
+        let name = valid_name(&input.name)?;
+        self.ensure_name_free(&name, None).await?;
+
+        let company = Company::new(
+            name,
+            input.foundation_date,
+            input.description,
+            input.url,
+            input.sector,
+        );
+        // The company and its CEO are saved together or not at all.
+        let work = self
+            .unit_of_work
+            .begin()
+            .await
+            .map_err(|_| CompanyError::DbInternalError)?;
+        let company_id = work.companies().save(&company).await.map_err(write_error)?;
+
+        // System jobs register companies without a CEO.
+        if !ctx.is_root() {
+            work.employments()
+                .save(ctx.user_id(), company_id, &Position::CEO)
+                .await
+                .map_err(|_| CompanyError::DbInternalError)?;
+        }
+        work.commit()
+            .await
+            .map_err(|_| CompanyError::DbInternalError)?;
+
+        self.find_company(company_id).await
+    }
+
+    async fn list(
+        &self,
+        _ctx: &Ctx,
+        page: &PageRequest<CompanySort>,
+    ) -> Result<Page<Company>, CompanyError> {
+        self.company_repository
+            .find_page(page)
+            .await
+            .map_err(|_| CompanyError::DbInternalError)
+    }
+
+    async fn get_profile(&self, _ctx: &Ctx, id: CompanyId) -> Result<Company, CompanyError> {
+        self.find_company(id).await
+    }
+
+    async fn list_employees(
+        &self,
+        _ctx: &Ctx,
+        id: CompanyId,
+        page: &PageRequest<EmploymentSort>,
+    ) -> Result<Page<Employment>, CompanyError> {
+        self.find_company(id).await?;
+
+        self.employment_repository
+            .find_by_company(id, page)
+            .await
+            .map_err(|_| CompanyError::DbInternalError)
+    }
+
+    async fn update(
+        &self,
+        ctx: &Ctx,
+        id: CompanyId,
+        input: CompanyUpdate,
+    ) -> Result<Company, CompanyError> {
+        let mut company = self.find_company(id).await?;
+        self.ensure_ceo(ctx, id).await?;
+        if input
+            .version
+            .is_some_and(|version| version != company.version)
+        {
+            return Err(CompanyError::VersionMismatch);
+        }
+
+        if let Some(name) = input.name {
+            let name = valid_name(&name)?;
+            self.ensure_name_free(&name, company.id).await?;
+            company.name = name;
+        }
+        if let Some(foundation_date) = input.foundation_date {
+            company.foundation_date = foundation_date;
+        }
+        if let Some(description) = input.description {
+            company.description = description;
+        }
+        if let Some(url) = input.url {
+            company.url = url;
+        }
+        if let Some(sector) = input.sector {
+            company.sector = sector;
+        }
+        company.updated_at = Timestamp::now_utc();
+
+        self.company_repository
+            .update(id, &company)
+            .await
+            .map_err(write_error)
+    }
+
+    async fn delete(&self, ctx: &Ctx, id: CompanyId) -> Result<(), CompanyError> {
+        self.find_company(id).await?;
+        self.ensure_ceo(ctx, id).await?;
+
+        self.company_repository
+            .delete(id)
+            .await
+            .map_err(|_| CompanyError::DbInternalError)
+    }
+
+    async fn restore(&self, ctx: &Ctx, id: CompanyId) -> Result<Company, CompanyError> {
+        check_admin(ctx)?;
+
+        self.company_repository
+            .restore(id)
+            .await
+            .map_err(|_| CompanyError::DbInternalError)?
+            .ok_or(CompanyError::CompanyNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::company::CompanyRepository;
+    use crate::adapter::driven::storage::memory::repository::employment::EmploymentRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+    use crate::adapter::driven::storage::memory::unit_of_work::MemUnitOfWorkFactory;
+    use crate::core::domain::valueobject::sector::Sector;
+
+    fn new_company(name: &str, url: &str) -> NewCompany {
+        NewCompany {
+            name: name.to_string(),
+            foundation_date: 2020,
+            description: "Makes things".to_string(),
+            url: url.to_string(),
+            sector: Sector::Software,
+        }
+    }
+
+    #[test]
+    fn only_the_ceo_passes_the_ownership_check() {
+        assert!(check_ceo(Some(&Position::CEO)).is_ok());
+        assert!(matches!(
+            check_ceo(Some(&Position::Manager)),
+            Err(CompanyError::NotCompanyCeo)
+        ));
+        assert!(matches!(check_ceo(None), Err(CompanyError::NotCompanyCeo)));
+    }
+
+    #[tokio::test]
+    async fn conflicts_on_unique_values_are_reported() {
+        let store = Arc::new(MemStore::new());
+        let service = CompanyService::new(
+            Arc::new(CompanyRepository::new(store.clone())),
+            Arc::new(EmploymentRepository::new(store.clone())),
+            Arc::new(MemUnitOfWorkFactory::new(store)),
+        );
+        let ctx = Ctx::root_ctx();
+        let acme = service
+            .register(&ctx, new_company("Acme", "https://acme.example"))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .register(&ctx, new_company("New", "https://acme.example"))
+                .await,
+            Err(CompanyError::UrlTaken)
+        ));
+        let other = service
+            .register(&ctx, new_company("Other", "https://other.example"))
+            .await
+            .unwrap();
+        let update = CompanyUpdate {
+            url: Some("https://acme.example".to_string()),
+            ..CompanyUpdate::default()
+        };
+        assert!(matches!(
+            service.update(&ctx, other.id.unwrap(), update).await,
+            Err(CompanyError::UrlTaken)
+        ));
+
+        // Deleted companies keep their name until they are purged.
+        service.delete(&ctx, acme.id.unwrap()).await.unwrap();
+        assert!(matches!(
+            service
+                .register(&ctx, new_company("Acme", "https://new.example"))
+                .await,
+            Err(CompanyError::NameTaken)
+        ));
+    }
+
+    #[test]
+    fn company_names_are_trimmed_and_required() {
+        assert_eq!(valid_name("  Acme ").unwrap(), "Acme");
+        assert!(matches!(valid_name("   "), Err(CompanyError::InvalidName)));
+    }
+}
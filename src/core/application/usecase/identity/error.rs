@@ -0,0 +1,46 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::core::application::usecase::auth::error::LoginError;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum IdentityError {
+    UnknownProvider,
+    /// The callback does not belong to a pending login of this browser.
+    InvalidState,
+    /// The provider refused the code or returned an invalid ID token.
+    ProviderError,
+    AlreadyLinked,
+    /// An unverified local account uses the provider's email.
+    UnverifiedAccount,
+    /// A deleted user still holds the provider's email.
+    UserAlreadyRegistered,
+    IdentityNotFound,
+    DbInternalError,
+    Login(LoginError),
+}
+
+impl From<LoginError> for IdentityError {
+    fn from(error: LoginError) -> Self {
+        IdentityError::Login(error)
+    }
+}
+
+impl fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentityError::UnknownProvider => write!(f, "Unknown identity provider"),
+            IdentityError::InvalidState => write!(f, "Invalid or expired login state"),
+            IdentityError::ProviderError => write!(f, "Identity provider error"),
+            IdentityError::AlreadyLinked => write!(f, "Identity linked to another user"),
+            IdentityError::UnverifiedAccount => {
+                write!(f, "Unverified account with the identity's email")
+            }
+            IdentityError::UserAlreadyRegistered => write!(f, "User already registered"),
+            IdentityError::IdentityNotFound => write!(f, "Identity not found"),
+            IdentityError::DbInternalError => write!(f, "Database internal error"),
+            IdentityError::Login(error) => write!(f, "Login error: {:?}", error),
+        }
+    }
+}
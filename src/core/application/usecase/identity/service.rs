@@ -0,0 +1,529 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::core::application::usecase::auth::error::LoginError;
+use crate::core::application::usecase::identity::error::IdentityError;
+use crate::core::domain::entity::identity::{AuthorizationRequest, UserIdentity};
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::port::error::AlreadyTaken;
+use crate::core::port::identity::{
+    AuthorizationRepo, AuthorizationStart, CallbackOutcome, IdentityManagement, IdentityProvider,
+    IdentityRepo, ProviderIdentity, SecondFactorChallenge,
+};
+use crate::core::port::mfa::MfaRepo;
+use crate::core::port::user::{LoginSuccess, UserRepo};
+
+pub struct IdentityService<I, A, U, M>
+where
+    I: IdentityRepo,
+    A: AuthorizationRepo,
+    U: UserRepo,
+    M: MfaRepo,
+{
+    identity_repository: Arc<I>,
+    authorization_repository: Arc<A>,
+    user_repository: Arc<U>,
+    mfa_repository: Arc<M>,
+    identity_provider: Arc<dyn IdentityProvider>,
+}
+
+impl<I, A, U, M> IdentityService<I, A, U, M>
+where
+    I: IdentityRepo,
+    A: AuthorizationRepo,
+    U: UserRepo,
+    M: MfaRepo,
+{
+    pub fn new(
+        identity_repository: Arc<I>,
+        authorization_repository: Arc<A>,
+        user_repository: Arc<U>,
+        mfa_repository: Arc<M>,
+        identity_provider: Arc<dyn IdentityProvider>,
+    ) -> Self {
+        Self {
+            identity_repository,
+            authorization_repository,
+            user_repository,
+            mfa_repository,
+            identity_provider,
+        }
+    }
+
+    async fn find_identity(
+        &self,
+        identity: &ProviderIdentity,
+    ) -> Result<Option<UserIdentity>, IdentityError> {
+        self.identity_repository
+            .find(&identity.provider, &identity.subject)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)
+    }
+
+    async fn save_identity(
+        &self,
+        user_id: UserId,
+        identity: &ProviderIdentity,
+    ) -> Result<UserIdentity, IdentityError> {
+        let linked = UserIdentity::new(
+            user_id,
+            &identity.provider,
+            &identity.subject,
+            identity.email.clone(),
+        );
+
+        self.identity_repository
+            .save(&linked)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)
+    }
+
+    async fn link(
+        &self,
+        user_id: UserId,
+        identity: &ProviderIdentity,
+    ) -> Result<UserIdentity, IdentityError> {
+        if let Some(linked) = self.find_identity(identity).await? {
+            if linked.user_id != user_id {
+                return Err(IdentityError::AlreadyLinked);
+            }
+            return Ok(linked);
+        }
+
+        let has_provider = self
+            .list(user_id)
+            .await?
+            .iter()
+            .any(|linked| linked.provider == identity.provider);
+        if has_provider {
+            return Err(IdentityError::AlreadyLinked);
+        }
+
+        self.save_identity(user_id, identity).await
+    }
+
+    async fn find_user(&self, user_id: UserId) -> Result<User, IdentityError> {
+        self.user_repository
+            .find_by_id(user_id)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?
+            .ok_or(IdentityError::DbInternalError)
+    }
+
+    /// Logs in the user linked to `identity`, or asks for the second factor
+    /// when the user has 2FA enabled. The provider login does not replace it.
+    async fn login(&self, identity: &ProviderIdentity) -> Result<CallbackOutcome, IdentityError> {
+        let user = self.find_or_link(identity).await?;
+        if user.is_blocked() {
+            return Err(LoginError::UserBlocked.into());
+        }
+        let user_id = user.id.ok_or(IdentityError::DbInternalError)?;
+        let mfa = self
+            .mfa_repository
+            .find_by_user(user_id)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?;
+        if !mfa.is_some_and(|mfa| mfa.is_enabled()) {
+            return Ok(CallbackOutcome::LoggedIn(LoginSuccess { user, mfa: false }));
+        }
+
+        let request = AuthorizationRequest::second_factor(&identity.provider, user_id);
+        let expires_in = self.identity_provider.timeout(&identity.provider);
+        self.authorization_repository
+            .put(&request, expires_in)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?;
+
+        Ok(CallbackOutcome::SecondFactorRequired(
+            SecondFactorChallenge {
+                ticket: request.state,
+                expires_in,
+            },
+        ))
+    }
+
+    /// Finds the user linked to `identity`. Unknown identities are linked by
+    /// their email, which the provider must have verified, to an existing user
+    /// or to a new one.
+    async fn find_or_link(&self, identity: &ProviderIdentity) -> Result<User, IdentityError> {
+        if let Some(linked) = self.find_identity(identity).await? {
+            return self.find_user(linked.user_id).await;
+        }
+
+        let email = identity
+            .email
+            .as_deref()
+            .filter(|_| identity.email_verified)
+            .and_then(|email| Email::parse(email).ok())
+            .ok_or(LoginError::UserProviderNotValid)?;
+        let found_user = self
+            .user_repository
+            .find_by_email(&email)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?;
+
+        let user = match found_user {
+            // Anyone can register an email they do not own. Its owner signs
+            // in with the password or verifies it before linking a provider.
+            Some(user) if user.email_verified_at.is_none() => {
+                return Err(IdentityError::UnverifiedAccount)
+            }
+            Some(user) => user,
+            None => self.register(identity, email).await?,
+        };
+
+        self.save_identity(user.id.ok_or(IdentityError::DbInternalError)?, identity)
+            .await?;
+
+        Ok(user)
+    }
+
+    /// Creates a user for a provider identity. Its password is random: it
+    /// signs in through the provider, or sets one with a password reset.
+    async fn register(
+        &self,
+        identity: &ProviderIdentity,
+        email: Email,
+    ) -> Result<User, IdentityError> {
+        let user = User::new(
+            identity.given_name.clone().unwrap_or_default(),
+            identity.family_name.clone().unwrap_or_default(),
+            email,
+            Uuid::new_v4().to_string(),
+            Role::USER,
+        )
+        .map_err(|_| LoginError::HashingError)?;
+        // A deleted user keeps the email until it is purged.
+        let mut user = self.user_repository.save(&user).await.map_err(|e| {
+            if e.is::<AlreadyTaken>() {
+                IdentityError::UserAlreadyRegistered
+            } else {
+                IdentityError::DbInternalError
+            }
+        })?;
+
+        // Saving leaves the verification unset, the provider verified it.
+        let id = user.id.ok_or(IdentityError::DbInternalError)?;
+        user.email_verified_at = Some(Timestamp::now_utc());
+        self.user_repository
+            .update(id, &user)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)
+    }
+}
+
+#[async_trait]
+impl<I, A, U, M> IdentityManagement for IdentityService<I, A, U, M>
+where
+    I: IdentityRepo,
+    A: AuthorizationRepo,
+    U: UserRepo,
+    M: MfaRepo,
+{
+    async fn authorize(
+        &self,
+        provider: &str,
+        link_user_id: Option<UserId>,
+    ) -> Result<AuthorizationStart, IdentityError> {
+        if !self.identity_provider.supports(provider) {
+            return Err(IdentityError::UnknownProvider);
+        }
+
+        let request = AuthorizationRequest::new(provider, link_user_id);
+        let expires_in = self.identity_provider.timeout(provider);
+        let url = self
+            .identity_provider
+            .authorization_url(&request)
+            .await
+            .map_err(|error| {
+                tracing::warn!("Identity provider {} unavailable: {:?}", provider, error);
+                IdentityError::ProviderError
+            })?;
+        self.authorization_repository
+            .put(&request, expires_in)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?;
+
+        Ok(AuthorizationStart {
+            url,
+            state: request.state,
+            expires_in,
+        })
+    }
+
+    async fn callback(
+        &self,
+        provider: &str,
+        state: &str,
+        code: &str,
+    ) -> Result<CallbackOutcome, IdentityError> {
+        let request = self
+            .authorization_repository
+            .take(state)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?
+            .filter(|request| request.provider == provider && request.pending_user_id.is_none())
+            .ok_or(IdentityError::InvalidState)?;
+
+        let identity = self
+            .identity_provider
+            .exchange_code(&request, code)
+            .await
+            .map_err(|error| {
+                tracing::warn!("Identity provider {} login failed: {:?}", provider, error);
+                IdentityError::ProviderError
+            })?;
+
+        match request.link_user_id {
+            Some(user_id) => self
+                .link(user_id, &identity)
+                .await
+                .map(CallbackOutcome::Linked),
+            None => self.login(&identity).await,
+        }
+    }
+
+    async fn take_second_factor(
+        &self,
+        provider: &str,
+        ticket: &str,
+    ) -> Result<User, IdentityError> {
+        let user_id = self
+            .authorization_repository
+            .take(ticket)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?
+            .filter(|request| request.provider == provider)
+            .and_then(|request| request.pending_user_id)
+            .ok_or(IdentityError::InvalidState)?;
+
+        let user = self.find_user(user_id).await?;
+        if user.is_blocked() {
+            return Err(LoginError::UserBlocked.into());
+        }
+
+        Ok(user)
+    }
+
+    async fn list(&self, user_id: UserId) -> Result<Vec<UserIdentity>, IdentityError> {
+        self.identity_repository
+            .find_by_user(user_id)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)
+    }
+
+    async fn unlink(&self, user_id: UserId, provider: &str) -> Result<(), IdentityError> {
+        let deleted = self
+            .identity_repository
+            .delete(user_id, provider)
+            .await
+            .map_err(|_| IdentityError::DbInternalError)?;
+
+        if !deleted {
+            return Err(IdentityError::IdentityNotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Error;
+
+    use super::*;
+    use crate::adapter::driven::storage::memory::repository::authorization::AuthorizationRepository;
+    use crate::adapter::driven::storage::memory::repository::identity::IdentityRepository;
+    use crate::adapter::driven::storage::memory::repository::mfa::MfaRepository;
+    use crate::adapter::driven::storage::memory::repository::user::UserRepository;
+    use crate::adapter::driven::storage::memory::store::MemStore;
+    use crate::core::domain::entity::mfa::UserMfa;
+    use crate::core::domain::valueobject::password::HashedPassword;
+    use crate::shared::config::config::Config;
+    use crate::shared::config::environment::Environment;
+
+    type Service =
+        IdentityService<IdentityRepository, AuthorizationRepository, UserRepository, MfaRepository>;
+
+    /// Provider that asserts the same identity for every code.
+    struct StubProvider(ProviderIdentity);
+
+    #[async_trait]
+    impl IdentityProvider for StubProvider {
+        fn supports(&self, provider: &str) -> bool {
+            provider == self.0.provider
+        }
+
+        fn timeout(&self, _provider: &str) -> u64 {
+            60
+        }
+
+        async fn authorization_url(
+            &self,
+            _request: &AuthorizationRequest,
+        ) -> Result<String, Error> {
+            Ok("https://provider.example.com/authorize".to_string())
+        }
+
+        async fn exchange_code(
+            &self,
+            _request: &AuthorizationRequest,
+            _code: &str,
+        ) -> Result<ProviderIdentity, Error> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn service() -> Service {
+        IdentityService::new(
+            Arc::new(IdentityRepository::new()),
+            Arc::new(AuthorizationRepository::new()),
+            Arc::new(UserRepository::new(Arc::new(MemStore::new()))),
+            Arc::new(MfaRepository::new()),
+            Arc::new(StubProvider(ProviderIdentity {
+                provider: "google".to_string(),
+                subject: "subject".to_string(),
+                email: Some("john@example.com".to_string()),
+                email_verified: true,
+                ..Default::default()
+            })),
+        )
+    }
+
+    async fn save(service: &Service, verified: bool) -> User {
+        let user = service
+            .user_repository
+            .save(&User {
+                id: None,
+                name: "John".to_string(),
+                surname: "Doe".to_string(),
+                email: Email::parse("john@example.com").unwrap(),
+                role: Role::USER,
+                password_hash: HashedPassword::from("hash".to_string()),
+                reset_token: None,
+                reset_sent_at: None,
+                email_verification_token: None,
+                email_verification_sent_at: None,
+                email_verified_at: None,
+                blocked_at: None,
+                created_at: Timestamp::now_utc(),
+                updated_at: Timestamp::now_utc(),
+                version: 1,
+                deleted_at: None,
+            })
+            .await
+            .unwrap();
+        if !verified {
+            return user;
+        }
+
+        let verified = User {
+            email_verified_at: Some(Timestamp::now_utc()),
+            ..user
+        };
+        service
+            .user_repository
+            .update(verified.id.unwrap(), &verified)
+            .await
+            .unwrap()
+    }
+
+    async fn login(service: &Service) -> Result<CallbackOutcome, IdentityError> {
+        let start = service.authorize("google", None).await.unwrap();
+
+        service.callback("google", &start.state, "code").await
+    }
+
+    #[tokio::test]
+    async fn registers_unknown_emails() {
+        let _ = Config::new(&Environment::Test);
+        let service = service();
+
+        let user = match login(&service).await.unwrap() {
+            CallbackOutcome::LoggedIn(login) => login.user,
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        };
+
+        assert!(user.email_verified_at.is_some());
+        assert_eq!(service.list(user.id.unwrap()).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn deleted_users_keep_their_email_registered() {
+        let _ = Config::new(&Environment::Test);
+        let service = service();
+        let user = save(&service, true).await;
+        service
+            .user_repository
+            .delete(user.id.unwrap())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            login(&service).await,
+            Err(IdentityError::UserAlreadyRegistered)
+        ));
+    }
+
+    #[tokio::test]
+    async fn refuses_to_link_unverified_accounts() {
+        let service = service();
+        let user = save(&service, false).await;
+
+        assert!(matches!(
+            login(&service).await,
+            Err(IdentityError::UnverifiedAccount)
+        ));
+        assert!(service.list(user.id.unwrap()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn users_with_2fa_complete_the_login_with_a_ticket() {
+        let service = service();
+        let user = save(&service, true).await;
+        let mut mfa = UserMfa::new(user.id.unwrap());
+        mfa.enable();
+        service.mfa_repository.save(&mfa).await.unwrap();
+
+        let ticket = match login(&service).await.unwrap() {
+            CallbackOutcome::SecondFactorRequired(challenge) => challenge.ticket,
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        };
+
+        // The ticket is no authorization state.
+        assert!(matches!(
+            service.callback("google", &ticket, "code").await,
+            Err(IdentityError::InvalidState)
+        ));
+        let ticket = match login(&service).await.unwrap() {
+            CallbackOutcome::SecondFactorRequired(challenge) => challenge.ticket,
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        };
+        assert!(matches!(
+            service.take_second_factor("github", &ticket).await,
+            Err(IdentityError::InvalidState)
+        ));
+        let ticket = match login(&service).await.unwrap() {
+            CallbackOutcome::SecondFactorRequired(challenge) => challenge.ticket,
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        };
+        assert_eq!(
+            service
+                .take_second_factor("google", &ticket)
+                .await
+                .unwrap()
+                .id,
+            user.id
+        );
+        assert!(matches!(
+            service.take_second_factor("google", &ticket).await,
+            Err(IdentityError::InvalidState)
+        ));
+    }
+}
@@ -0,0 +1,22 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MfaError {
+    AlreadyEnabled,
+    NotEnrolled,
+    InvalidCode,
+    DbInternalError,
+}
+
+impl fmt::Display for MfaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MfaError::AlreadyEnabled => write!(f, "Two-factor authentication already enabled"),
+            MfaError::NotEnrolled => write!(f, "No two-factor enrollment to confirm"),
+            MfaError::InvalidCode => write!(f, "Invalid two-factor code"),
+            MfaError::DbInternalError => write!(f, "Database internal error"),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::core::application::usecase::mfa::error::MfaError;
+use crate::core::domain::entity::mfa::UserMfa;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::mfa::{MfaManagement, MfaRepo, TotpEnrollment};
+use crate::shared::config::config::Config;
+
+#[derive(Debug, Clone)]
+pub struct MfaService<K>
+where
+    K: MfaRepo,
+{
+    mfa_repository: Arc<K>,
+}
+
+impl<K> MfaService<K>
+where
+    K: MfaRepo,
+{
+    pub fn new(mfa_repository: Arc<K>) -> Self {
+        Self { mfa_repository }
+    }
+
+    async fn find(&self, user_id: UserId) -> Result<Option<UserMfa>, MfaError> {
+        self.mfa_repository
+            .find_by_user(user_id)
+            .await
+            .map_err(|_| MfaError::DbInternalError)
+    }
+}
+
+#[async_trait]
+impl<K> MfaManagement for MfaService<K>
+where
+    K: MfaRepo,
+{
+    async fn enroll_totp(&self, user_id: UserId, email: &str) -> Result<TotpEnrollment, MfaError> {
+        if self
+            .find(user_id)
+            .await?
+            .is_some_and(|mfa| mfa.is_enabled())
+        {
+            return Err(MfaError::AlreadyEnabled);
+        }
+
+        let mfa = self
+            .mfa_repository
+            .save(&UserMfa::new(user_id))
+            .await
+            .map_err(|_| MfaError::DbInternalError)?;
+        let totp = mfa.totp().ok_or(MfaError::DbInternalError)?;
+
+        Ok(TotpEnrollment {
+            otpauth_uri: totp.otpauth_uri(&Config::get().server.name, email),
+            secret: mfa.totp_secret,
+        })
+    }
+
+    async fn confirm_totp(&self, user_id: UserId, code: &str) -> Result<Vec<String>, MfaError> {
+        let mut mfa = self.find(user_id).await?.ok_or(MfaError::NotEnrolled)?;
+        if mfa.is_enabled() {
+            return Err(MfaError::AlreadyEnabled);
+        }
+        if !mfa.verify_totp(code, Timestamp::now_utc().to_unix_timestamp()) {
+            return Err(MfaError::InvalidCode);
+        }
+
+        let recovery_codes = mfa.enable();
+        self.mfa_repository
+            .save(&mfa)
+            .await
+            .map_err(|_| MfaError::DbInternalError)?;
+
+        Ok(recovery_codes)
+    }
+}
@@ -1,3 +1,10 @@
+pub mod admin;
+pub mod api_key;
+pub mod audit;
 pub mod auth;
 pub mod company;
+pub mod identity;
+pub mod mfa;
+pub mod purge;
+pub mod session;
 pub mod user;
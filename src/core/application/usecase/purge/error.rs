@@ -0,0 +1,19 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum PurgeError {
+    /// Purging runs as a system job only.
+    NotRoot,
+    DbInternalError,
+}
+
+impl fmt::Display for PurgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PurgeError::NotRoot => write!(f, "Only the system can purge deleted rows"),
+            PurgeError::DbInternalError => write!(f, "Database internal error"),
+        }
+    }
+}
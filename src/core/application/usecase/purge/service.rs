@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{Duration, TimeZone, Utc};
+
+use crate::core::application::usecase::purge::error::PurgeError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::port::audit::AuditLog;
+use crate::core::port::company::CompanyRepo;
+use crate::core::port::purge::{DeletedPurging, PurgeReport};
+use crate::core::port::user::UserRepo;
+use crate::shared::ctx::ctx::Ctx;
+
+#[derive(Clone)]
+pub struct PurgeService<U, C>
+where
+    U: UserRepo,
+    C: CompanyRepo,
+{
+    user_repository: Arc<U>,
+    company_repository: Arc<C>,
+    audit_log: Arc<dyn AuditLog>,
+    /// Seconds a deleted row is kept.
+    retention: u64,
+}
+
+impl<U, C> PurgeService<U, C>
+where
+    U: UserRepo,
+    C: CompanyRepo,
+{
+    pub fn new(
+        user_repository: Arc<U>,
+        company_repository: Arc<C>,
+        audit_log: Arc<dyn AuditLog>,
+        retention: u64,
+    ) -> Self {
+        Self {
+            user_repository,
+            company_repository,
+            audit_log,
+            retention,
+        }
+    }
+}
+
+/// Rows deleted before the returned moment are past `retention` seconds.
+/// Retentions reaching back before year 1 stop there: no row is that old, and
+/// the database cannot hold much earlier moments.
+fn cutoff(now: &Timestamp, retention: u64) -> Timestamp {
+    let earliest = Utc.with_ymd_and_hms(1, 1, 1, 0, 0, 0).unwrap();
+    let cutoff = i64::try_from(retention)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .and_then(|retention| now.datetime.checked_sub_signed(retention))
+        .map_or(earliest, |cutoff| cutoff.max(earliest));
+
+    Timestamp::new(cutoff)
+}
+
+#[async_trait]
+impl<U, C> DeletedPurging for PurgeService<U, C>
+where
+    U: UserRepo,
+    C: CompanyRepo,
+{
+    async fn purge(&self, ctx: &Ctx) -> Result<PurgeReport, PurgeError> {
+        if !ctx.is_root() {
+            return Err(PurgeError::NotRoot);
+        }
+        let before = cutoff(&Timestamp::now_utc(), self.retention);
+
+        let companies = self
+            .company_repository
+            .purge_deleted(&before)
+            .await
+            .map_err(|_| PurgeError::DbInternalError)?;
+        let users = self
+            .user_repository
+            .purge_deleted(&before)
+            .await
+            .map_err(|_| PurgeError::DbInternalError)?;
+        let report = PurgeReport { users, companies };
+
+        if report != PurgeReport::default() {
+            let entry = AuditEntry::new(AuditAction::DeletedPurged, None, None).with_details(
+                format!("users={}; companies={}", report.users, report.companies),
+            );
+            if let Err(error) = self.audit_log.record(entry.clone()).await {
+                tracing::error!("Audit entry not recorded: {}, {:?}", error, entry);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cutoff_lies_retention_seconds_back() {
+        let now = Timestamp::new(Utc.with_ymd_and_hms(2024, 10, 20, 12, 0, 0).unwrap());
+
+        assert_eq!(
+            cutoff(&now, 24 * 3600),
+            Timestamp::new(Utc.with_ymd_and_hms(2024, 10, 19, 12, 0, 0).unwrap())
+        );
+        assert_eq!(cutoff(&now, 0), now);
+        let earliest = Timestamp::new(Utc.with_ymd_and_hms(1, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(cutoff(&now, u64::MAX), earliest);
+        assert_eq!(cutoff(&now, i64::MAX as u64), earliest);
+        assert_eq!(cutoff(&now, 3000 * 365 * 24 * 3600), earliest);
+        // The cutoff is always one the database can store.
+        cutoff(&now, u64::MAX).convert_to_offset();
+    }
+}
@@ -0,0 +1,26 @@
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SessionError {
+    InvalidRefreshToken,
+    SessionExpired,
+    RefreshTokenReused,
+    SessionNotFound,
+    DbInternalError,
+    TokenError,
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::InvalidRefreshToken => write!(f, "Invalid refresh token"),
+            SessionError::SessionExpired => write!(f, "Session expired or revoked"),
+            SessionError::RefreshTokenReused => write!(f, "Refresh token reused"),
+            SessionError::SessionNotFound => write!(f, "Session not found"),
+            SessionError::DbInternalError => write!(f, "Database internal error"),
+            SessionError::TokenError => write!(f, "Access token cannot be issued"),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::core::application::usecase::auth::token::generate_web_token;
+use crate::core::application::usecase::session::error::SessionError;
+use crate::core::domain::entity::session::Session;
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::session::{SessionManagement, SessionMeta, SessionRepo, TokenPair};
+use crate::core::port::user::UserRepo;
+use crate::shared::config::config::Config;
+
+#[derive(Debug, Clone)]
+pub struct SessionService<K, U>
+where
+    K: SessionRepo,
+    U: UserRepo,
+{
+    session_repository: Arc<K>,
+    user_repository: Arc<U>,
+}
+
+impl<K, U> SessionService<K, U>
+where
+    K: SessionRepo,
+    U: UserRepo,
+{
+    pub fn new(session_repository: Arc<K>, user_repository: Arc<U>) -> Self {
+        Self {
+            session_repository,
+            user_repository,
+        }
+    }
+
+    fn token_pair(
+        user: &User,
+        session: Session,
+        refresh_token: String,
+    ) -> Result<TokenPair, SessionError> {
+        let user_id = user.id.ok_or(SessionError::InvalidRefreshToken)?;
+        let access_token = generate_web_token(user_id, user.email.as_str(), &user.role, &session)
+            .map_err(|_| SessionError::TokenError)?;
+
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            session,
+        })
+    }
+
+    async fn revoke_reused(&self, session_id: Uuid) -> Result<(), SessionError> {
+        tracing::warn!(
+            "Refresh token reuse detected, revoking session {}",
+            session_id
+        );
+
+        self.session_repository
+            .revoke(session_id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)
+    }
+}
+
+#[async_trait]
+impl<K, U> SessionManagement for SessionService<K, U>
+where
+    K: SessionRepo,
+    U: UserRepo,
+{
+    async fn start(
+        &self,
+        user: &User,
+        mfa: bool,
+        meta: &SessionMeta,
+    ) -> Result<TokenPair, SessionError> {
+        let user_id = user.id.ok_or(SessionError::InvalidRefreshToken)?;
+        let (session, refresh_token) = Session::new(
+            user_id,
+            mfa,
+            meta.user_agent.clone(),
+            meta.ip.clone(),
+            Config::get().auth.jwt.refresh_expiration,
+        );
+
+        let session = self
+            .session_repository
+            .save(&session)
+            .await
+            .map_err(|_| SessionError::DbInternalError)?;
+
+        Self::token_pair(user, session, refresh_token)
+    }
+
+    async fn refresh(
+        &self,
+        refresh_token: &str,
+        meta: &SessionMeta,
+    ) -> Result<TokenPair, SessionError> {
+        let id = Session::id_from_refresh_token(refresh_token)
+            .ok_or(SessionError::InvalidRefreshToken)?;
+        let mut session = self
+            .session_repository
+            .find_by_id(id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)?
+            .ok_or(SessionError::InvalidRefreshToken)?;
+
+        if !session.is_active() {
+            return Err(SessionError::SessionExpired);
+        }
+
+        // The session exists but the token is not its current one: a rotated
+        // token is being replayed, so whoever holds the chain is not trusted.
+        if !session.matches(refresh_token) {
+            self.revoke_reused(session.id).await?;
+            return Err(SessionError::RefreshTokenReused);
+        }
+
+        let previous_hash = session.refresh_token_hash.clone();
+        let next_token = session.rotate(Config::get().auth.jwt.refresh_expiration);
+        session.user_agent = meta.user_agent.clone().or(session.user_agent);
+        session.ip = meta.ip.clone().or(session.ip);
+
+        let rotated = self
+            .session_repository
+            .rotate(&session, &previous_hash)
+            .await
+            .map_err(|_| SessionError::DbInternalError)?;
+        // Another request rotated the same token first.
+        if !rotated {
+            self.revoke_reused(session.id).await?;
+            return Err(SessionError::RefreshTokenReused);
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(session.user_id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)?
+            .filter(|user| !user.is_blocked())
+            .ok_or(SessionError::InvalidRefreshToken)?;
+
+        Self::token_pair(&user, session, next_token)
+    }
+
+    async fn list(&self, user_id: UserId) -> Result<Vec<Session>, SessionError> {
+        self.session_repository
+            .find_active_by_user(user_id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)
+    }
+
+    async fn is_active(&self, session_id: Uuid) -> Result<bool, SessionError> {
+        let session = match self
+            .session_repository
+            .find_by_id(session_id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)?
+        {
+            Some(session) if session.is_active() => session,
+            _ => return Ok(false),
+        };
+
+        // Blocking a user ends their sessions right away.
+        let user = self
+            .user_repository
+            .find_by_id(session.user_id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)?;
+
+        Ok(user.is_some_and(|user| !user.is_blocked()))
+    }
+
+    async fn revoke(&self, user_id: UserId, session_id: Uuid) -> Result<(), SessionError> {
+        let session = self
+            .session_repository
+            .find_by_id(session_id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)?
+            .filter(|session| session.user_id == user_id)
+            .ok_or(SessionError::SessionNotFound)?;
+
+        self.session_repository
+            .revoke(session.id)
+            .await
+            .map_err(|_| SessionError::DbInternalError)
+    }
+}
@@ -0,0 +1,128 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::scope::Scope;
+use crate::shared::data::base64::b64u_encode;
+
+const KEY_PREFIX: &str = "mm";
+const PREFIX_LEN: usize = 12;
+
+/// A personal API key. Keys have the form `mm_<prefix>_<secret>`: the prefix
+/// finds the key and is shown in lists, only a hash of the whole key is kept.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub user_id: UserId,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<Scope>,
+    /// Whether the key was created from a session that passed 2FA.
+    pub mfa: bool,
+    pub created_at: Timestamp,
+    pub last_used_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
+    pub revoked_at: Option<Timestamp>,
+}
+
+impl ApiKey {
+    /// Creates a key, returning it with its plain text value. The value cannot
+    /// be recovered afterwards.
+    pub fn new(
+        user_id: UserId,
+        name: String,
+        scopes: Vec<Scope>,
+        mfa: bool,
+        expires_at: Option<Timestamp>,
+    ) -> (Self, String) {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let prefix: String = Uuid::new_v4().simple().to_string()[..PREFIX_LEN].to_string();
+        let key = format!("{}_{}_{}", KEY_PREFIX, prefix, b64u_encode(bytes));
+
+        let api_key = ApiKey {
+            id: Uuid::new_v4(),
+            user_id,
+            name,
+            prefix,
+            key_hash: hash_key(&key),
+            scopes,
+            mfa,
+            created_at: Timestamp::now_utc(),
+            last_used_at: None,
+            expires_at,
+            revoked_at: None,
+        };
+
+        (api_key, key)
+    }
+
+    /// Prefix named by a key.
+    pub fn prefix_of(key: &str) -> Option<&str> {
+        let rest = key.strip_prefix(KEY_PREFIX)?.strip_prefix('_')?;
+        let (prefix, secret) = rest.split_once('_')?;
+
+        (prefix.len() == PREFIX_LEN && !secret.is_empty()).then_some(prefix)
+    }
+
+    pub fn matches(&self, key: &str) -> bool {
+        self.key_hash == hash_key(key)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+            && self
+                .expires_at
+                .as_ref()
+                .is_none_or(|expires_at| expires_at.datetime > Timestamp::now_utc().datetime)
+    }
+}
+
+fn hash_key(key: &str) -> String {
+    b64u_encode(Sha256::digest(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_names_its_prefix() {
+        let (api_key, key) = ApiKey::new(
+            UserId::generate(),
+            "ci".to_string(),
+            vec![Scope::ProfileRead],
+            false,
+            None,
+        );
+
+        assert_eq!(ApiKey::prefix_of(&key), Some(api_key.prefix.as_str()));
+        assert!(api_key.matches(&key));
+        assert!(!api_key.matches(&format!("{}x", key)));
+        assert!(api_key.is_active());
+        assert_eq!(api_key.scopes, vec![Scope::ProfileRead]);
+        assert_eq!(ApiKey::prefix_of("mm_short_secret"), None);
+        assert_eq!(ApiKey::prefix_of("garbage"), None);
+    }
+
+    #[test]
+    fn revoked_and_expired_keys_are_inactive() {
+        let (mut revoked, _) =
+            ApiKey::new(UserId::generate(), "a".to_string(), vec![], false, None);
+        revoked.revoked_at = Some(Timestamp::now_utc());
+        let (expired, _) = ApiKey::new(
+            UserId::generate(),
+            "b".to_string(),
+            vec![],
+            false,
+            Some(Timestamp::now_utc()),
+        );
+
+        assert!(!revoked.is_active());
+        assert!(!expired.is_active());
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::domain::error::DomainError;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+
+/// What an audit entry records.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    LoginSucceeded,
+    LoginFailed,
+    Logout,
+    SessionRevoked,
+    PasswordChanged,
+    PasswordResetForced,
+    RoleChanged,
+    UserBlocked,
+    UserUnblocked,
+    LockoutCleared,
+    UserDeleted,
+    UserRestored,
+    MfaEnabled,
+    ApiKeyCreated,
+    ApiKeyRevoked,
+    SigningKeyRotated,
+    CompanyRegistered,
+    CompanyUpdated,
+    CompanyDeleted,
+    CompanyRestored,
+    EmploymentChanged,
+    DeletedPurged,
+}
+
+impl AuditAction {
+    pub const ALL: &'static [AuditAction] = &[
+        AuditAction::LoginSucceeded,
+        AuditAction::LoginFailed,
+        AuditAction::Logout,
+        AuditAction::SessionRevoked,
+        AuditAction::PasswordChanged,
+        AuditAction::PasswordResetForced,
+        AuditAction::RoleChanged,
+        AuditAction::UserBlocked,
+        AuditAction::UserUnblocked,
+        AuditAction::LockoutCleared,
+        AuditAction::UserDeleted,
+        AuditAction::UserRestored,
+        AuditAction::MfaEnabled,
+        AuditAction::ApiKeyCreated,
+        AuditAction::ApiKeyRevoked,
+        AuditAction::SigningKeyRotated,
+        AuditAction::CompanyRegistered,
+        AuditAction::CompanyUpdated,
+        AuditAction::CompanyDeleted,
+        AuditAction::CompanyRestored,
+        AuditAction::EmploymentChanged,
+        AuditAction::DeletedPurged,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LoginSucceeded => "login_succeeded",
+            AuditAction::LoginFailed => "login_failed",
+            AuditAction::Logout => "logout",
+            AuditAction::SessionRevoked => "session_revoked",
+            AuditAction::PasswordChanged => "password_changed",
+            AuditAction::PasswordResetForced => "password_reset_forced",
+            AuditAction::RoleChanged => "role_changed",
+            AuditAction::UserBlocked => "user_blocked",
+            AuditAction::UserUnblocked => "user_unblocked",
+            AuditAction::LockoutCleared => "lockout_cleared",
+            AuditAction::UserDeleted => "user_deleted",
+            AuditAction::UserRestored => "user_restored",
+            AuditAction::MfaEnabled => "mfa_enabled",
+            AuditAction::ApiKeyCreated => "api_key_created",
+            AuditAction::ApiKeyRevoked => "api_key_revoked",
+            AuditAction::SigningKeyRotated => "signing_key_rotated",
+            AuditAction::CompanyRegistered => "company_registered",
+            AuditAction::CompanyUpdated => "company_updated",
+            AuditAction::CompanyDeleted => "company_deleted",
+            AuditAction::CompanyRestored => "company_restored",
+            AuditAction::EmploymentChanged => "employment_changed",
+            AuditAction::DeletedPurged => "deleted_purged",
+        }
+    }
+}
+
+impl TryFrom<&str> for AuditAction {
+    type Error = DomainError;
+
+    fn try_from(action: &str) -> Result<Self, Self::Error> {
+        AuditAction::ALL
+            .iter()
+            .find(|a| a.as_str() == action)
+            .copied()
+            .ok_or_else(|| DomainError::UnknownAuditAction(action.to_string()))
+    }
+}
+
+/// A security relevant event: who (`actor_id`) did what (`action`) to whom or
+/// what (`target_id`), and from which client. Entries are append-only.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub action: AuditAction,
+    /// `None` when the caller is not authenticated, e.g. a failed login.
+    pub actor_id: Option<UserId>,
+    pub target_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// Free-form context, such as the new role or the email of a failed login.
+    pub details: Option<String>,
+    pub created_at: Timestamp,
+}
+
+impl AuditEntry {
+    pub fn new(action: AuditAction, actor_id: Option<UserId>, target_id: Option<Uuid>) -> Self {
+        AuditEntry {
+            id: Uuid::new_v4(),
+            action,
+            actor_id,
+            target_id,
+            ip: None,
+            user_agent: None,
+            details: None,
+            created_at: Timestamp::now_utc(),
+        }
+    }
+
+    pub fn with_client(mut self, ip: Option<String>, user_agent: Option<String>) -> Self {
+        self.ip = ip;
+        self.user_agent = user_agent;
+        self
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_round_trip_through_their_names() {
+        for action in AuditAction::ALL {
+            let json = serde_json::to_string(action).unwrap();
+
+            assert_eq!(json, format!("\"{}\"", action.as_str()));
+            assert_eq!(AuditAction::try_from(action.as_str()), Ok(*action));
+        }
+        assert!(AuditAction::try_from("unknown").is_err());
+    }
+}
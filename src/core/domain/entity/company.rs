@@ -1,12 +1,12 @@
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::CompanyId;
 use crate::core::domain::valueobject::sector::Sector;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Company {
-    pub id: Option<Uuid>,
+    pub id: Option<CompanyId>,
     pub name: String,
     pub foundation_date: i16,
     pub description: String,
@@ -14,6 +14,14 @@ pub struct Company {
     pub sector: Sector,
     pub updated_at: Timestamp,
     pub created_at: Timestamp,
+    /// Bumped on every update. An update only applies to the version it was
+    /// read at, see [`VersionConflict`].
+    ///
+    /// [`VersionConflict`]: crate::core::port::error::VersionConflict
+    pub version: i32,
+    /// Set when the company is soft-deleted: hidden from every query until it is
+    /// restored or purged.
+    pub deleted_at: Option<Timestamp>,
 }
 
 impl Company {
@@ -25,7 +33,7 @@ impl Company {
         sector: Sector,
     ) -> Self {
         Company {
-            id: Some(Uuid::new_v4()),
+            id: Some(CompanyId::generate()),
             name,
             foundation_date,
             description,
@@ -33,6 +41,8 @@ impl Company {
             sector,
             created_at: Timestamp::now_utc(),
             updated_at: Timestamp::now_utc(),
+            version: 1,
+            deleted_at: None,
         }
     }
 }
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::{CompanyId, EmploymentId, UserId};
+use crate::core::domain::valueobject::position::Position;
+
+/// A user holding a position in a company, since `created_at`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Employment {
+    pub id: EmploymentId,
+    pub user_id: UserId,
+    pub company_id: CompanyId,
+    pub position: Position,
+    pub created_at: Timestamp,
+}
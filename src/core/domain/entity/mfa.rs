@@ -0,0 +1,175 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::totp::Totp;
+use crate::shared::data::base64::b64u_encode;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+/// No `0/o`, `1/l/i`: recovery codes get typed from paper.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// TOTP second factor of a user. It is enrolled first and only enforced at
+/// login once confirmed with a valid code, see [`UserMfa::enable`].
+///
+/// Recovery codes are only kept hashed, each one can be used once.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserMfa {
+    pub user_id: UserId,
+    /// Base32 TOTP secret.
+    pub totp_secret: String,
+    pub enabled_at: Option<Timestamp>,
+    /// Time step of the last accepted code, so a code cannot be replayed.
+    pub last_used_step: Option<i64>,
+    pub recovery_codes: Vec<String>,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+impl UserMfa {
+    /// Starts a TOTP enrollment with a fresh secret.
+    pub fn new(user_id: UserId) -> Self {
+        UserMfa {
+            user_id,
+            totp_secret: Totp::generate().to_base32(),
+            enabled_at: None,
+            last_used_step: None,
+            recovery_codes: vec![],
+            created_at: Timestamp::now_utc(),
+            updated_at: Timestamp::now_utc(),
+        }
+    }
+
+    pub fn totp(&self) -> Option<Totp> {
+        Totp::from_base32(&self.totp_secret)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// Turns the second factor on and issues a new set of recovery codes,
+    /// returned in plain text. They cannot be recovered afterwards.
+    pub fn enable(&mut self) -> Vec<String> {
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect();
+
+        self.recovery_codes = codes.iter().map(|code| hash_recovery_code(code)).collect();
+        self.enabled_at = Some(Timestamp::now_utc());
+        self.updated_at = Timestamp::now_utc();
+
+        codes
+    }
+
+    /// Checks a TOTP code at `unix_time`. A code is only accepted once, and
+    /// never one older than the last accepted code.
+    pub fn verify_totp(&mut self, code: &str, unix_time: u64) -> bool {
+        let Some(step) = self.totp().and_then(|totp| totp.verify(code, unix_time)) else {
+            return false;
+        };
+        let step = step as i64;
+        if self.last_used_step.is_some_and(|last| step <= last) {
+            return false;
+        }
+
+        self.last_used_step = Some(step);
+        self.updated_at = Timestamp::now_utc();
+
+        true
+    }
+
+    /// Consumes `code` if it is one of the unused recovery codes.
+    pub fn use_recovery_code(&mut self, code: &str) -> bool {
+        let hash = hash_recovery_code(code);
+        let Some(index) = self.recovery_codes.iter().position(|h| *h == hash) else {
+            return false;
+        };
+
+        self.recovery_codes.remove(index);
+        self.updated_at = Timestamp::now_utc();
+
+        true
+    }
+
+    /// Accepts either a TOTP code or a recovery code, and tells which one to
+    /// record.
+    pub fn verify(&mut self, code: &str, unix_time: u64) -> Option<UsedCode> {
+        if self.verify_totp(code, unix_time) {
+            return self.last_used_step.map(UsedCode::TotpStep);
+        }
+        let hash = hash_recovery_code(code);
+
+        self.use_recovery_code(code)
+            .then_some(UsedCode::RecoveryCode(hash))
+    }
+}
+
+/// A code accepted by [`UserMfa::verify`], recorded so that it is not accepted
+/// again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsedCode {
+    /// Time step of a TOTP code.
+    TotpStep(i64),
+    /// Hash of a recovery code.
+    RecoveryCode(String),
+}
+
+fn generate_recovery_code() -> String {
+    let mut rng = rand::thread_rng();
+    let mut chars = (0..10)
+        .map(|_| RECOVERY_CODE_ALPHABET[rng.gen_range(0..RECOVERY_CODE_ALPHABET.len())] as char);
+    let head: String = chars.by_ref().take(5).collect();
+    let tail: String = chars.collect();
+
+    format!("{}-{}", head, tail)
+}
+
+/// Hash of a recovery code, ignoring case, spaces and dashes.
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    b64u_encode(Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totp_codes_cannot_be_replayed() {
+        let mut mfa = UserMfa::new(UserId::generate());
+        let totp = mfa.totp().unwrap();
+        let now = Timestamp::now_utc().to_unix_timestamp();
+        let code = totp.code_at(now / 30);
+
+        assert!(mfa.verify_totp(&code, now));
+        assert!(!mfa.verify_totp(&code, now));
+        assert!(!mfa.verify_totp(&totp.code_at(now / 30 - 1), now));
+        assert!(mfa.verify_totp(&totp.code_at(now / 30 + 1), now));
+    }
+
+    #[test]
+    fn recovery_codes_are_single_use() {
+        let mut mfa = UserMfa::new(UserId::generate());
+
+        let codes = mfa.enable();
+
+        assert!(mfa.is_enabled());
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert!(!mfa.recovery_codes.contains(&codes[0]));
+        assert!(mfa.use_recovery_code(&codes[0].to_uppercase()));
+        assert!(!mfa.use_recovery_code(&codes[0]));
+        assert_eq!(
+            mfa.verify(&codes[1], 0),
+            Some(UsedCode::RecoveryCode(hash_recovery_code(&codes[1])))
+        );
+        assert_eq!(mfa.recovery_codes.len(), RECOVERY_CODE_COUNT - 2);
+    }
+}
@@ -1,2 +1,8 @@
+pub mod api_key;
+pub mod audit;
 pub mod company;
+pub mod employment;
+pub mod identity;
+pub mod mfa;
+pub mod session;
 pub mod user;
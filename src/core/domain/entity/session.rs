@@ -0,0 +1,136 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::shared::data::base64::b64u_encode;
+
+/// A login session. It holds the hash of the current refresh token; every
+/// refresh rotates it, so the session is the whole chain of refresh tokens.
+///
+/// Refresh tokens have the form `<session id>.<secret>`. A token that names
+/// the session but does not match the current hash is an already rotated
+/// token being reused.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: UserId,
+    pub refresh_token_hash: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    /// Whether the login that started the session passed a second factor.
+    pub mfa: bool,
+    pub created_at: Timestamp,
+    pub last_used_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub revoked_at: Option<Timestamp>,
+}
+
+impl Session {
+    /// Starts a session for `user_id`, returning it with its first refresh
+    /// token.
+    pub fn new(
+        user_id: UserId,
+        mfa: bool,
+        user_agent: Option<String>,
+        ip: Option<String>,
+        duration_sec: u64,
+    ) -> (Self, String) {
+        let now = Timestamp::now_utc();
+        let mut session = Session {
+            id: Uuid::new_v4(),
+            user_id,
+            refresh_token_hash: String::new(),
+            user_agent,
+            ip,
+            mfa,
+            created_at: now.clone(),
+            last_used_at: now,
+            expires_at: Timestamp::now_utc(),
+            revoked_at: None,
+        };
+        let refresh_token = session.rotate(duration_sec);
+
+        (session, refresh_token)
+    }
+
+    /// Session id named by a refresh token.
+    pub fn id_from_refresh_token(refresh_token: &str) -> Option<Uuid> {
+        let (id, _) = refresh_token.split_once('.')?;
+        Uuid::parse_str(id).ok()
+    }
+
+    /// Replaces the refresh token and extends the session by `duration_sec`.
+    /// Returns the new refresh token.
+    pub fn rotate(&mut self, duration_sec: u64) -> String {
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let refresh_token = format!("{}.{}", self.id, b64u_encode(secret));
+
+        self.refresh_token_hash = hash_refresh_token(&refresh_token);
+        self.last_used_at = Timestamp::now_utc();
+        self.expires_at = Timestamp::now_utc() + duration_sec * 1000;
+
+        refresh_token
+    }
+
+    pub fn matches(&self, refresh_token: &str) -> bool {
+        self.refresh_token_hash == hash_refresh_token(refresh_token)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at.datetime > Timestamp::now_utc().datetime
+    }
+
+    pub fn revoke(&mut self) {
+        if self.revoked_at.is_none() {
+            self.revoked_at = Some(Timestamp::now_utc());
+        }
+    }
+}
+
+fn hash_refresh_token(refresh_token: &str) -> String {
+    b64u_encode(Sha256::digest(refresh_token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_token_names_its_session() {
+        let (session, refresh_token) = Session::new(UserId::generate(), false, None, None, 60);
+
+        assert_eq!(
+            Session::id_from_refresh_token(&refresh_token),
+            Some(session.id)
+        );
+        assert_eq!(Session::id_from_refresh_token("garbage"), None);
+        assert!(session.matches(&refresh_token));
+        assert!(session.is_active());
+    }
+
+    #[test]
+    fn rotation_invalidates_previous_refresh_token() {
+        let (mut session, first) = Session::new(UserId::generate(), false, None, None, 60);
+
+        let second = session.rotate(60);
+
+        assert_ne!(first, second);
+        assert!(!session.matches(&first));
+        assert!(session.matches(&second));
+        assert_eq!(Session::id_from_refresh_token(&second), Some(session.id));
+    }
+
+    #[test]
+    fn revoked_and_expired_sessions_are_inactive() {
+        let (mut revoked, _) = Session::new(UserId::generate(), false, None, None, 60);
+        revoked.revoke();
+        let (expired, _) = Session::new(UserId::generate(), false, None, None, 0);
+
+        assert!(!revoked.is_active());
+        assert!(!expired.is_active());
+    }
+}
@@ -3,15 +3,17 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
 use crate::core::domain::valueobject::password::HashedPassword;
 use crate::core::domain::valueobject::role::Role;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct User {
-    pub id: Option<Uuid>,
+    pub id: Option<UserId>,
     pub name: String,
     pub surname: String,
-    pub email: String,
+    pub email: Email,
     pub role: Role,
     pub password_hash: HashedPassword,
     pub reset_token: Option<String>,
@@ -22,18 +24,32 @@ pub struct User {
     pub blocked_at: Option<Timestamp>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
+    /// Bumped on every update. An update only applies to the version it was
+    /// read at, see [`VersionConflict`].
+    ///
+    /// [`VersionConflict`]: crate::core::port::error::VersionConflict
+    pub version: i32,
+    /// Set when the user is soft-deleted: hidden from every query until it is
+    /// restored or purged.
+    pub deleted_at: Option<Timestamp>,
 }
 
 impl User {
-    pub fn new(name: String, surname: String, email: String, password: String, role: Role) -> Self {
-        let hashed_password = HashedPassword::new(password.as_str(), &email);
-        User {
-            id: Some(Uuid::new_v4()),
+    pub fn new(
+        name: String,
+        surname: String,
+        email: Email,
+        password: String,
+        role: Role,
+    ) -> Result<Self, Error> {
+        let hashed_password = HashedPassword::new(password.as_str())?;
+        Ok(User {
+            id: Some(UserId::generate()),
             name,
             surname,
             email,
             role,
-            password_hash: hashed_password.unwrap(),
+            password_hash: hashed_password,
             reset_token: None,
             reset_sent_at: None,
             email_verification_token: None,
@@ -42,7 +58,9 @@ impl User {
             blocked_at: None,
             created_at: Timestamp::now_utc(),
             updated_at: Timestamp::now_utc(),
-        }
+            version: 1,
+            deleted_at: None,
+        })
     }
 
     pub fn update_role(&mut self, role: Option<Role>) -> Result<(), Error> {
@@ -52,4 +70,38 @@ impl User {
 
         Ok(())
     }
+
+    /// Replaces the password hash with a fresh one for `password`.
+    pub fn set_password(&mut self, password: &str) -> Result<(), Error> {
+        self.password_hash = HashedPassword::new(password)?;
+
+        Ok(())
+    }
+
+    /// Blocked users cannot log in, and their sessions and API keys stop
+    /// working until they are unblocked.
+    pub fn is_blocked(&self) -> bool {
+        self.blocked_at.is_some()
+    }
+
+    /// Blocks the user. Blocking twice keeps the first time.
+    pub fn block(&mut self) {
+        if self.blocked_at.is_none() {
+            self.blocked_at = Some(Timestamp::now_utc());
+        }
+    }
+
+    pub fn unblock(&mut self) {
+        self.blocked_at = None;
+    }
+
+    /// Invalidates the current password and issues a fresh reset token, so the
+    /// user can only get back in through the reset flow.
+    pub fn force_password_reset(&mut self) -> Result<(), Error> {
+        self.set_password(&Uuid::new_v4().to_string())?;
+        self.reset_token = Some(Uuid::new_v4().to_string());
+        self.reset_sent_at = Some(Timestamp::now_utc());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+/// A value that does not map to the domain, such as a role name read back
+/// from storage that no longer exists.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DomainError {
+    #[error("Unknown role: {0}")]
+    UnknownRole(String),
+    #[error("Unknown sector: {0}")]
+    UnknownSector(String),
+    #[error("Unknown position: {0}")]
+    UnknownPosition(String),
+    #[error("Unknown audit action: {0}")]
+    UnknownAuditAction(String),
+    #[error("Invalid email: {0}")]
+    InvalidEmail(String),
+    /// The entity was never saved, so it has no id yet.
+    #[error("{0} has no id")]
+    MissingId(&'static str),
+}
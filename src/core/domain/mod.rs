@@ -1,3 +1,4 @@
 pub mod aggregate;
 pub mod entity;
+pub mod error;
 pub mod valueobject;
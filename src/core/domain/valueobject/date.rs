@@ -1,23 +1,36 @@
 use std::ops::Add;
 
 use anyhow::{anyhow, Error};
-use chrono::{DateTime, Duration, Local, NaiveDateTime, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, SecondsFormat, Timelike, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
 use sqlx::types::time::OffsetDateTime;
+use sqlx::{Decode, Encode, Postgres, Type};
 use tower_cookies::cookie::time::format_description::well_known::Rfc3339;
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+/// A point in time, in UTC, to the microsecond: the precision Postgres keeps
+/// for `TIMESTAMPTZ`. Finer precision is truncated on construction, so a
+/// timestamp reads back from the database exactly as it was written.
+///
+/// Serializes as an RFC 3339 string, e.g. `2024-10-19T12:30:45.123456Z`.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Timestamp {
     pub datetime: DateTime<Utc>,
 }
 
 impl From<OffsetDateTime> for Timestamp {
     fn from(odt: OffsetDateTime) -> Self {
-        let dt = Utc::now();
-        let naive_utc = dt.naive_utc();
-        let offset = dt.offset().clone();
-        let dt_new = DateTime::<Utc>::from_naive_utc_and_offset(naive_utc, offset);
-        Self { datetime: dt_new }
+        let datetime = DateTime::<Utc>::from_timestamp(odt.unix_timestamp(), odt.nanosecond())
+            .expect("OffsetDateTime is within chrono's range");
+        Timestamp::new(datetime)
+    }
+}
+
+impl From<Timestamp> for OffsetDateTime {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.convert_to_offset()
     }
 }
 
@@ -33,13 +46,14 @@ impl Add<u64> for Timestamp {
 
 impl Timestamp {
     pub fn new(datetime: DateTime<Utc>) -> Self {
-        Self { datetime }
+        let micros = datetime.nanosecond() / 1_000 * 1_000;
+        Self {
+            datetime: datetime.with_nanosecond(micros).unwrap_or(datetime),
+        }
     }
 
     pub fn now_utc() -> Self {
-        Self {
-            datetime: Utc::now(),
-        }
+        Timestamp::new(Utc::now())
     }
 
     pub fn to_local(&self) -> DateTime<Local> {
@@ -51,7 +65,19 @@ impl Timestamp {
     }
 
     pub fn convert_to_offset(&self) -> OffsetDateTime {
-        OffsetDateTime::from_unix_timestamp(self.datetime.timestamp()).unwrap()
+        let nanos = i128::from(self.datetime.timestamp()) * 1_000_000_000
+            + i128::from(self.datetime.timestamp_subsec_nanos());
+        OffsetDateTime::from_unix_timestamp_nanos(nanos).expect("Timestamp is within time's range")
+    }
+
+    pub fn to_rfc3339(&self) -> String {
+        self.datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+
+    pub fn parse_rfc3339(moment: &str) -> Result<Self, Error> {
+        DateTime::parse_from_rfc3339(moment)
+            .map(|datetime| Timestamp::new(datetime.with_timezone(&Utc)))
+            .map_err(|e| anyhow!("Invalid RFC 3339 date-time {}: {}", moment, e))
     }
 
     pub fn get_expire_time(&self, expire: u64) -> u64 {
@@ -65,6 +91,107 @@ impl Timestamp {
     }
 }
 
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_rfc3339())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let moment = String::deserialize(deserializer)?;
+        Timestamp::parse_rfc3339(&moment).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Type<Postgres> for Timestamp {
+    fn type_info() -> PgTypeInfo {
+        <OffsetDateTime as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <OffsetDateTime as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Timestamp {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        self.convert_to_offset().encode_by_ref(buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Timestamp {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Timestamp::from(OffsetDateTime::decode(value)?))
+    }
+}
+
 pub fn parse_utc(moment: &str) -> Result<OffsetDateTime, Error> {
     OffsetDateTime::parse(moment, &Rfc3339).map_err(|_| anyhow!("Error while parsing date-time!"))
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use proptest::prelude::*;
+    use proptest::strategy::ValueTree;
+    use proptest::test_runner::TestRunner;
+    use sqlx::{Pool, Postgres};
+
+    use super::*;
+
+    /// Any microsecond between the years 1 and 9999, the range both chrono
+    /// and Postgres can hold.
+    fn timestamps() -> impl Strategy<Value = Timestamp> {
+        let min = Utc.with_ymd_and_hms(1, 1, 1, 0, 0, 0).unwrap();
+        let max = Utc.with_ymd_and_hms(9999, 12, 31, 23, 59, 59).unwrap();
+
+        (min.timestamp_micros()..=max.timestamp_micros())
+            .prop_map(|micros| Timestamp::new(DateTime::from_timestamp_micros(micros).unwrap()))
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips_through_time(timestamp in timestamps()) {
+            prop_assert_eq!(Timestamp::from(timestamp.convert_to_offset()), timestamp);
+        }
+
+        #[test]
+        fn round_trips_through_rfc3339(timestamp in timestamps()) {
+            let json = serde_json::to_string(&timestamp).unwrap();
+
+            prop_assert_eq!(json.clone(), format!("\"{}\"", timestamp.to_rfc3339()));
+            prop_assert_eq!(serde_json::from_str::<Timestamp>(&json).unwrap(), timestamp);
+        }
+    }
+
+    #[test]
+    fn keeps_microseconds_only() {
+        let datetime = Utc.with_ymd_and_hms(2024, 10, 19, 12, 30, 45).unwrap()
+            + Duration::nanoseconds(123_456_789);
+        let timestamp = Timestamp::new(datetime);
+
+        assert_eq!(timestamp.to_rfc3339(), "2024-10-19T12:30:45.123456Z");
+        assert_eq!(
+            Timestamp::parse_rfc3339("2024-10-19T14:30:45.123456789+02:00").unwrap(),
+            timestamp
+        );
+        assert!(Timestamp::parse_rfc3339("2024-10-19 12:30:45").is_err());
+    }
+
+    #[sqlx::test]
+    async fn round_trips_through_the_database(pool: Pool<Postgres>) {
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let timestamp = timestamps().new_tree(&mut runner).unwrap().current();
+            let stored: Timestamp = sqlx::query_scalar("SELECT $1::TIMESTAMPTZ")
+                .bind(&timestamp)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+            assert_eq!(stored, timestamp);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+use validator::ValidateEmail;
+
+use crate::core::domain::error::DomainError;
+
+/// A valid email address, trimmed and lowercased, so that two spellings of
+/// the same address compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Email(String);
+
+impl Email {
+    pub fn parse(email: &str) -> Result<Self, DomainError> {
+        let email = email.trim().to_lowercase();
+
+        if email.validate_email() {
+            Ok(Email(email))
+        } else {
+            Err(DomainError::InvalidEmail(email))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for Email {
+    type Error = DomainError;
+
+    fn try_from(email: &str) -> Result<Self, Self::Error> {
+        Email::parse(email)
+    }
+}
+
+impl TryFrom<String> for Email {
+    type Error = DomainError;
+
+    fn try_from(email: String) -> Result<Self, Self::Error> {
+        Email::parse(&email)
+    }
+}
+
+impl From<Email> for String {
+    fn from(email: Email) -> Self {
+        email.0
+    }
+}
+
+impl AsRef<str> for Email {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Email {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Type<Postgres> for Email {
+    fn type_info() -> PgTypeInfo {
+        <String as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for Email {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Email {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Email::parse(<&str as Decode<Postgres>>::decode(value)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emails_are_validated_and_normalized() {
+        let email = Email::parse("  John.Doe@Example.COM ").unwrap();
+
+        assert_eq!(email.as_str(), "john.doe@example.com");
+        assert_eq!(Email::parse("john.doe@example.com"), Ok(email.clone()));
+        assert_eq!(
+            serde_json::to_string(&email).unwrap(),
+            "\"john.doe@example.com\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Email>("\"JOHN.DOE@example.com\"").unwrap(),
+            email
+        );
+        assert!(Email::parse("john.doe").is_err());
+        assert!(serde_json::from_str::<Email>("\"@example.com\"").is_err());
+    }
+}
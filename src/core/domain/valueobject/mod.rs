@@ -1,5 +1,9 @@
 pub mod date;
+pub mod email;
+pub mod id;
 pub mod password;
 pub mod position;
 pub mod role;
+pub mod scope;
 pub mod sector;
+pub mod totp;
@@ -1,40 +1,49 @@
+use std::sync::OnceLock;
+
+use argonautica::config::DEFAULT_SALT_LEN;
+use argonautica::input::Salt;
+use argonautica::output::HashRaw;
 use argonautica::{Hasher, Verifier};
 use serde_derive::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::shared::config::config::Config;
+use crate::shared::config::config::{Config, Password};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HashedPassword(String);
 
 #[derive(Debug, Error)]
 pub enum PasswordError {
-    #[error("Invalid password")]
-    InvalidPassword,
+    #[error("Password hashing failed: {0}")]
+    HashingFailed(String),
+    #[error("Stored password hash is malformed")]
+    InvalidHash,
 }
 
 impl HashedPassword {
-    pub fn new(password: &str, email: &str) -> Result<Self, PasswordError> {
-        let mut hasher = Hasher::default();
-        let hashed_password = hasher
-            .with_password(password)
-            .with_salt(email)
-            .with_secret_key(get_secret_key())
-            .hash()
-            .unwrap();
-        Ok(HashedPassword(hashed_password))
+    /// Hashes `password` with a random salt and the configured parameters.
+    pub fn new(password: &str) -> Result<Self, PasswordError> {
+        hash(password, &Config::get().auth.password)
     }
 
     pub fn verify_password(&self, password: &str) -> Result<bool, PasswordError> {
-        let mut verifier = Verifier::default();
-        let result = verifier
-            .with_hash(&self.0)
-            .with_password(password)
-            .with_secret_key(get_secret_key())
-            .verify()
-            .map_err(|_| PasswordError::InvalidPassword)?;
+        verify(&self.0, password, &Config::get().auth.password)
+    }
 
-        Ok(result)
+    /// Verifies `password` against a throwaway hash. Logins for unknown
+    /// accounts call this so they take as long as logins for known ones.
+    pub fn verify_dummy(password: &str) {
+        static DUMMY: OnceLock<Option<HashedPassword>> = OnceLock::new();
+
+        if let Some(dummy) = DUMMY.get_or_init(|| HashedPassword::new("dummy password").ok()) {
+            let _ = dummy.verify_password(password);
+        }
+    }
+
+    /// Whether the hash was made with other parameters than the configured
+    /// ones, and should be replaced on the next successful login.
+    pub fn needs_rehash(&self) -> bool {
+        needs_rehash(&self.0, &Config::get().auth.password)
     }
 
     pub fn as_string(&self) -> String {
@@ -48,7 +57,87 @@ impl From<String> for HashedPassword {
     }
 }
 
-fn get_secret_key() -> &'static str {
-    let settings = Config::get();
-    &settings.auth.password.secret
+fn hash(password: &str, config: &Password) -> Result<HashedPassword, PasswordError> {
+    let mut hasher = Hasher::default();
+    let hashed_password = hasher
+        .configure_memory_size(config.memory_size)
+        .configure_iterations(config.iterations)
+        .configure_lanes(config.parallelism)
+        .configure_threads(config.parallelism)
+        .with_password(password)
+        .with_salt(Salt::random(DEFAULT_SALT_LEN))
+        .with_secret_key(config.secret.as_str())
+        .hash()
+        .map_err(|e| PasswordError::HashingFailed(e.to_string()))?;
+
+    Ok(HashedPassword(hashed_password))
+}
+
+fn verify(hash: &str, password: &str, config: &Password) -> Result<bool, PasswordError> {
+    let mut verifier = Verifier::default();
+    verifier
+        .with_hash(hash)
+        .with_password(password)
+        .with_secret_key(config.secret.as_str())
+        .verify()
+        .map_err(|_| PasswordError::InvalidHash)
+}
+
+fn needs_rehash(hash: &str, config: &Password) -> bool {
+    match hash.parse::<HashRaw>() {
+        Ok(raw) => {
+            raw.memory_size() != config.memory_size
+                || raw.iterations() != config.iterations
+                || raw.lanes() != config.parallelism
+                || raw.raw_salt_bytes().len() != DEFAULT_SALT_LEN as usize
+        }
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(iterations: u32) -> Password {
+        Password {
+            secret: "secret".to_string(),
+            memory_size: 1_024,
+            iterations,
+            parallelism: 1,
+        }
+    }
+
+    #[test]
+    fn hash_verifies_only_its_password() {
+        let hashed = hash("password", &config(1)).unwrap();
+
+        assert!(verify(&hashed.0, "password", &config(1)).unwrap());
+        assert!(!verify(&hashed.0, "other", &config(1)).unwrap());
+    }
+
+    #[test]
+    fn hashes_use_random_salts() {
+        let first = hash("password", &config(1)).unwrap();
+        let second = hash("password", &config(1)).unwrap();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn outdated_parameters_need_rehash() {
+        let hashed = hash("password", &config(1)).unwrap();
+
+        assert!(!needs_rehash(&hashed.0, &config(1)));
+        assert!(needs_rehash(&hashed.0, &config(2)));
+        assert!(needs_rehash("not a hash", &config(1)));
+    }
+
+    #[test]
+    fn malformed_hash_is_an_error() {
+        assert!(matches!(
+            verify("not a hash", "password", &config(1)),
+            Err(PasswordError::InvalidHash)
+        ));
+    }
 }
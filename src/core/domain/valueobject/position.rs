@@ -1,9 +1,36 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+use crate::core::domain::error::DomainError;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum Position {
     CEO,
     Manager,
     WhiteCollar,
     BlueCollar,
 }
+
+impl Position {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Position::CEO => "CEO",
+            Position::Manager => "Manager",
+            Position::WhiteCollar => "WhiteCollar",
+            Position::BlueCollar => "BlueCollar",
+        }
+    }
+}
+
+impl TryFrom<&str> for Position {
+    type Error = DomainError;
+
+    fn try_from(position: &str) -> Result<Self, Self::Error> {
+        match position {
+            "CEO" => Ok(Position::CEO),
+            "Manager" => Ok(Position::Manager),
+            "WhiteCollar" => Ok(Position::WhiteCollar),
+            "BlueCollar" => Ok(Position::BlueCollar),
+            _ => Err(DomainError::UnknownPosition(position.to_string())),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::Type;
 
+use crate::core::domain::error::DomainError;
+
 #[derive(Debug, Clone, Deserialize, Serialize, Type, PartialEq)]
 #[sqlx(type_name = "TEXT")]
 pub enum Role {
@@ -10,6 +12,10 @@ pub enum Role {
 }
 
 impl Role {
+    /// Every role, in decreasing order of privilege.
+    pub const ALL: &'static [Role] = &[Role::ADMIN, Role::MODERATOR, Role::USER];
+    pub const ADMINS: &'static [Role] = &[Role::ADMIN];
+
     pub fn as_string(&self) -> String {
         match self {
             Role::ADMIN => "ADMIN".to_string(),
@@ -29,13 +35,39 @@ impl AsRef<str> for Role {
     }
 }
 
-impl From<String> for Role {
-    fn from(s: String) -> Self {
-        match s.as_str() {
-            "ADMIN" => Role::ADMIN,
-            "MODERATOR" => Role::MODERATOR,
-            "USER" => Role::USER,
-            _ => panic!("Invalid role string: {}", s),
+impl TryFrom<&str> for Role {
+    type Error = DomainError;
+
+    fn try_from(role: &str) -> Result<Self, Self::Error> {
+        match role {
+            "ADMIN" => Ok(Role::ADMIN),
+            "MODERATOR" => Ok(Role::MODERATOR),
+            "USER" => Ok(Role::USER),
+            _ => Err(DomainError::UnknownRole(role.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for Role {
+    type Error = DomainError;
+
+    fn try_from(role: String) -> Result<Self, Self::Error> {
+        Role::try_from(role.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_round_trip_through_their_names() {
+        for role in Role::ALL {
+            assert_eq!(Role::try_from(role.as_string()), Ok(role.clone()));
         }
+        assert_eq!(
+            Role::try_from("admin"),
+            Err(DomainError::UnknownRole("admin".to_string()))
+        );
     }
 }
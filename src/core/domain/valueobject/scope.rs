@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// What an API key may do. Each protected route names the scope it needs, see
+/// `ROUTE_PERMISSIONS`; routes without one cannot be called with a key.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Scope {
+    #[serde(rename = "profile:read")]
+    ProfileRead,
+    #[serde(rename = "companies:read")]
+    CompaniesRead,
+    #[serde(rename = "companies:write")]
+    CompaniesWrite,
+    #[serde(rename = "admin:users:read")]
+    AdminUsersRead,
+    #[serde(rename = "admin:users:write")]
+    AdminUsersWrite,
+    #[serde(rename = "admin:companies:write")]
+    AdminCompaniesWrite,
+    #[serde(rename = "admin:keys")]
+    AdminKeys,
+    #[serde(rename = "admin:audit:read")]
+    AdminAuditRead,
+}
+
+impl Scope {
+    pub const ALL: &'static [Scope] = &[
+        Scope::ProfileRead,
+        Scope::CompaniesRead,
+        Scope::CompaniesWrite,
+        Scope::AdminUsersRead,
+        Scope::AdminUsersWrite,
+        Scope::AdminCompaniesWrite,
+        Scope::AdminKeys,
+        Scope::AdminAuditRead,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::ProfileRead => "profile:read",
+            Scope::CompaniesRead => "companies:read",
+            Scope::CompaniesWrite => "companies:write",
+            Scope::AdminUsersRead => "admin:users:read",
+            Scope::AdminUsersWrite => "admin:users:write",
+            Scope::AdminCompaniesWrite => "admin:companies:write",
+            Scope::AdminKeys => "admin:keys",
+            Scope::AdminAuditRead => "admin:audit:read",
+        }
+    }
+
+    pub fn parse(scope: &str) -> Option<Self> {
+        Scope::ALL.iter().find(|s| s.as_str() == scope).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_round_trip_through_their_names() {
+        for scope in Scope::ALL {
+            let json = serde_json::to_string(scope).unwrap();
+
+            assert_eq!(json, format!("\"{}\"", scope.as_str()));
+            assert_eq!(Scope::parse(scope.as_str()), Some(*scope));
+        }
+        assert_eq!(Scope::parse("admin"), None);
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::core::domain::error::DomainError;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Sector {
     Digital,
@@ -23,17 +25,21 @@ impl Sector {
             Sector::Music => "Music".to_owned(),
         }
     }
+}
+
+impl TryFrom<&str> for Sector {
+    type Error = DomainError;
 
-    pub fn from_string(domain: &str) -> Option<Self> {
-        match domain {
-            "Digital" => Some(Sector::Digital),
-            "Marketing" => Some(Sector::Marketing),
-            "Advertisement" => Some(Sector::Advertisement),
-            "Software" => Some(Sector::Software),
-            "AI" => Some(Sector::AI),
-            "Business" => Some(Sector::Business),
-            "Music" => Some(Sector::Music),
-            _ => None,
+    fn try_from(sector: &str) -> Result<Self, Self::Error> {
+        match sector {
+            "Digital" => Ok(Sector::Digital),
+            "Marketing" => Ok(Sector::Marketing),
+            "Advertisement" => Ok(Sector::Advertisement),
+            "Software" => Ok(Sector::Software),
+            "AI" => Ok(Sector::AI),
+            "Business" => Ok(Sector::Business),
+            "Music" => Ok(Sector::Music),
+            _ => Err(DomainError::UnknownSector(sector.to_string())),
         }
     }
 }
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+
+use crate::core::application::usecase::admin::error::AdminError;
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::port::page::{Page, PageRequest};
+use crate::core::port::user::{UserFilter, UserSort};
+
+/// A page of users, and how many users match the filter in all.
+#[derive(Debug, Clone)]
+pub struct UserPage {
+    pub users: Page<User>,
+    pub total: i64,
+}
+
+#[async_trait]
+pub trait AdminManagement: Send + Sync {
+    async fn list_users(
+        &self,
+        filter: &UserFilter,
+        page: &PageRequest<UserSort>,
+    ) -> Result<UserPage, AdminError>;
+    async fn get_user(&self, id: UserId) -> Result<User, AdminError>;
+    /// Applies only if the user is still at `version`, when given. `actor`
+    /// cannot demote themselves, and the last admin cannot be demoted.
+    async fn change_role(
+        &self,
+        actor: UserId,
+        id: UserId,
+        role: Role,
+        version: Option<i32>,
+    ) -> Result<User, AdminError>;
+    /// Soft-deletes the user, who can no longer log in until restored.
+    /// `actor` cannot delete themselves, and the last admin cannot be deleted.
+    async fn delete_user(&self, actor: UserId, id: UserId) -> Result<(), AdminError>;
+    /// Brings back a deleted user that was not purged yet.
+    async fn restore_user(&self, id: UserId) -> Result<User, AdminError>;
+    async fn force_password_reset(&self, id: UserId) -> Result<User, AdminError>;
+    /// Blocks the user, who can no longer log in and loses their sessions and
+    /// API keys. `actor` cannot block themselves.
+    async fn block_user(&self, actor: UserId, id: UserId) -> Result<User, AdminError>;
+    async fn unblock_user(&self, id: UserId) -> Result<User, AdminError>;
+    /// Lifts the login lockout of a user.
+    async fn clear_lockout(&self, id: UserId) -> Result<(), AdminError>;
+    /// Rolls the JWT signing key to `kid`, or to a newly generated key.
+    /// Returns the `kid` now signing tokens.
+    async fn rotate_signing_key(&self, kid: Option<String>) -> Result<String, AdminError>;
+}
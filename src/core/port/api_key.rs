@@ -0,0 +1,45 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::core::application::usecase::api_key::error::ApiKeyError;
+use crate::core::domain::entity::api_key::ApiKey;
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::scope::Scope;
+
+/// Input of a new API key.
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[async_trait]
+pub trait ApiKeyRepo: Send + Sync {
+    async fn save(&self, api_key: &ApiKey) -> Result<ApiKey, Error>;
+    async fn find_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, Error>;
+    /// Keys that are not revoked, newest first.
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<ApiKey>, Error>;
+    /// Returns whether a key of `user_id` was revoked.
+    async fn revoke(&self, user_id: UserId, id: Uuid) -> Result<bool, Error>;
+    async fn touch(&self, id: Uuid) -> Result<(), Error>;
+}
+
+#[async_trait]
+pub trait ApiKeyManagement: Send + Sync {
+    /// Creates a key for `user_id` and returns it with its plain text value.
+    /// `mfa` tells whether the creating session passed 2FA.
+    async fn create(
+        &self,
+        user_id: UserId,
+        mfa: bool,
+        input: NewApiKey,
+    ) -> Result<(ApiKey, String), ApiKeyError>;
+    async fn list(&self, user_id: UserId) -> Result<Vec<ApiKey>, ApiKeyError>;
+    async fn revoke(&self, user_id: UserId, id: Uuid) -> Result<(), ApiKeyError>;
+    /// Resolves a presented key to the key and its owner.
+    async fn authenticate(&self, key: &str) -> Result<(ApiKey, User), ApiKeyError>;
+}
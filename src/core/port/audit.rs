@@ -0,0 +1,86 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::application::usecase::audit::error::AuditError;
+use crate::core::domain::entity::audit::{AuditAction, AuditEntry};
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::page::{Page, PageRequest, SortKey, SortOrder};
+
+/// Criteria for querying the audit log. Every `None` field matches all entries.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub action: Option<AuditAction>,
+    pub actor_id: Option<UserId>,
+    pub target_id: Option<Uuid>,
+    /// Inclusive lower bound on `created_at`.
+    pub from: Option<Timestamp>,
+    /// Exclusive upper bound on `created_at`.
+    pub to: Option<Timestamp>,
+}
+
+/// Orders audit entries can be paged in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSort {
+    /// Newest first.
+    #[default]
+    CreatedAt,
+}
+
+impl AuditSort {
+    /// The value of `entry` for this key, and its id, for page cursors.
+    pub fn cursor_key(&self, entry: &AuditEntry) -> (String, Uuid) {
+        let key = match self {
+            AuditSort::CreatedAt => entry.created_at.to_rfc3339(),
+        };
+
+        (key, entry.id)
+    }
+}
+
+impl SortKey for AuditSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditSort::CreatedAt => "created_at",
+        }
+    }
+
+    fn order(&self) -> SortOrder {
+        match self {
+            AuditSort::CreatedAt => SortOrder::NewestFirst,
+        }
+    }
+}
+
+/// Entries of an export, and whether more matched than it holds.
+#[derive(Debug, Clone)]
+pub struct AuditExport {
+    pub entries: Vec<AuditEntry>,
+    pub truncated: bool,
+}
+
+#[async_trait]
+pub trait AuditRepo: Send + Sync {
+    async fn append(&self, entry: &AuditEntry) -> Result<(), Error>;
+    async fn find_by_filter(
+        &self,
+        filter: &AuditFilter,
+        page: &PageRequest<AuditSort>,
+    ) -> Result<Page<AuditEntry>, Error>;
+}
+
+#[async_trait]
+pub trait AuditLog: Send + Sync {
+    async fn record(&self, entry: AuditEntry) -> Result<(), AuditError>;
+    async fn search(
+        &self,
+        filter: &AuditFilter,
+        page: &PageRequest<AuditSort>,
+    ) -> Result<Page<AuditEntry>, AuditError>;
+    /// Entries matching `filter` for export, newest first and capped at
+    /// `MAX_EXPORT_ROWS`.
+    async fn export(&self, filter: &AuditFilter) -> Result<AuditExport, AuditError>;
+}
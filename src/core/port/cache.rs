@@ -0,0 +1,43 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// Values by key, each forgotten `ttl_sec` after it was stored.
+#[async_trait]
+pub trait CacheRepo: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>, Error>;
+    async fn set(&self, key: &str, value: String, ttl_sec: u64) -> Result<(), Error>;
+    async fn remove(&self, key: &str) -> Result<(), Error>;
+}
+
+/// Hits and misses of a cache since startup.
+#[derive(Debug, Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheCounters {
+    pub fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
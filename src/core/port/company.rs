@@ -1,21 +1,124 @@
 use anyhow::Error;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::core::application::usecase::company::error::CompanyError;
 use crate::core::domain::entity::company::Company;
+use crate::core::domain::entity::employment::Employment;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::id::CompanyId;
+use crate::core::domain::valueobject::sector::Sector;
+use crate::core::port::employment::EmploymentSort;
+use crate::core::port::page::{Page, PageRequest, SortKey, SortOrder};
+use crate::shared::ctx::ctx::Ctx;
+
+/// Input of a new company.
+#[derive(Debug, Clone)]
+pub struct NewCompany {
+    pub name: String,
+    pub foundation_date: i16,
+    pub description: String,
+    pub url: String,
+    pub sector: Sector,
+}
+
+/// Changes to a company. `None` fields are left as they are.
+#[derive(Debug, Clone, Default)]
+pub struct CompanyUpdate {
+    pub name: Option<String>,
+    pub foundation_date: Option<i16>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub sector: Option<Sector>,
+    /// The version the changes were based on. When set, they only apply if
+    /// the company is still at that version.
+    pub version: Option<i32>,
+}
+
+/// Orders companies can be paged in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompanySort {
+    /// Newest first.
+    #[default]
+    CreatedAt,
+    Name,
+}
+
+impl CompanySort {
+    /// The value of `company` for this key, and its id, for page cursors.
+    pub fn cursor_key(&self, company: &Company) -> (String, Uuid) {
+        let key = match self {
+            CompanySort::CreatedAt => company.created_at.to_rfc3339(),
+            CompanySort::Name => company.name.clone(),
+        };
+
+        (key, company.id.map(Uuid::from).unwrap_or_default())
+    }
+}
+
+impl SortKey for CompanySort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CompanySort::CreatedAt => "created_at",
+            CompanySort::Name => "name",
+        }
+    }
+
+    fn order(&self) -> SortOrder {
+        match self {
+            CompanySort::CreatedAt => SortOrder::NewestFirst,
+            CompanySort::Name => SortOrder::Alphabetical,
+        }
+    }
+}
 
 #[async_trait]
 pub trait CompanyRepo: Send + Sync {
-    async fn save(&self, entity: &Company) -> Result<Uuid, Error>;
-    async fn update(&self, id_str: &str, entity: &Company) -> Result<Company, Error>;
-    async fn delete(&self, id_str: &str) -> Result<(), Error>;
+    async fn save(&self, entity: &Company) -> Result<CompanyId, Error>;
+    async fn update(&self, id: CompanyId, entity: &Company) -> Result<Company, Error>;
+    /// Soft-deletes the company: it and its employments are hidden from
+    /// every query until it is restored or purged. Deleting twice is a no-op.
+    async fn delete(&self, id: CompanyId) -> Result<(), Error>;
+    /// Undoes `delete`. `None` when there is no deleted company with `id`.
+    async fn restore(&self, id: CompanyId) -> Result<Option<Company>, Error>;
+    /// Removes the companies deleted before `before` for good, with their
+    /// employments. Returns how many were removed.
+    async fn purge_deleted(&self, before: &Timestamp) -> Result<u64, Error>;
     async fn find_all(&self) -> Result<Vec<Company>, Error>;
-    async fn find_by_id(&self, id_str: &str) -> Result<Option<Company>, Error>;
+    async fn find_by_id(&self, id: CompanyId) -> Result<Option<Company>, Error>;
     async fn find_by_name(&self, name: &str) -> Result<Option<Company>, Error>;
+    async fn find_page(&self, page: &PageRequest<CompanySort>) -> Result<Page<Company>, Error>;
 }
 
-// #[async_trait]
-// pub trait CompanyManagement: Send + Sync {
-//     async fn register(&self, input: &None) -> Result<None, Error>;
-//     async fn get_profile(&self, input: &None) -> Result<None, Error>;
-// }
+#[async_trait]
+pub trait CompanyManagement: Send + Sync {
+    /// Registers a company with the user of `ctx` as its CEO.
+    async fn register(&self, ctx: &Ctx, input: NewCompany) -> Result<Company, CompanyError>;
+    async fn list(
+        &self,
+        ctx: &Ctx,
+        page: &PageRequest<CompanySort>,
+    ) -> Result<Page<Company>, CompanyError>;
+    async fn get_profile(&self, ctx: &Ctx, id: CompanyId) -> Result<Company, CompanyError>;
+    /// The employments of the company, by users that are not deleted.
+    async fn list_employees(
+        &self,
+        ctx: &Ctx,
+        id: CompanyId,
+        page: &PageRequest<EmploymentSort>,
+    ) -> Result<Page<Employment>, CompanyError>;
+    /// Only the CEO of the company, or the root context, may edit it.
+    async fn update(
+        &self,
+        ctx: &Ctx,
+        id: CompanyId,
+        input: CompanyUpdate,
+    ) -> Result<Company, CompanyError>;
+    /// Soft-deletes the company. Only its CEO, or the root context, may.
+    async fn delete(&self, ctx: &Ctx, id: CompanyId) -> Result<(), CompanyError>;
+    /// Brings back a deleted company that was not purged yet. Only
+    /// administrators, or the root context, may.
+    async fn restore(&self, ctx: &Ctx, id: CompanyId) -> Result<Company, CompanyError>;
+}
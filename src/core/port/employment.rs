@@ -0,0 +1,66 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::domain::entity::employment::Employment;
+use crate::core::domain::valueobject::id::{CompanyId, EmploymentId, UserId};
+use crate::core::domain::valueobject::position::Position;
+use crate::core::port::page::{Page, PageRequest, SortKey, SortOrder};
+
+/// Orders employments can be paged in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmploymentSort {
+    /// Newest first.
+    #[default]
+    CreatedAt,
+}
+
+impl EmploymentSort {
+    /// The value of `employment` for this key, and its id, for page cursors.
+    pub fn cursor_key(&self, employment: &Employment) -> (String, Uuid) {
+        let key = match self {
+            EmploymentSort::CreatedAt => employment.created_at.to_rfc3339(),
+        };
+
+        (key, Uuid::from(employment.id))
+    }
+}
+
+impl SortKey for EmploymentSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            EmploymentSort::CreatedAt => "created_at",
+        }
+    }
+
+    fn order(&self) -> SortOrder {
+        match self {
+            EmploymentSort::CreatedAt => SortOrder::NewestFirst,
+        }
+    }
+}
+
+#[async_trait]
+pub trait EmploymentRepo: Send + Sync {
+    async fn save(
+        &self,
+        user_id: UserId,
+        company_id: CompanyId,
+        position: &Position,
+    ) -> Result<EmploymentId, Error>;
+    /// Position of `user_id` in `company_id`, if employed there.
+    async fn find_position(
+        &self,
+        user_id: UserId,
+        company_id: CompanyId,
+    ) -> Result<Option<Position>, Error>;
+    /// Employments in `company_id`, hidden like `find_position` while the
+    /// user or the company is deleted.
+    async fn find_by_company(
+        &self,
+        company_id: CompanyId,
+        page: &PageRequest<EmploymentSort>,
+    ) -> Result<Page<Employment>, Error>;
+}
@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+/// An update based on a stale read: the entity was changed since it was read
+/// at `expected` version. Repositories fail updates with it rather than
+/// overwrite the other change.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{entity} {id} is no longer at version {expected}")]
+pub struct VersionConflict {
+    pub entity: &'static str,
+    pub id: String,
+    pub expected: i32,
+}
+
+impl VersionConflict {
+    pub fn new(entity: &'static str, id: impl ToString, expected: i32) -> Self {
+        VersionConflict {
+            entity,
+            id: id.to_string(),
+            expected,
+        }
+    }
+}
+
+/// A save or update that would give an entity a unique value, e.g. an email,
+/// that another one already holds. Deleted entities keep theirs until purged.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{entity} {field} is already taken")]
+pub struct AlreadyTaken {
+    pub entity: &'static str,
+    pub field: &'static str,
+}
+
+impl AlreadyTaken {
+    pub fn new(entity: &'static str, field: &'static str) -> Self {
+        AlreadyTaken { entity, field }
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::core::application::usecase::identity::error::IdentityError;
+use crate::core::domain::entity::identity::{AuthorizationRequest, UserIdentity};
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::port::user::LoginSuccess;
+
+/// Identity asserted by a provider in a verified ID token.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+}
+
+/// Where the provider login of an [`AuthorizationRequest`] starts.
+#[derive(Debug, Clone)]
+pub struct AuthorizationStart {
+    pub url: String,
+    pub state: String,
+    /// Seconds the request stays valid.
+    pub expires_in: u64,
+}
+
+/// Provider login of a user with 2FA enabled, completed by sending a code
+/// along with the ticket.
+#[derive(Debug, Clone)]
+pub struct SecondFactorChallenge {
+    pub ticket: String,
+    /// Seconds the ticket stays valid.
+    pub expires_in: u64,
+}
+
+/// Result of a provider callback.
+#[derive(Debug, Clone)]
+pub enum CallbackOutcome {
+    LoggedIn(LoginSuccess),
+    SecondFactorRequired(SecondFactorChallenge),
+    Linked(UserIdentity),
+}
+
+/// Client for the configured `OpenID Connect` providers.
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    fn supports(&self, provider: &str) -> bool;
+    /// Seconds a started login of `provider` stays valid.
+    fn timeout(&self, provider: &str) -> u64;
+    async fn authorization_url(&self, request: &AuthorizationRequest) -> Result<String, Error>;
+    /// Redeems `code` with the request's PKCE verifier and returns the
+    /// identity of the verified ID token.
+    async fn exchange_code(
+        &self,
+        request: &AuthorizationRequest,
+        code: &str,
+    ) -> Result<ProviderIdentity, Error>;
+}
+
+/// Started logins, by state. Each one can be taken once.
+#[async_trait]
+pub trait AuthorizationRepo: Send + Sync {
+    async fn put(&self, request: &AuthorizationRequest, ttl_sec: u64) -> Result<(), Error>;
+    async fn take(&self, state: &str) -> Result<Option<AuthorizationRequest>, Error>;
+}
+
+#[async_trait]
+pub trait IdentityRepo: Send + Sync {
+    async fn find(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, Error>;
+    async fn find_by_user(&self, user_id: UserId) -> Result<Vec<UserIdentity>, Error>;
+    async fn save(&self, identity: &UserIdentity) -> Result<UserIdentity, Error>;
+    /// Returns whether an identity was removed.
+    async fn delete(&self, user_id: UserId, provider: &str) -> Result<bool, Error>;
+}
+
+#[async_trait]
+pub trait IdentityManagement: Send + Sync {
+    /// Starts a provider login, or a link to `link_user_id` when set.
+    async fn authorize(
+        &self,
+        provider: &str,
+        link_user_id: Option<UserId>,
+    ) -> Result<AuthorizationStart, IdentityError>;
+    async fn callback(
+        &self,
+        provider: &str,
+        state: &str,
+        code: &str,
+    ) -> Result<CallbackOutcome, IdentityError>;
+    /// Takes the pending login of a [`SecondFactorChallenge`]. Each ticket
+    /// can be taken once, whether the code turns out valid or not.
+    async fn take_second_factor(&self, provider: &str, ticket: &str)
+        -> Result<User, IdentityError>;
+    async fn list(&self, user_id: UserId) -> Result<Vec<UserIdentity>, IdentityError>;
+    async fn unlink(&self, user_id: UserId, provider: &str) -> Result<(), IdentityError>;
+}
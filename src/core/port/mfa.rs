@@ -0,0 +1,35 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::core::application::usecase::mfa::error::MfaError;
+use crate::core::domain::entity::mfa::{UsedCode, UserMfa};
+use crate::core::domain::valueobject::id::UserId;
+
+/// TOTP secret of a pending enrollment, to be added to an authenticator app.
+#[derive(Debug, Clone)]
+pub struct TotpEnrollment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[async_trait]
+pub trait MfaRepo: Send + Sync {
+    async fn find_by_user(&self, user_id: UserId) -> Result<Option<UserMfa>, Error>;
+    /// Inserts or replaces the second factor of `mfa.user_id`.
+    async fn save(&self, mfa: &UserMfa) -> Result<UserMfa, Error>;
+    /// Records a code accepted at login, unless it was used in the meantime:
+    /// a TOTP step no later than the last recorded one, or a recovery code no
+    /// longer left. Returns whether it was recorded, so concurrent logins
+    /// with the same code cannot both succeed.
+    async fn record_use(&self, user_id: UserId, code: &UsedCode) -> Result<bool, Error>;
+}
+
+#[async_trait]
+pub trait MfaManagement: Send + Sync {
+    /// Starts a TOTP enrollment, replacing any unconfirmed one. `email` labels
+    /// the account in the authenticator app.
+    async fn enroll_totp(&self, user_id: UserId, email: &str) -> Result<TotpEnrollment, MfaError>;
+    /// Enables the enrolled TOTP once `code` proves the app has the secret.
+    /// Returns the recovery codes.
+    async fn confirm_totp(&self, user_id: UserId, code: &str) -> Result<Vec<String>, MfaError>;
+}
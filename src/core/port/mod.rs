@@ -1,2 +1,15 @@
+pub mod admin;
+pub mod api_key;
+pub mod audit;
+pub mod cache;
 pub mod company;
+pub mod employment;
+pub mod error;
+pub mod identity;
+pub mod mfa;
+pub mod page;
+pub mod purge;
+pub mod session;
+pub mod throttle;
+pub mod unit_of_work;
 pub mod user;
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::shared::data::base64::{b64u_decode, b64u_encode};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// How a sort key orders its values. Ties are broken by id, in the same
+/// direction, so every order is total and a page can resume after any row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Timestamps, newest first. Cursor keys are RFC 3339.
+    NewestFirst,
+    /// Text, alphabetically by bytes (the `"C"` collation), so every store
+    /// orders it alike whatever its locale.
+    Alphabetical,
+}
+
+/// A field a list can be paged by.
+pub trait SortKey: Copy + Send + Sync {
+    /// Name of the key in query strings and cursors.
+    fn as_str(&self) -> &'static str;
+    fn order(&self) -> SortOrder;
+}
+
+/// A cursor that was not issued for the requested sort key, or not issued at
+/// all.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid page cursor")]
+pub struct InvalidCursor;
+
+/// Position after the last row of a page: the sort key value and id of that
+/// row. Clients get it as an opaque string and pass it back unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Cursor {
+    #[serde(rename = "s")]
+    sort: String,
+    #[serde(rename = "k")]
+    pub key: String,
+    #[serde(rename = "i")]
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new<S: SortKey>(sort: S, key: impl Into<String>, id: Uuid) -> Self {
+        Cursor {
+            sort: sort.as_str().to_string(),
+            key: key.into(),
+            id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        b64u_encode(serde_json::to_vec(self).expect("a cursor serializes"))
+    }
+
+    /// Reads a cursor of `encode`, checking it was issued for `sort`.
+    pub fn decode<S: SortKey>(cursor: &str, sort: S) -> Result<Self, InvalidCursor> {
+        let cursor: Cursor = b64u_decode(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(&json).ok())
+            .ok_or(InvalidCursor)?;
+        if cursor.sort != sort.as_str() {
+            return Err(InvalidCursor);
+        }
+        if sort.order() == SortOrder::NewestFirst && cursor.timestamp().is_none() {
+            return Err(InvalidCursor);
+        }
+
+        Ok(cursor)
+    }
+
+    /// The key of a cursor for a `NewestFirst` sort.
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        Timestamp::parse_rfc3339(&self.key).ok()
+    }
+}
+
+/// Up to `limit` rows ordered by `sort`, starting after `cursor`, or from the
+/// first row without one.
+#[derive(Debug, Clone)]
+pub struct PageRequest<S> {
+    pub limit: i64,
+    pub cursor: Option<Cursor>,
+    pub sort: S,
+}
+
+impl<S: SortKey> PageRequest<S> {
+    /// Clamps `limit` to `1..=MAX_PAGE_SIZE`, defaulting to
+    /// `DEFAULT_PAGE_SIZE`.
+    pub fn new(limit: Option<i64>, cursor: Option<&str>, sort: S) -> Result<Self, InvalidCursor> {
+        Ok(PageRequest {
+            limit: limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE),
+            cursor: cursor
+                .map(|cursor| Cursor::decode(cursor, sort))
+                .transpose()?,
+            sort,
+        })
+    }
+
+    /// Rows repositories fetch: one more than `limit`, to tell whether
+    /// another page follows.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+}
+
+/// A page of rows, and the cursor of the next page when there is one.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub limit: i64,
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    /// The page of `rows`, fetched with `fetch_limit`. `key` gives the sort
+    /// key value and id of a row, for the cursor of the next page.
+    pub fn from_rows<S: SortKey>(
+        mut rows: Vec<T>,
+        request: &PageRequest<S>,
+        key: impl Fn(&T) -> (String, Uuid),
+    ) -> Self {
+        let limit = usize::try_from(request.limit).unwrap_or(usize::MAX);
+        let next_cursor = if rows.len() > limit {
+            rows.truncate(limit);
+            rows.last().map(|last| {
+                let (value, id) = key(last);
+                Cursor::new(request.sort, value, id)
+            })
+        } else {
+            None
+        };
+
+        Page {
+            items: rows,
+            limit: request.limit,
+            next_cursor,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            limit: self.limit,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy)]
+    enum TestSort {
+        CreatedAt,
+        Name,
+    }
+
+    impl SortKey for TestSort {
+        fn as_str(&self) -> &'static str {
+            match self {
+                TestSort::CreatedAt => "created_at",
+                TestSort::Name => "name",
+            }
+        }
+
+        fn order(&self) -> SortOrder {
+            match self {
+                TestSort::CreatedAt => SortOrder::NewestFirst,
+                TestSort::Name => SortOrder::Alphabetical,
+            }
+        }
+    }
+
+    #[test]
+    fn cursors_only_resume_the_sort_they_were_issued_for() {
+        let cursor = Cursor::new(TestSort::Name, "Acme", Uuid::new_v4());
+        let encoded = cursor.encode();
+
+        assert_eq!(Cursor::decode(&encoded, TestSort::Name), Ok(cursor));
+        assert_eq!(
+            Cursor::decode(&encoded, TestSort::CreatedAt),
+            Err(InvalidCursor)
+        );
+        assert_eq!(
+            Cursor::decode("not a cursor", TestSort::Name),
+            Err(InvalidCursor)
+        );
+
+        let forged = Cursor::new(TestSort::CreatedAt, "yesterday", Uuid::new_v4());
+        assert_eq!(
+            Cursor::decode(&forged.encode(), TestSort::CreatedAt),
+            Err(InvalidCursor)
+        );
+    }
+
+    #[test]
+    fn pages_hold_up_to_limit_rows_and_point_past_the_last() {
+        let request = PageRequest::new(Some(2), None, TestSort::Name).unwrap();
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let key = |id: &Uuid| (id.to_string(), *id);
+
+        let page = Page::from_rows(ids.clone(), &request, key);
+        assert_eq!(page.items, ids[..2]);
+        assert_eq!(page.next_cursor.map(|cursor| cursor.id), Some(ids[1]));
+
+        let last = Page::from_rows(ids[..2].to_vec(), &request, key);
+        assert_eq!(last.items.len(), 2);
+        assert!(last.next_cursor.is_none());
+
+        assert_eq!(
+            PageRequest::new(Some(0), None, TestSort::Name)
+                .unwrap()
+                .limit,
+            1
+        );
+        assert_eq!(
+            PageRequest::new(None, None, TestSort::Name).unwrap().limit,
+            DEFAULT_PAGE_SIZE
+        );
+    }
+}
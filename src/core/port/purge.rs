@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+
+use crate::core::application::usecase::purge::error::PurgeError;
+use crate::shared::ctx::ctx::Ctx;
+
+/// Rows removed by one purge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PurgeReport {
+    pub users: u64,
+    pub companies: u64,
+}
+
+#[async_trait]
+pub trait DeletedPurging: Send + Sync {
+    /// Removes, for good, the users and companies deleted longer ago than the
+    /// retention period. Only the root context may purge.
+    async fn purge(&self, ctx: &Ctx) -> Result<PurgeReport, PurgeError>;
+}
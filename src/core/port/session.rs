@@ -0,0 +1,57 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::core::application::usecase::session::error::SessionError;
+use crate::core::domain::entity::session::Session;
+use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::id::UserId;
+
+/// Device metadata recorded with a session.
+#[derive(Debug, Clone, Default)]
+pub struct SessionMeta {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+/// Access token and refresh token issued for a session.
+#[derive(Debug, Clone)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub session: Session,
+}
+
+#[async_trait]
+pub trait SessionRepo: Send + Sync {
+    async fn save(&self, session: &Session) -> Result<Session, Error>;
+    /// Stores a rotated session, unless its refresh token changed since it was
+    /// read as `previous_hash`. Returns whether the session was updated.
+    async fn rotate(&self, session: &Session, previous_hash: &str) -> Result<bool, Error>;
+    async fn revoke(&self, id: Uuid) -> Result<(), Error>;
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Session>, Error>;
+    /// Sessions that are neither revoked nor expired, most recently used first.
+    async fn find_active_by_user(&self, user_id: UserId) -> Result<Vec<Session>, Error>;
+}
+
+#[async_trait]
+pub trait SessionManagement: Send + Sync {
+    /// Starts a session after a successful login; `mfa` records whether the
+    /// login passed a second factor.
+    async fn start(
+        &self,
+        user: &User,
+        mfa: bool,
+        meta: &SessionMeta,
+    ) -> Result<TokenPair, SessionError>;
+    async fn refresh(
+        &self,
+        refresh_token: &str,
+        meta: &SessionMeta,
+    ) -> Result<TokenPair, SessionError>;
+    async fn list(&self, user_id: UserId) -> Result<Vec<Session>, SessionError>;
+    /// Whether the session exists, is neither revoked nor expired and its
+    /// user is not blocked, so the access tokens issued for it are accepted.
+    async fn is_active(&self, session_id: Uuid) -> Result<bool, SessionError>;
+    async fn revoke(&self, user_id: UserId, session_id: Uuid) -> Result<(), SessionError>;
+}
@@ -0,0 +1,27 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::core::application::usecase::auth::error::LoginError;
+
+/// Failure counters and locks, by key.
+#[async_trait]
+pub trait AttemptRepo: Send + Sync {
+    /// Counts a failure for `key` and returns the number of failures so far.
+    /// The counter is forgotten `ttl_sec` after the last failure.
+    async fn increment(&self, key: &str, ttl_sec: u64) -> Result<u32, Error>;
+    async fn lock(&self, key: &str, duration_sec: u64) -> Result<(), Error>;
+    /// Seconds left on the lock of `key`, if it is locked.
+    async fn lock_remaining(&self, key: &str) -> Result<Option<u64>, Error>;
+    /// Forgets the failures and lock of `key`.
+    async fn clear(&self, key: &str) -> Result<(), Error>;
+}
+
+#[async_trait]
+pub trait LoginThrottling: Send + Sync {
+    /// Refuses the attempt while the account or the IP is locked.
+    async fn check(&self, email: &str, ip: Option<&str>) -> Result<(), LoginError>;
+    async fn record_failure(&self, email: &str, ip: Option<&str>) -> Result<(), LoginError>;
+    async fn record_success(&self, email: &str) -> Result<(), LoginError>;
+    /// Lifts the lockout of an account.
+    async fn clear(&self, email: &str) -> Result<(), LoginError>;
+}
@@ -0,0 +1,21 @@
+use anyhow::Error;
+use async_trait::async_trait;
+
+use crate::core::port::company::CompanyRepo;
+use crate::core::port::employment::EmploymentRepo;
+
+/// Repositories bound to one transaction. Their writes become visible to
+/// others together, on `commit`; dropping the unit without committing rolls
+/// them back.
+#[async_trait]
+pub trait UnitOfWork: Send + Sync {
+    fn companies(&self) -> &dyn CompanyRepo;
+    fn employments(&self) -> &dyn EmploymentRepo;
+    async fn commit(self: Box<Self>) -> Result<(), Error>;
+    async fn rollback(self: Box<Self>) -> Result<(), Error>;
+}
+
+#[async_trait]
+pub trait UnitOfWorkFactory: Send + Sync {
+    async fn begin(&self) -> Result<Box<dyn UnitOfWork>, Error>;
+}
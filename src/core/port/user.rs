@@ -1,29 +1,131 @@
 use anyhow::Error;
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::ValidationErrors;
 
 use crate::adapter::driving::presentation::http::handler::auth::login::UserLoginRequest;
+use crate::adapter::driving::presentation::http::handler::auth::password::ChangePasswordRequest;
 use crate::adapter::driving::presentation::http::handler::auth::register::UserRegisterRequest;
-use crate::core::application::usecase::auth::error::{LoginError, MeError, RegisterError};
+use crate::core::application::usecase::auth::error::{
+    LoginError, MeError, PasswordError, RegisterError,
+};
 use crate::core::domain::entity::user::User;
+use crate::core::domain::valueobject::date::Timestamp;
+use crate::core::domain::valueobject::email::Email;
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::role::Role;
+use crate::core::port::page::{Page, PageRequest, SortKey, SortOrder};
+use crate::shared::ctx::ctx::Ctx;
+
+/// Criteria for listing users. Every `None` field matches all users.
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    pub role: Option<Role>,
+    pub verified: Option<bool>,
+    pub blocked: Option<bool>,
+    /// Inclusive lower bound on `created_at`.
+    pub created_from: Option<Timestamp>,
+    /// Exclusive upper bound on `created_at`.
+    pub created_to: Option<Timestamp>,
+}
+
+/// Orders users can be paged in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSort {
+    /// Newest first.
+    #[default]
+    CreatedAt,
+    Email,
+}
+
+impl UserSort {
+    /// The value of `user` for this key, and its id, for page cursors.
+    pub fn cursor_key(&self, user: &User) -> (String, Uuid) {
+        let key = match self {
+            UserSort::CreatedAt => user.created_at.to_rfc3339(),
+            UserSort::Email => user.email.to_string(),
+        };
+
+        (key, user.id.map(Uuid::from).unwrap_or_default())
+    }
+}
+
+impl SortKey for UserSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserSort::CreatedAt => "created_at",
+            UserSort::Email => "email",
+        }
+    }
+
+    fn order(&self) -> SortOrder {
+        match self {
+            UserSort::CreatedAt => SortOrder::NewestFirst,
+            UserSort::Email => SortOrder::Alphabetical,
+        }
+    }
+}
+
+/// A user who passed login, and whether a second factor was verified.
+#[derive(Debug, Clone)]
+pub struct LoginSuccess {
+    pub user: User,
+    pub mfa: bool,
+}
 
 #[async_trait]
 pub trait UserRepo: Send + Sync {
     async fn save(&self, entity: &User) -> Result<User, Error>;
-    async fn update(&self, id_str: &str, entity: &User) -> Result<User, Error>;
-    async fn delete(&self, id_str: &str) -> Result<(), Error>;
+    /// Writes `entity` if the stored user is still at its version. Tokens
+    /// and timestamps left `None` keep their value, except `blocked_at`, which
+    /// is written as given so that users can be unblocked.
+    async fn update(&self, id: UserId, entity: &User) -> Result<User, Error>;
+    /// Soft-deletes the user: it and its employments are hidden from every
+    /// query until it is restored or purged. Deleting twice is a no-op.
+    async fn delete(&self, id: UserId) -> Result<(), Error>;
+    /// Undoes `delete`. `None` when there is no deleted user with `id`.
+    async fn restore(&self, id: UserId) -> Result<Option<User>, Error>;
+    /// Removes the users deleted before `before` for good, with everything
+    /// that belongs to them. Returns how many were removed.
+    async fn purge_deleted(&self, before: &Timestamp) -> Result<u64, Error>;
     async fn find_all(&self) -> Result<Vec<User>, Error>;
-    async fn find_by_id(&self, id_str: &str) -> Result<Option<User>, Error>;
-    async fn find_by_email(&self, email: &str) -> Result<Option<User>, Error>;
+    async fn find_by_id(&self, id: UserId) -> Result<Option<User>, Error>;
+    async fn find_by_email(&self, email: &Email) -> Result<Option<User>, Error>;
+    async fn find_by_filter(
+        &self,
+        filter: &UserFilter,
+        page: &PageRequest<UserSort>,
+    ) -> Result<Page<User>, Error>;
+    async fn count_by_filter(&self, filter: &UserFilter) -> Result<i64, Error>;
 }
 
+/// Registration and login establish who the caller is, so they run without a
+/// [`Ctx`]; everything else acts for the user of `ctx`.
 #[async_trait]
 pub trait UserManagement: Send + Sync {
     async fn register(
         &self,
         input: &UserRegisterRequest,
     ) -> Result<User, RegisterError<ValidationErrors>>;
-    async fn login(&self, input: &UserLoginRequest) -> Result<User, LoginError>;
-    async fn me(&self, email: &str) -> Result<User, MeError>;
+    /// Checks the password, then the TOTP or recovery code in `input.otp`
+    /// when the user has 2FA enabled.
+    async fn login(&self, input: &UserLoginRequest) -> Result<LoginSuccess, LoginError>;
+    /// Second login step. Returns whether a second factor was verified, which
+    /// is only the case for users with 2FA enabled.
+    async fn verify_second_factor(
+        &self,
+        user_id: UserId,
+        code: Option<&str>,
+    ) -> Result<bool, LoginError>;
+    async fn me(&self, ctx: &Ctx) -> Result<User, MeError>;
+    /// Replaces the password of the user of `ctx` once `input` proves the
+    /// current one.
+    async fn change_password(
+        &self,
+        ctx: &Ctx,
+        input: &ChangePasswordRequest,
+    ) -> Result<User, PasswordError<ValidationErrors>>;
     // async fn update_profile(&self, input: &UserRegisterRequest) -> Result<(), Error>;
 }
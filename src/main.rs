@@ -1,38 +1,259 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Error;
 use log::info;
+use matchmaker::adapter::driven::oauth::oidc::OidcClient;
+use matchmaker::adapter::driven::storage::cached::user::{cache_key, CachedUserRepository};
 use matchmaker::adapter::driven::storage::db::db_connection::DB;
+use matchmaker::adapter::driven::storage::db::repository::api_key::ApiKeyRepository;
+use matchmaker::adapter::driven::storage::db::repository::audit::AuditRepository;
+use matchmaker::adapter::driven::storage::db::repository::company::CompanyRepository;
+use matchmaker::adapter::driven::storage::db::repository::employment::EmploymentRepository;
+use matchmaker::adapter::driven::storage::db::repository::identity::IdentityRepository;
+use matchmaker::adapter::driven::storage::db::repository::mfa::MfaRepository;
+use matchmaker::adapter::driven::storage::db::repository::session::SessionRepository;
 use matchmaker::adapter::driven::storage::db::repository::user::UserRepository;
+use matchmaker::adapter::driven::storage::db::unit_of_work::PgUnitOfWorkFactory;
 use matchmaker::adapter::driven::storage::memory::redis_connection::connect_redis;
+use matchmaker::adapter::driven::storage::memory::repository as memory;
+use matchmaker::adapter::driven::storage::memory::repository::attempt::{
+    AttemptRepository, RedisAttemptRepository,
+};
+use matchmaker::adapter::driven::storage::memory::repository::authorization::{
+    AuthorizationRepository, RedisAuthorizationRepository,
+};
+use matchmaker::adapter::driven::storage::memory::repository::cache::{
+    CacheRepository, RedisCacheRepository,
+};
+use matchmaker::adapter::driven::storage::memory::store::MemStore;
+use matchmaker::adapter::driven::storage::memory::unit_of_work::MemUnitOfWorkFactory;
+use matchmaker::adapter::driving::job::purge::spawn_purge_job;
 use matchmaker::adapter::driving::presentation::http::router::{make_router, AppState};
 use matchmaker::adapter::driving::presentation::http::server::Server;
+use matchmaker::core::application::usecase::admin::service::AdminService;
+use matchmaker::core::application::usecase::api_key::service::ApiKeyService;
+use matchmaker::core::application::usecase::audit::service::AuditService;
 use matchmaker::core::application::usecase::auth::service::UserService;
+use matchmaker::core::application::usecase::auth::throttle::LoginThrottleService;
+use matchmaker::core::application::usecase::company::service::CompanyService;
+use matchmaker::core::application::usecase::identity::service::IdentityService;
+use matchmaker::core::application::usecase::mfa::service::MfaService;
+use matchmaker::core::application::usecase::purge::service::PurgeService;
+use matchmaker::core::application::usecase::session::service::SessionService;
+use matchmaker::core::port::api_key::ApiKeyRepo;
+use matchmaker::core::port::audit::AuditRepo;
+use matchmaker::core::port::cache::CacheRepo;
+use matchmaker::core::port::company::{CompanyManagement, CompanyRepo};
+use matchmaker::core::port::identity::{IdentityManagement, IdentityRepo};
+use matchmaker::core::port::mfa::MfaRepo;
+use matchmaker::core::port::session::SessionRepo;
+use matchmaker::core::port::throttle::LoginThrottling;
+use matchmaker::core::port::user::UserRepo;
+use matchmaker::shared::config::config::{Config, OAuth2, Storage};
 use matchmaker::shared::config::environment::Environment;
 use matchmaker::shared::logger::logger;
 use matchmaker::shared::worker::mailer::email_sender::EmailSender;
 use matchmaker::shared::worker::service::TaskContext;
 
+/// Repositories of one storage backend.
+struct Repositories<U, C, S, M, I, K, L> {
+    user: Arc<U>,
+    company: Arc<C>,
+    session: Arc<S>,
+    mfa: Arc<M>,
+    identity: Arc<I>,
+    api_key: Arc<K>,
+    audit: Arc<L>,
+    company_service: Arc<dyn CompanyManagement>,
+}
+
+impl
+    Repositories<
+        UserRepository,
+        CompanyRepository,
+        SessionRepository,
+        MfaRepository,
+        IdentityRepository,
+        ApiKeyRepository,
+        AuditRepository,
+    >
+{
+    fn postgres(db: &DB) -> Self {
+        let company = Arc::new(CompanyRepository::new(Arc::clone(&db.pool)));
+
+        Repositories {
+            user: Arc::new(UserRepository::new(Arc::clone(&db.pool))),
+            company: Arc::clone(&company),
+            session: Arc::new(SessionRepository::new(Arc::clone(&db.pool))),
+            mfa: Arc::new(MfaRepository::new(Arc::clone(&db.pool))),
+            identity: Arc::new(IdentityRepository::new(Arc::clone(&db.pool))),
+            api_key: Arc::new(ApiKeyRepository::new(Arc::clone(&db.pool))),
+            audit: Arc::new(AuditRepository::new(Arc::clone(&db.pool))),
+            company_service: Arc::new(CompanyService::new(
+                company,
+                Arc::new(EmploymentRepository::new(Arc::clone(&db.pool))),
+                Arc::new(PgUnitOfWorkFactory::new(Arc::clone(&db.pool))),
+            )),
+        }
+    }
+}
+
+impl
+    Repositories<
+        memory::user::UserRepository,
+        memory::company::CompanyRepository,
+        memory::session::SessionRepository,
+        memory::mfa::MfaRepository,
+        memory::identity::IdentityRepository,
+        memory::api_key::ApiKeyRepository,
+        memory::audit::AuditRepository,
+    >
+{
+    fn memory() -> Self {
+        let store = Arc::new(MemStore::new());
+        let company = Arc::new(memory::company::CompanyRepository::new(Arc::clone(&store)));
+
+        Repositories {
+            user: Arc::new(memory::user::UserRepository::new(Arc::clone(&store))),
+            company: Arc::clone(&company),
+            session: Arc::new(memory::session::SessionRepository::new()),
+            mfa: Arc::new(memory::mfa::MfaRepository::new()),
+            identity: Arc::new(memory::identity::IdentityRepository::new()),
+            api_key: Arc::new(memory::api_key::ApiKeyRepository::new()),
+            audit: Arc::new(memory::audit::AuditRepository::new()),
+            company_service: Arc::new(CompanyService::new(
+                company,
+                Arc::new(memory::employment::EmploymentRepository::new(Arc::clone(
+                    &store,
+                ))),
+                Arc::new(MemUnitOfWorkFactory::new(store)),
+            )),
+        }
+    }
+}
+
 //todo: mail kismindaki hata giderilicek
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    Environment::from_env()
-        .load()
-        .expect("Environment loading failed!");
+    let environment = Environment::from_env();
+    environment.load().expect("Environment loading failed!");
     logger::init();
     info!("Logger initialized!");
-    let db = DB::new().await?;
-    info!("DB initialized!");
+    match Config::get().storage {
+        Storage::Postgres => {
+            let db = DB::new(&environment).await?;
+            info!("DB initialized!");
+            serve(Repositories::postgres(&db)).await
+        }
+        Storage::Memory => {
+            info!("Using in-memory storage, data is lost on shutdown!");
+            serve(Repositories::memory()).await
+        }
+    }
+}
+
+async fn serve<U, C, S, M, I, K, L>(
+    repositories: Repositories<U, C, S, M, I, K, L>,
+) -> Result<(), Error>
+where
+    U: UserRepo + 'static,
+    C: CompanyRepo + 'static,
+    S: SessionRepo + 'static,
+    M: MfaRepo + 'static,
+    I: IdentityRepo + 'static,
+    K: ApiKeyRepo + 'static,
+    L: AuditRepo + 'static,
+{
     let cache = connect_redis().await;
     info!("Redis initialized");
-    let user_repository = Arc::new(UserRepository::new(Arc::clone(&db.pool)));
-    // let company_repository = CompanyRepository::new(Arc::clone(&db.pool));
-    let user_service = Arc::new(UserService::new(Arc::clone(&user_repository)));
+    let user_cache: Arc<dyn CacheRepo> = match &cache {
+        Some(pool) => Arc::new(RedisCacheRepository::new(pool.clone())),
+        None => Arc::new(CacheRepository::new()),
+    };
+    let user_repository = Arc::new(CachedUserRepository::new(
+        repositories.user,
+        user_cache,
+        Config::get().cache.user_ttl,
+        &cache_key(Config::get().cache.key.as_deref())?,
+    ));
+    let mfa_repository = repositories.mfa;
+    let user_service = Arc::new(UserService::new(
+        Arc::clone(&user_repository),
+        Arc::clone(&mfa_repository),
+    ));
+    let login_throttle: Arc<dyn LoginThrottling> = match &cache {
+        Some(pool) => Arc::new(LoginThrottleService::new(Arc::new(
+            RedisAttemptRepository::new(pool.clone()),
+        ))),
+        None => Arc::new(LoginThrottleService::new(
+            Arc::new(AttemptRepository::new()),
+        )),
+    };
+    let admin_service = Arc::new(AdminService::new(
+        Arc::clone(&user_repository),
+        Arc::clone(&login_throttle),
+    ));
+    let session_service = Arc::new(SessionService::new(
+        repositories.session,
+        Arc::clone(&user_repository),
+    ));
+    let mfa_service = Arc::new(MfaService::new(Arc::clone(&mfa_repository)));
+    let identity_repository = repositories.identity;
+    let oidc_client = Arc::new(OidcClient::new(&OAuth2::from_initializers(
+        Config::get().initializers.as_ref(),
+    )?)?);
+    let identity_service: Arc<dyn IdentityManagement> = match &cache {
+        Some(pool) => Arc::new(IdentityService::new(
+            identity_repository,
+            Arc::new(RedisAuthorizationRepository::new(pool.clone())),
+            Arc::clone(&user_repository),
+            Arc::clone(&mfa_repository),
+            oidc_client,
+        )),
+        None => Arc::new(IdentityService::new(
+            identity_repository,
+            Arc::new(AuthorizationRepository::new()),
+            Arc::clone(&user_repository),
+            Arc::clone(&mfa_repository),
+            oidc_client,
+        )),
+    };
+    let api_key_service = Arc::new(ApiKeyService::new(
+        repositories.api_key,
+        Arc::clone(&user_repository),
+    ));
+    let audit_log = Arc::new(AuditService::new(repositories.audit));
+    let retention = &Config::get().retention;
+    spawn_purge_job(
+        Arc::new(PurgeService::new(
+            Arc::clone(&user_repository),
+            repositories.company,
+            audit_log.clone(),
+            retention.deleted_retention,
+        )),
+        Duration::from_secs(retention.purge_interval),
+    );
     let mailer = EmailSender::new();
     let task_context = TaskContext::new(cache, mailer);
-    let app_state = Arc::new(AppState::new(user_service, task_context));
+    let app_state = Arc::new(AppState::new(
+        user_service,
+        repositories.company_service,
+        admin_service,
+        session_service,
+        mfa_service,
+        identity_service,
+        api_key_service,
+        audit_log,
+        login_throttle,
+        user_repository.counters(),
+        task_context,
+    ));
     let route = make_router(app_state);
-    Server::bind().serve(route.into_make_service()).await?;
+    Server::bind()
+        .serve(route.into_make_service_with_connect_info::<SocketAddr>())
+        .await?;
     Ok(())
 }
 
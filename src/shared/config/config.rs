@@ -23,10 +23,16 @@ pub struct Config {
     pub logger: Logger,
     pub server: Server,
     pub database: Database,
+    #[serde(default)]
+    pub storage: Storage,
     pub queue: Option<Redis>,
     pub auth: Auth,
     #[serde(default)]
     pub workers: Workers,
+    #[serde(default)]
+    pub retention: Retention,
+    #[serde(default)]
+    pub cache: Cache,
     pub mailer: Option<Mailer>,
     pub initializers: Option<Initializers>,
 
@@ -130,8 +136,9 @@ pub struct LoggerFileAppender {
 
 /// Database configuration
 ///
-/// Configures the [SeaORM](https://www.sea-ql.org/SeaORM/) connection and pool, as well as Loco's additional DB
-/// management utils such as `auto_migrate`, `truncate` and `recreate`.
+/// Configures the `SQLx` connection pool, as well as the DB management utils
+/// `auto_migrate`, `truncate` and `recreate`. Truncating and recreating are
+/// refused in `production`. Timeouts are in milliseconds.
 ///
 /// Example (development):
 /// ```yaml
@@ -164,13 +171,14 @@ pub struct Database {
     /// Maximum number of connections for a pool
     pub max_connections: u32,
 
-    /// Set the timeout duration when acquiring a connection
+    /// Set the timeout for connecting to the database on startup
     pub connect_timeout: u64,
 
     /// Set the idle duration before closing a connection
     pub idle_timeout: u64,
 
-    /// Set the timeout for acquiring a connection
+    /// Set the timeout for acquiring a connection from the pool, 30 seconds
+    /// when not set
     pub acquire_timeout: Option<u64>,
 
     /// Run migration up when application loads. It is recommended to turn it on
@@ -180,7 +188,7 @@ pub struct Database {
     pub auto_migrate: bool,
 
     /// Truncate database when application loads. It will delete data from your
-    /// tables. Commonly used in `test`.
+    /// tables, but the append-only `audit_log`. Commonly used in `test`.
     #[serde(default)]
     pub dangerously_truncate: bool,
 
@@ -218,31 +226,277 @@ pub struct Redis {
 /// # config/development.yaml
 /// auth:
 ///   jwt:
+///     location:
+///       - from: Bearer
+///       - from: Cookie
+///         name: token
+///     algorithm: HS512 # HS256 | HS512 | RS256
 ///     secret: <your secret>
-///     expiration: 604800 # 7 days
+///     expiration: 900 # 15 minutes
+///     refresh_expiration: 2592000 # 30 days
+/// ```
+///
+/// With `RS256`, tokens are signed with a local RSA key instead of `secret`:
+/// ```yaml
+/// auth:
+///   jwt:
+///     algorithm: RS256
+///     private_key: config/keys/jwt.pem
+///     public_key: config/keys/jwt.pub.pem
+///     expiration: 900
+/// ```
+///
+/// To rotate keys without logging everyone out, list them under `keys`. The
+/// `active_kid` key signs new tokens, the others only verify tokens that were
+/// signed before the rotation. Remove a key, or mark it `retired`, once those
+/// tokens have expired:
+/// ```yaml
+/// auth:
+///   jwt:
+///     algorithm: HS512
+///     active_kid: "2024-10"
+///     keys:
+///       - kid: "2024-09"
+///         secret: <previous secret>
+///       - kid: "2024-10"
+///         secret: <current secret>
+///     expiration: 900
 /// ```
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Auth {
     /// JWT authentication config
     pub jwt: JWT,
     pub password: Password,
+    /// Brute-force protection for logins
+    #[serde(default)]
+    pub lockout: Lockout,
+}
+
+/// Login lockout configuration. Failed logins are counted per account and per
+/// IP; once a counter reaches its limit, further logins are refused for
+/// `base_duration` seconds, doubling with every extra failure up to
+/// `max_duration`.
+///
+/// Counters live in Redis when `queue` is configured, in memory otherwise.
+///
+/// Example (development):
+/// ```yaml
+/// # config/development.yaml
+/// auth:
+///   lockout:
+///     max_attempts: 5
+///     ip_max_attempts: 20
+///     base_duration: 60
+///     max_duration: 3600
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Lockout {
+    /// Failed logins per account before it is locked
+    pub max_attempts: u32,
+    /// Failed logins per IP before it is locked
+    pub ip_max_attempts: u32,
+    /// First lockout duration in seconds
+    pub base_duration: u64,
+    /// Longest lockout duration in seconds. Failure counters are also
+    /// forgotten after this long without failures.
+    pub max_duration: u64,
+}
+
+impl Default for Lockout {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            ip_max_attempts: 20,
+            base_duration: 60,
+            max_duration: 3600,
+        }
+    }
 }
 
 /// JWT configuration structure.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct JWT {
     /// The location where JWT tokens are expected to be found during
-    /// authentication.
-    pub location: Option<JWTLocation>,
-    /// The secret key For JWT token
+    /// authentication. Either a single location or a list, tried in order.
+    /// Defaults to the `Bearer` header, then the `token` cookie.
+    pub location: Option<JWTLocationConfig>,
+    /// Signing algorithm
+    ///
+    /// * options: `HS256` | `HS512` | `RS256`
+    #[serde(default)]
+    pub algorithm: JWTAlgorithm,
+    /// The secret key For JWT token, used by the `HS*` algorithms
+    #[serde(default)]
     pub secret: String,
-    /// The expiration time sec for authentication tokens
+    /// Path to the PEM encoded RSA private key, used by `RS256` to sign
+    pub private_key: Option<String>,
+    /// Path to the PEM encoded RSA public key, used by `RS256` to verify
+    pub public_key: Option<String>,
+    /// Keyring for key rotation. When set, `secret`, `private_key` and
+    /// `public_key` above are ignored.
+    #[serde(default)]
+    pub keys: Vec<JWTKey>,
+    /// `kid` of the key that signs new tokens. Defaults to the last key that
+    /// is not retired.
+    pub active_kid: Option<String>,
+    /// The expiration time sec for access tokens. Keep it short: access
+    /// tokens are not checked against their session.
     pub expiration: u64,
+    /// The expiration time sec for refresh tokens, extended on every refresh
+    #[serde(default = "default_refresh_expiration")]
+    pub refresh_expiration: u64,
+}
+
+fn default_refresh_expiration() -> u64 {
+    2_592_000 // 30 days
 }
 
+/// A single signing key of the JWT keyring.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct JWTKey {
+    /// Key id, written to the `kid` header of the tokens it signs
+    pub kid: String,
+    /// Signing algorithm of this key. Defaults to `auth.jwt.algorithm`
+    pub algorithm: Option<JWTAlgorithm>,
+    /// The secret key, used by the `HS*` algorithms
+    #[serde(default)]
+    pub secret: String,
+    /// Path to the PEM encoded RSA private key, used by `RS256` to sign
+    pub private_key: Option<String>,
+    /// Path to the PEM encoded RSA public key, used by `RS256` to verify
+    pub public_key: Option<String>,
+    /// Retired keys neither sign nor verify tokens
+    #[serde(default)]
+    pub retired: bool,
+}
+
+/// Algorithms accepted for signing and verifying JWTs.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum JWTAlgorithm {
+    HS256,
+    #[default]
+    HS512,
+    RS256,
+}
+
+impl JWT {
+    /// `kid` given to the key built from `secret`, `private_key` and
+    /// `public_key` when no `keys` are configured.
+    pub const DEFAULT_KID: &'static str = "default";
+
+    /// Configured keys that are not retired. Without `keys`, the single key
+    /// from `secret`, `private_key` and `public_key`.
+    #[must_use]
+    pub fn keyring(&self) -> Vec<JWTKey> {
+        if self.keys.is_empty() {
+            return vec![JWTKey {
+                kid: Self::DEFAULT_KID.to_string(),
+                algorithm: Some(self.algorithm),
+                secret: self.secret.clone(),
+                private_key: self.private_key.clone(),
+                public_key: self.public_key.clone(),
+                retired: false,
+            }];
+        }
+
+        self.keys
+            .iter()
+            .filter(|key| !key.retired)
+            .cloned()
+            .collect()
+    }
+
+    /// Configured token locations, in priority order.
+    #[must_use]
+    pub fn locations(&self) -> Vec<JWTLocation> {
+        match &self.location {
+            Some(JWTLocationConfig::Single(location)) => vec![location.clone()],
+            Some(JWTLocationConfig::Multiple(locations)) => locations.clone(),
+            None => vec![
+                JWTLocation::Bearer,
+                JWTLocation::Cookie {
+                    name: DEFAULT_TOKEN_COOKIE.to_string(),
+                },
+            ],
+        }
+    }
+
+    /// Whether clients send tokens themselves, in the `Authorization` header
+    /// or a query parameter, and so need them in the response body.
+    #[must_use]
+    pub fn has_header_location(&self) -> bool {
+        self.locations()
+            .iter()
+            .any(|location| !matches!(location, JWTLocation::Cookie { .. }))
+    }
+
+    /// Name of the cookie tokens are issued in, if cookies are a configured
+    /// location.
+    #[must_use]
+    pub fn cookie_name(&self) -> Option<String> {
+        self.locations()
+            .into_iter()
+            .find_map(|location| match location {
+                JWTLocation::Cookie { name } => Some(name),
+                _ => None,
+            })
+    }
+}
+
+/// Name of the token cookie when `auth.jwt.location` is not set.
+pub const DEFAULT_TOKEN_COOKIE: &str = "token";
+
+/// Name of the refresh token cookie, set when tokens are issued in cookies.
+pub const REFRESH_TOKEN_COOKIE: &str = "refresh_token";
+
+/// One or several [`JWTLocation`]s.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum JWTLocationConfig {
+    Single(JWTLocation),
+    Multiple(Vec<JWTLocation>),
+}
+
+/// Password hashing configuration. Hashes are Argon2id with a random salt per
+/// password; stored hashes made with other parameters are rehashed on the
+/// next successful login.
+///
+/// Example (development):
+/// ```yaml
+/// # config/development.yaml
+/// auth:
+///   password:
+///     secret: <your secret>
+///     memory_size: 4096 # KiB
+///     iterations: 192
+///     parallelism: 1
+/// ```
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Password {
+    /// Secret key mixed into every hash
     pub secret: String,
+    /// Argon2 memory cost in KiB
+    #[serde(default = "default_password_memory_size")]
+    pub memory_size: u32,
+    /// Argon2 time cost, the number of passes over the memory
+    #[serde(default = "default_password_iterations")]
+    pub iterations: u32,
+    /// Argon2 lanes, also the number of threads used to hash
+    #[serde(default = "default_password_parallelism")]
+    pub parallelism: u32,
+}
+
+fn default_password_memory_size() -> u32 {
+    4_096
+}
+
+fn default_password_iterations() -> u32 {
+    192
+}
+
+fn default_password_parallelism() -> u32 {
+    1
 }
 
 /// Defines the authentication mechanism for middleware.
@@ -320,6 +574,84 @@ pub struct Workers {
     pub queues: Option<Vec<String>>,
 }
 
+/// How long deleted users and companies can be restored. A background job
+/// runs every `purge_interval` seconds and removes, for good, those deleted
+/// more than `deleted_retention` seconds ago.
+///
+/// Example (development):
+/// ```yaml
+/// # config/development.yaml
+/// retention:
+///   deleted_retention: 2592000
+///   purge_interval: 3600
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Retention {
+    /// Seconds a deleted row is kept before it is purged
+    pub deleted_retention: u64,
+    /// Seconds between two purges
+    pub purge_interval: u64,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            deleted_retention: 30 * 24 * 3600,
+            purge_interval: 3600,
+        }
+    }
+}
+
+/// How long looked-up users are cached. The cache lives in Redis when
+/// `queue` is configured, in memory otherwise. A `user_ttl` of 0 disables it.
+///
+/// Cached users are encrypted with `key`. Instances sharing a Redis should
+/// share it too; without one each instance generates its own at startup and
+/// only reads back what it cached itself.
+///
+/// Example (development):
+/// ```yaml
+/// # config/development.yaml
+/// cache:
+///   user_ttl: 60
+///   key: {{ get_env(name="CACHE_KEY") }}
+/// ```
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Cache {
+    /// Seconds a user is served from the cache after it was read
+    pub user_ttl: u64,
+    /// 32 bytes encoded in unpadded base64url, encrypting cached users
+    pub key: Option<String>,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            user_ttl: 60,
+            key: None,
+        }
+    }
+}
+
+/// Where the application keeps its data.
+///
+/// Example (demo without Postgres):
+/// ```yaml
+/// storage: memory
+/// ```
+#[derive(Clone, Copy, Default, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Storage {
+    /// The database of the `database` section.
+    #[default]
+    Postgres,
+    /// Process memory. Nothing survives a restart and every instance has its
+    /// own data, so this is meant for tests and demos.
+    Memory,
+}
+
 /// Worker mode configuration
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
 pub enum WorkerMode {
@@ -370,9 +702,12 @@ pub struct SecureHeadersConfig {
     pub overrides: Option<BTreeMap<String, String>>,
 }
 
+/// Which peers may set the client IP with `X-Forwarded-For` or `X-Real-IP`.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RemoteIPConfig {
     pub enable: bool,
+    /// Addresses or `address/prefix` networks. Loopback and private networks
+    /// when omitted.
     pub trusted_proxies: Option<Vec<String>>,
 }
 
@@ -485,8 +820,87 @@ pub struct Mailer {
 ///     - client_identifier: google # Identifier for the `OAuth2` provider.
 ///       Replace 'google' with your provider's name if different, must be
 ///       unique within the oauth2 config. ... # other fields
+/// ```
+/// See [`OAuth2`] for the fields of the `oauth2` block.
 pub type Initializers = BTreeMap<String, serde_json::Value>;
 
+/// `OAuth2` / `OpenID Connect` login, read from `initializers.oauth2`.
+///
+/// Example (development):
+/// ```yaml
+/// # config/development.yaml
+/// initializers:
+///   oauth2:
+///     authorization_code:
+///       - client_identifier: google
+///         client_credentials:
+///           client_id: {{ get_env(name="GOOGLE_CLIENT_ID") }}
+///           client_secret: {{ get_env(name="GOOGLE_CLIENT_SECRET") }}
+///         url_config:
+///           issuer: https://accounts.google.com
+///           redirect_url: http://localhost:5150/api/v1/auth/oauth2/google/callback
+///           scopes: [openid, email, profile]
+///         timeout_seconds: 600
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OAuth2 {
+    /// Providers for the authorization code grant, with PKCE.
+    #[serde(default)]
+    pub authorization_code: Vec<OAuth2Provider>,
+}
+
+impl OAuth2 {
+    /// The `oauth2` initializer, or no providers when it is missing.
+    pub fn from_initializers(initializers: Option<&Initializers>) -> Result<Self, ConfigError> {
+        match initializers.and_then(|initializers| initializers.get("oauth2")) {
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| ConfigError::YamlParseError(e.to_string())),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OAuth2Provider {
+    /// Name of the provider in the login URLs, unique within the config.
+    pub client_identifier: String,
+    pub client_credentials: OAuth2ClientCredentials,
+    pub url_config: OAuth2UrlConfig,
+    /// How long a started login may take, in seconds.
+    #[serde(default = "default_oauth2_timeout")]
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OAuth2ClientCredentials {
+    pub client_id: String,
+    /// Omitted for public clients, which rely on PKCE alone.
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OAuth2UrlConfig {
+    /// Issuer identifier. The endpoints and keys are read from its
+    /// `/.well-known/openid-configuration` document.
+    pub issuer: String,
+    /// Callback URL registered with the provider.
+    pub redirect_url: String,
+    #[serde(default = "default_oauth2_scopes")]
+    pub scopes: Vec<String>,
+}
+
+fn default_oauth2_timeout() -> u64 {
+    600
+}
+
+fn default_oauth2_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "email".to_string(),
+        "profile".to_string(),
+    ]
+}
+
 /// SMTP mailer configuration structure.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SmtpMailer {
@@ -1,28 +1,39 @@
+use crate::core::domain::valueobject::id::UserId;
+use crate::core::domain::valueobject::role::Role;
 use crate::shared::ctx::error::Error;
 use uuid::Uuid;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Who a use case runs for. Built by the auth middleware for every protected
+/// request, or with [`Ctx::root_ctx`] for system jobs.
 #[derive(Clone, Debug)]
 pub struct Ctx {
-    user_id: Uuid,
+    user_id: UserId,
+    role: Role,
+    request_id: Uuid,
     conv_id: Option<i64>,
 }
 
 impl Ctx {
+    /// Context of the system itself, which passes every ownership check.
     pub fn root_ctx() -> Self {
         Ctx {
-            user_id: Uuid::nil(),
+            user_id: UserId::from(Uuid::nil()),
+            role: Role::ADMIN,
+            request_id: Uuid::new_v4(),
             conv_id: None,
         }
     }
 
-    pub fn new(user_id: Uuid) -> Result<Self> {
-        if user_id == Uuid::nil() {
+    pub fn new(user_id: UserId, role: Role, request_id: Uuid) -> Result<Self> {
+        if user_id.as_uuid().is_nil() {
             Err(Error::CtxCannotNewRootCtx)
         } else {
             Ok(Self {
                 user_id,
+                role,
+                request_id,
                 conv_id: None,
             })
         }
@@ -36,11 +47,23 @@ impl Ctx {
 }
 
 impl Ctx {
-    pub fn user_id(&self) -> Uuid {
+    pub fn user_id(&self) -> UserId {
         self.user_id
     }
 
+    pub fn role(&self) -> &Role {
+        &self.role
+    }
+
+    pub fn request_id(&self) -> Uuid {
+        self.request_id
+    }
+
     pub fn conv_id(&self) -> Option<i64> {
         self.conv_id
     }
+
+    pub fn is_root(&self) -> bool {
+        self.user_id.as_uuid().is_nil()
+    }
 }
@@ -1,6 +1,7 @@
 use anyhow::Error;
 use tera::{Context, Tera};
 
+pub mod base32;
 pub mod base64;
 pub mod date;
 